@@ -0,0 +1,140 @@
+use crate::error::FactorionError;
+use std::fs;
+
+/// Exists while an owner paused the bot, so the pause survives restarts.
+pub(crate) const PAUSED_FILE_PATH: &str = "paused";
+
+/// What the owners of the bot asked for with [`AdminCommand`]s.
+#[derive(Debug, Default)]
+pub(crate) struct AdminState {
+    /// Stored in [`PAUSED_FILE_PATH`], see [`Self::set_paused`].
+    pub(crate) paused: bool,
+    /// Reload the config at the start of the next poll, even if the file didn't change.
+    pub(crate) reload_config: bool,
+}
+
+impl AdminState {
+    /// Starts paused if an owner paused the bot before it was restarted.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        let paused = match fs::metadata(PAUSED_FILE_PATH) {
+            Ok(_) => true,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(source) => {
+                return Err(FactorionError::Io {
+                    path: PAUSED_FILE_PATH,
+                    source,
+                })
+            }
+        };
+        Ok(Self {
+            paused,
+            reload_config: false,
+        })
+    }
+
+    /// Pauses or resumes the bot, creating or removing [`PAUSED_FILE_PATH`].
+    pub(crate) fn set_paused(&mut self, paused: bool) -> Result<(), FactorionError> {
+        self.paused = paused;
+        let result = if paused {
+            fs::write(PAUSED_FILE_PATH, "")
+        } else {
+            fs::remove_file(PAUSED_FILE_PATH).or_else(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => Ok(()),
+                _ => Err(e),
+            })
+        };
+        result.map_err(|source| FactorionError::Io {
+            path: PAUSED_FILE_PATH,
+            source,
+        })
+    }
+}
+
+/// A command the owner of the bot sends it in a private message, like `disable r/mathmemes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AdminCommand {
+    /// Stop answering anything, until `resume`.
+    Pause,
+    Resume,
+    /// Reply with what the bot is doing.
+    Status,
+    /// Stop answering in a subreddit, like a moderator writing `!factorion pause`.
+    Disable(String),
+    Enable(String),
+    /// Load `config.toml` again right away.
+    ReloadConfig,
+}
+
+impl AdminCommand {
+    /// Parses the body of a message, failing with the reply explaining what is understood.
+    /// Subreddit names are returned in lowercase.
+    pub(crate) fn parse(body: &str) -> Result<Self, String> {
+        let body = body.trim().to_lowercase();
+        let mut words = body.split_whitespace();
+        let command = match (words.next(), words.next()) {
+            (Some("pause"), None) => AdminCommand::Pause,
+            (Some("resume"), None) => AdminCommand::Resume,
+            (Some("status"), None) => AdminCommand::Status,
+            (Some("reload-config"), None) => AdminCommand::ReloadConfig,
+            (Some("disable"), Some(subreddit)) => {
+                AdminCommand::Disable(parse_subreddit(subreddit)?)
+            }
+            (Some("enable"), Some(subreddit)) => AdminCommand::Enable(parse_subreddit(subreddit)?),
+            _ => return Err(Self::usage(&body)),
+        };
+        if words.next().is_some() {
+            return Err(Self::usage(&body));
+        }
+        Ok(command)
+    }
+
+    fn usage(body: &str) -> String {
+        format!(
+            "I don't understand `{body}`. I know `pause`, `resume`, `status`, `disable r/subreddit`, `enable r/subreddit` and `reload-config`."
+        )
+    }
+}
+
+fn parse_subreddit(subreddit: &str) -> Result<String, String> {
+    let name = subreddit
+        .trim_start_matches('/')
+        .strip_prefix("r/")
+        .unwrap_or(subreddit);
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("`{subreddit}` is no subreddit."));
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_admin_command() {
+        assert_eq!(AdminCommand::parse(" Pause\n"), Ok(AdminCommand::Pause));
+        assert_eq!(AdminCommand::parse("resume"), Ok(AdminCommand::Resume));
+        assert_eq!(AdminCommand::parse("status"), Ok(AdminCommand::Status));
+        assert_eq!(
+            AdminCommand::parse("reload-config"),
+            Ok(AdminCommand::ReloadConfig)
+        );
+        assert_eq!(
+            AdminCommand::parse("disable r/MathMemes"),
+            Ok(AdminCommand::Disable("mathmemes".to_string()))
+        );
+        assert_eq!(
+            AdminCommand::parse("enable /r/askmath"),
+            Ok(AdminCommand::Enable("askmath".to_string()))
+        );
+        assert_eq!(
+            AdminCommand::parse("enable askmath"),
+            Ok(AdminCommand::Enable("askmath".to_string()))
+        );
+        assert!(AdminCommand::parse("disable").is_err());
+        assert!(AdminCommand::parse("disable r/").is_err());
+        assert!(AdminCommand::parse("disable r/a+b").is_err());
+        assert!(AdminCommand::parse("pause now").is_err());
+        assert!(AdminCommand::parse("hello").is_err());
+    }
+}
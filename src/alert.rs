@@ -0,0 +1,188 @@
+use crate::error::FactorionError;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// How many errors of the same kind within `ALERT_WINDOW` it takes to send an alert.
+const FAILURES_BEFORE_ALERT: u32 = 3;
+/// In seconds, also the least time between two alerts about the same kind of error.
+const ALERT_WINDOW: u64 = 60 * 60;
+/// Alerts are sent from the main loop, which shouldn't hang on a slow webhook.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The errors logged so far, see [`FactorionError::log`].
+static ALERTS: LazyLock<Mutex<Alerts>> = LazyLock::new(|| Mutex::new(Alerts::default()));
+
+/// Counts errors by their kind, and writes an alert once the same kind keeps happening,
+/// like reddit being down or every reply being rejected.
+#[derive(Debug, Default)]
+pub(crate) struct Alerts {
+    /// When the current streak of every kind started, in unix time, and how many errors it has.
+    failures: HashMap<&'static str, (u64, u32)>,
+    last_alert: HashMap<&'static str, u64>,
+    pending: Vec<String>,
+}
+
+impl Alerts {
+    fn record(&mut self, kind: &'static str, message: String, now: u64) {
+        let (since, count) = self.failures.entry(kind).or_insert((now, 0));
+        if now >= *since + ALERT_WINDOW {
+            *since = now;
+            *count = 0;
+        }
+        *count += 1;
+        if *count < FAILURES_BEFORE_ALERT {
+            return;
+        }
+        let count = std::mem::take(count);
+        *since = now;
+        let alerted_lately = self
+            .last_alert
+            .get(kind)
+            .is_some_and(|last_alert| now < last_alert + ALERT_WINDOW);
+        if !alerted_lately {
+            self.last_alert.insert(kind, now);
+            self.pending
+                .push(format!("{message} ({count} {kind} errors within an hour)"));
+        }
+    }
+}
+
+/// Counts an error, for [`take_pending`] to alert about once it keeps happening.
+pub(crate) fn record(error: &FactorionError, context: &str) {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    ALERTS.lock().expect("Alerts lock poisoned").record(
+        error.kind(),
+        format!("{context}: {error}"),
+        now,
+    );
+}
+
+/// The alerts that weren't sent yet.
+pub(crate) fn take_pending() -> Vec<String> {
+    std::mem::take(&mut ALERTS.lock().expect("Alerts lock poisoned").pending)
+}
+
+/// A Discord or Slack webhook the alerts are posted to, set with `ALERT_WEBHOOK`.
+#[derive(Debug, Clone)]
+pub(crate) struct Webhook {
+    client: Client,
+    url: String,
+}
+
+impl Webhook {
+    /// No webhook without a URL.
+    pub(crate) fn new(url: &str) -> Result<Option<Self>, FactorionError> {
+        let url = url.trim();
+        if url.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            client: Client::builder().timeout(WEBHOOK_TIMEOUT).build()?,
+            url: url.to_string(),
+        }))
+    }
+
+    /// Slack takes the message as `text`, Discord as `content`.
+    fn payload(&self, message: &str) -> Value {
+        if self.url.contains("hooks.slack.com") {
+            json!({ "text": message })
+        } else {
+            json!({ "content": message })
+        }
+    }
+
+    pub(crate) async fn send(&self, message: &str) -> Result<(), FactorionError> {
+        self.client
+            .post(&self.url)
+            .json(&self.payload(message))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Posts every alert collected since the last time.
+    /// Failing to do so is only printed, so it doesn't cause another alert.
+    pub(crate) async fn send_pending(&self) {
+        for alert in take_pending() {
+            if let Err(e) = self.send(&alert).await {
+                eprintln!("Failed to send alert: {e}");
+            }
+        }
+    }
+
+    /// Also alerts when the bot panics, before it exits.
+    pub(crate) fn alert_on_panic(&self) {
+        let url = self.url.clone();
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            default_hook(info);
+            let message = format!("factorion-bot panicked: {info}");
+            let url = url.clone();
+            // The panic may happen inside the async runtime, which can't be blocked on there
+            let sent = std::thread::spawn(move || {
+                let runtime = tokio::runtime::Runtime::new().ok()?;
+                let webhook = Webhook::new(&url).ok()??;
+                runtime.block_on(webhook.send(&message)).ok()
+            })
+            .join();
+            if !matches!(sent, Ok(Some(()))) {
+                eprintln!("Failed to send alert about the panic");
+            }
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alerts() {
+        let mut alerts = Alerts::default();
+        alerts.record("http", "Failed to get comments".to_string(), 0);
+        alerts.record("http", "Failed to get comments".to_string(), 10);
+        alerts.record("io", "Failed to store".to_string(), 10);
+        assert!(alerts.pending.is_empty());
+        alerts.record("http", "Failed to get mentions".to_string(), 20);
+        assert_eq!(
+            alerts.pending,
+            vec!["Failed to get mentions (3 http errors within an hour)"]
+        );
+
+        // Not again within the hour
+        for now in 30..33 {
+            alerts.record("http", "Failed to get comments".to_string(), now);
+        }
+        assert_eq!(alerts.pending.len(), 1);
+
+        // Errors further apart than an hour are no streak
+        for now in [0, 1, ALERT_WINDOW + 1] {
+            alerts.record("upload", "Failed to upload".to_string(), now);
+        }
+        assert_eq!(alerts.pending.len(), 1);
+
+        for now in [2 * ALERT_WINDOW, 2 * ALERT_WINDOW + 1, 2 * ALERT_WINDOW + 2] {
+            alerts.record("http", "Failed to get comments".to_string(), now);
+        }
+        assert_eq!(alerts.pending.len(), 2);
+    }
+
+    #[test]
+    fn test_webhook_payload() {
+        assert!(Webhook::new(" ").unwrap().is_none());
+        let slack = Webhook::new("https://hooks.slack.com/services/T0/B0/x")
+            .unwrap()
+            .unwrap();
+        assert_eq!(slack.payload("down"), json!({"text": "down"}));
+        let discord = Webhook::new("https://discord.com/api/webhooks/1/x")
+            .unwrap()
+            .unwrap();
+        assert_eq!(discord.payload("down"), json!({"content": "down"}));
+    }
+}
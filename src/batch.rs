@@ -0,0 +1,92 @@
+use crate::moderation::ModRequest;
+use crate::reddit_comment::{RedditComment, Status};
+use std::collections::HashMap;
+
+/// Merges the comments someone wrote with calculations in the same thread into one,
+/// so they get a single reply to the latest of them instead of one reply per comment.
+/// Expects the comments newest first, like reddit lists them.
+/// Comments that wouldn't be answered anyway, and requests to moderate the bot, are left alone.
+pub(crate) fn batch_by_author(comments: Vec<RedditComment>) -> Vec<RedditComment> {
+    let mut batched: Vec<RedditComment> = Vec::with_capacity(comments.len());
+    // Where the latest comment of an author in a thread ended up in `batched`
+    let mut latest: HashMap<(String, String), usize> = HashMap::new();
+    for comment in comments {
+        if !is_batchable(&comment) {
+            batched.push(comment);
+            continue;
+        }
+        let key = (comment.thread.clone(), comment.author.to_lowercase());
+        match latest.get(&key) {
+            Some(&index) => batched[index].merge(comment),
+            None => {
+                latest.insert(key, batched.len());
+                batched.push(comment);
+            }
+        }
+    }
+    batched
+}
+
+fn is_batchable(comment: &RedditComment) -> bool {
+    !comment.thread.is_empty()
+        && !comment.author.is_empty()
+        && comment.status.contains(&Status::FactorialsFound)
+        && comment.status.contains(&Status::NotReplied)
+        && !comment.status.contains(&Status::NumberTooBig)
+        && !comment.status.contains(&Status::ThreadLocked)
+        && ModRequest::parse(&comment.parsed_body, &comment.subreddit).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(body: &str, id: &str, author: &str, thread: &str) -> RedditComment {
+        let mut comment = RedditComment::new(body, id);
+        comment.author = author.to_string();
+        comment.thread = thread.to_string();
+        comment.subreddit = "mathmemes".to_string();
+        comment.add_status(Status::NotReplied);
+        comment
+    }
+
+    #[test]
+    fn test_batch_by_author() {
+        let batched = batch_by_author(vec![
+            comment("And 6!", "c4", "someone", "t3_a"),
+            comment("7!", "c3", "someone_else", "t3_a"),
+            comment("5! in another thread", "c2", "someone", "t3_b"),
+            comment("I meant 5!", "c1", "Someone", "t3_a"),
+            comment("No factorial here", "c0", "someone", "t3_a"),
+        ]);
+        let ids: Vec<_> = batched.iter().map(|comment| comment.id.as_str()).collect();
+        assert_eq!(ids, vec!["c4", "c3", "c2", "c0"]);
+
+        let merged = &batched[0];
+        assert_eq!(merged.merged_ids, vec!["c1"]);
+        assert_eq!(merged.sources(), vec!["5!", "6!"]);
+        assert_eq!(
+            merged.get_reply(),
+            "Factorial of 5 is 120 \n\nFactorial of 6 is 720 \n\n\
+            \n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+        assert!(batched[1].merged_ids.is_empty());
+    }
+
+    #[test]
+    fn test_batch_by_author_skips_unanswered() {
+        let mut replied = comment("5!", "c1", "someone", "t3_a");
+        replied
+            .status
+            .retain(|status| *status != Status::NotReplied);
+        replied.add_status(Status::AlreadyReplied);
+        let batched = batch_by_author(vec![
+            comment("6!", "c3", "someone", "t3_a"),
+            comment("!factorion pause 4!", "c2", "someone", "t3_a"),
+            replied,
+            comment("7!", "c0", "someone", ""),
+        ]);
+        assert_eq!(batched.len(), 4);
+        assert!(batched[0].merged_ids.is_empty());
+    }
+}
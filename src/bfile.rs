@@ -0,0 +1,130 @@
+use crate::error::FactorionError;
+use crate::math;
+use crate::reddit_comment::{
+    UPPER_ALTERNATING_FACTORIAL_LIMIT, UPPER_CALCULATION_LIMIT, UPPER_EXPONENTIAL_FACTORIAL_LIMIT,
+    UPPER_HYPERFACTORIAL_LIMIT, UPPER_LEFT_FACTORIAL_LIMIT, UPPER_SUPERFACTORIAL_LIMIT,
+};
+use num_bigint::BigInt;
+
+/// An operation by name, how to calculate it and the largest index that is still calculated.
+type Operation = (&'static str, fn(u64) -> BigInt, u64);
+
+/// The operations a b-file can be exported for.
+const OPERATIONS: [Operation; 8] = [
+    (
+        "factorial",
+        |n| math::factorial(n, 1),
+        UPPER_CALCULATION_LIMIT as u64,
+    ),
+    (
+        "double-factorial",
+        |n| math::factorial(n, 2),
+        UPPER_CALCULATION_LIMIT as u64,
+    ),
+    ("primorial", math::primorial, UPPER_CALCULATION_LIMIT as u64),
+    (
+        "hyperfactorial",
+        math::hyperfactorial,
+        UPPER_HYPERFACTORIAL_LIMIT,
+    ),
+    (
+        "superfactorial",
+        math::superfactorial,
+        UPPER_SUPERFACTORIAL_LIMIT,
+    ),
+    (
+        "left-factorial",
+        math::left_factorial,
+        UPPER_LEFT_FACTORIAL_LIMIT,
+    ),
+    (
+        "alternating-factorial",
+        math::alternating_factorial,
+        UPPER_ALTERNATING_FACTORIAL_LIMIT,
+    ),
+    (
+        "exponential-factorial",
+        math::exponential_factorial,
+        UPPER_EXPONENTIAL_FACTORIAL_LIMIT,
+    ),
+];
+
+/// Writes the values of an operation for every index from `start` to `end` in the OEIS b-file format,
+/// one `index value` pair per line.
+pub(crate) fn b_file(operation: &str, start: u64, end: u64) -> Result<String, FactorionError> {
+    let (_, calculate, limit) = OPERATIONS
+        .iter()
+        .find(|(name, _, _)| *name == operation)
+        .ok_or_else(|| {
+            FactorionError::Usage(format!(
+                "Unknown operation {operation}, expected one of: {}",
+                OPERATIONS.map(|(name, _, _)| name).join(", ")
+            ))
+        })?;
+    if start > end {
+        return Err(FactorionError::Usage(format!(
+            "Start {start} is above the end {end}"
+        )));
+    }
+    if end > *limit {
+        return Err(FactorionError::Usage(format!(
+            "End {end} is above the limit {limit} of {operation}"
+        )));
+    }
+
+    Ok((start..=end)
+        .map(|n| format!("{n} {}\n", calculate(n)))
+        .collect())
+}
+
+/// Prints the b-file for the arguments of the `b-file` command: operation, start and end.
+pub(crate) fn print_b_file(args: &[String]) -> Result<(), FactorionError> {
+    let [operation, start, end] = args else {
+        return Err(FactorionError::Usage(
+            "Expected: b-file <operation> <start> <end>".to_string(),
+        ));
+    };
+    let parse_index = |index: &String| {
+        index
+            .parse::<u64>()
+            .map_err(|_| FactorionError::Usage(format!("Invalid index: {index}")))
+    };
+    print!(
+        "{}",
+        b_file(operation, parse_index(start)?, parse_index(end)?)?
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_b_file() {
+        assert_eq!(
+            b_file("factorial", 0, 5).unwrap(),
+            "0 1\n1 1\n2 2\n3 6\n4 24\n5 120\n"
+        );
+        assert_eq!(b_file("primorial", 5, 7).unwrap(), "5 30\n6 30\n7 210\n");
+        assert_eq!(
+            b_file("exponential-factorial", 1, 4).unwrap(),
+            "1 1\n2 2\n3 9\n4 262144\n"
+        );
+    }
+
+    #[test]
+    fn test_b_file_invalid_arguments() {
+        assert!(b_file("fibonacci", 0, 5).is_err());
+        assert!(b_file("factorial", 5, 0).is_err());
+        assert!(b_file("exponential-factorial", 0, 6).is_err());
+        assert!(b_file("hyperfactorial", 20000, 20000).is_err());
+        assert!(b_file("superfactorial", 501, 501).is_err());
+        assert!(b_file("left-factorial", 10_001, 10_001).is_err());
+        assert!(b_file("alternating-factorial", 10_001, 10_001).is_err());
+        assert!(
+            print_b_file(&["factorial".to_string(), "x".to_string(), "5".to_string()]).is_err()
+        );
+        assert!(print_b_file(&["factorial".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::FactorionError;
+use crate::reddit_comment::RedditComment;
+use fancy_regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+pub(crate) const BOT_STATS_FILE_PATH: &str = "bot_stats.json";
+
+/// Totals about the replies of the bot, for users asking with `!stats` in a mention.
+/// Stored as JSON, so they keep adding up across restarts.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct BotStats {
+    replies: u64,
+    /// The biggest number of which the factorial was calculated for a reply.
+    largest_factorial: Option<u64>,
+    /// Replies per lowercase subreddit name.
+    subreddits: BTreeMap<String, u64>,
+}
+
+impl BotStats {
+    /// Reads the stats, starting from zero if there is no file yet.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        match fs::read_to_string(BOT_STATS_FILE_PATH) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(FactorionError::Io {
+                path: BOT_STATS_FILE_PATH,
+                source,
+            }),
+        }
+    }
+
+    pub(crate) fn store(&self) -> Result<(), FactorionError> {
+        fs::write(BOT_STATS_FILE_PATH, serde_json::to_string(self)?).map_err(|source| {
+            FactorionError::Io {
+                path: BOT_STATS_FILE_PATH,
+                source,
+            }
+        })
+    }
+
+    /// Counts a reply to the comment.
+    pub(crate) fn record(&mut self, comment: &RedditComment) {
+        self.replies += 1;
+        if !comment.subreddit.is_empty() {
+            *self
+                .subreddits
+                .entry(comment.subreddit.to_lowercase())
+                .or_default() += 1;
+        }
+        let largest = comment
+            .factorial_list
+            .iter()
+            .filter(|factorial| factorial.level == 1)
+            .map(|factorial| factorial.number)
+            .max();
+        self.largest_factorial = self.largest_factorial.max(largest);
+    }
+
+    /// Whether a comment asks for the stats, with `!stats`.
+    pub(crate) fn is_request(body: &str) -> bool {
+        let request_regex = Regex::new(r"(?i)!stats\b").expect("Invalid stats regex");
+        request_regex.is_match(body).unwrap_or(false)
+    }
+
+    /// The reply to a `!stats` request, without footer.
+    pub(crate) fn reply(&self) -> String {
+        let mut reply = format!("I replied to {} comments so far.", self.replies);
+        if let Some(largest) = self.largest_factorial {
+            reply.push_str(&format!(
+                " The largest factorial I calculated is {largest}!."
+            ));
+        }
+        let most_active = self
+            .subreddits
+            .iter()
+            .max_by_key(|(subreddit, replies)| (**replies, std::cmp::Reverse(*subreddit)));
+        if let Some((subreddit, replies)) = most_active {
+            reply.push_str(&format!(
+                " I'm most active in r/{subreddit}, with {replies} replies."
+            ));
+        }
+        reply
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(body: &str, subreddit: &str) -> RedditComment {
+        let mut comment = RedditComment::new(body, "abc");
+        comment.subreddit = subreddit.to_string();
+        comment
+    }
+
+    #[test]
+    fn test_bot_stats() {
+        let mut stats = BotStats::default();
+        assert_eq!(stats.reply(), "I replied to 0 comments so far.");
+
+        stats.record(&comment("5! and 20!", "mathmemes"));
+        stats.record(&comment("7!! and 10!", "AskMath"));
+        stats.record(&comment("3!", "askmath"));
+        assert_eq!(
+            stats.reply(),
+            "I replied to 3 comments so far. The largest factorial I calculated is 20!. \
+            I'm most active in r/askmath, with 2 replies."
+        );
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert_eq!(serde_json::from_str::<BotStats>(&json).unwrap(), stats);
+    }
+
+    #[test]
+    fn test_is_request() {
+        assert!(BotStats::is_request("u/factorion-bot !stats"));
+        assert!(BotStats::is_request("!STATS please"));
+        assert!(!BotStats::is_request("!statistics"));
+        assert!(!BotStats::is_request("stats"));
+    }
+}
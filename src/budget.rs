@@ -0,0 +1,103 @@
+use crate::error::FactorionError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+pub(crate) const REPLY_BUDGET_FILE_PATH: &str = "reply_budget.json";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// How many replies every user got today, keyed by their lowercase name,
+/// so a single user spamming factorials can't use up the whole rate limit.
+/// The counts start over at midnight UTC, and are stored as JSON, so restarts don't reset them.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ReplyBudget {
+    /// The day the counts are for, in days since the unix epoch.
+    day: u64,
+    replies: HashMap<String, u32>,
+}
+
+impl ReplyBudget {
+    /// Reads the counts, starting without any if there is no file yet.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        match fs::read_to_string(REPLY_BUDGET_FILE_PATH) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(FactorionError::Io {
+                path: REPLY_BUDGET_FILE_PATH,
+                source,
+            }),
+        }
+    }
+
+    pub(crate) fn store(&self) -> Result<(), FactorionError> {
+        fs::write(REPLY_BUDGET_FILE_PATH, serde_json::to_string(self)?).map_err(|source| {
+            FactorionError::Io {
+                path: REPLY_BUDGET_FILE_PATH,
+                source,
+            }
+        })
+    }
+
+    /// Whether the user may get another reply today, with at most `limit` per day (0 for no limit).
+    /// Users whose name is unknown are never limited.
+    pub(crate) fn allows(&mut self, author: &str, limit: u32, now: u64) -> bool {
+        self.start_day(now);
+        limit == 0
+            || author.is_empty()
+            || self
+                .replies
+                .get(&author.to_lowercase())
+                .copied()
+                .unwrap_or(0)
+                < limit
+    }
+
+    /// Counts a reply to the user.
+    pub(crate) fn record(&mut self, author: &str, now: u64) {
+        self.start_day(now);
+        if !author.is_empty() {
+            *self.replies.entry(author.to_lowercase()).or_default() += 1;
+        }
+    }
+
+    /// Forgets the counts of the days before the one of `now`, in unix time.
+    fn start_day(&mut self, now: u64) {
+        let day = now / SECONDS_PER_DAY;
+        if day != self.day {
+            self.day = day;
+            self.replies.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_budget() {
+        let mut budget = ReplyBudget::default();
+        let now = 20_000 * SECONDS_PER_DAY + 100;
+        assert!(budget.allows("Someone", 2, now));
+        budget.record("Someone", now);
+        budget.record("someone", now + 1);
+        assert!(!budget.allows("someone", 2, now + 2));
+        assert!(budget.allows("someone", 3, now + 2));
+        assert!(budget.allows("someone", 0, now + 2));
+        assert!(budget.allows("someone_else", 2, now + 2));
+
+        budget.record("", now);
+        assert!(budget.allows("", 1, now));
+
+        // The next day starts over
+        assert!(budget.allows("someone", 2, now + SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn test_reply_budget_roundtrip() {
+        let mut budget = ReplyBudget::default();
+        budget.record("someone", 0);
+        let json = serde_json::to_string(&budget).unwrap();
+        assert_eq!(serde_json::from_str::<ReplyBudget>(&json).unwrap(), budget);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::math;
+use num_bigint::BigInt;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{LazyLock, Mutex};
+
+pub(crate) const DEFAULT_CALCULATION_CACHE_SIZE: usize = 100;
+
+/// The factorials calculated lately, keyed by number and level, as the same ones get asked for all the time.
+static FACTORIALS: LazyLock<Mutex<LruCache<(u64, u64), BigInt>>> =
+    LazyLock::new(|| Mutex::new(LruCache::new(DEFAULT_CALCULATION_CACHE_SIZE)));
+
+/// Keeps up to `capacity` values, dropping the least recently used one when full.
+/// Finding that one scans all entries, which is fine for the few hundred results worth keeping.
+#[derive(Debug)]
+pub(crate) struct LruCache<K, V> {
+    capacity: usize,
+    /// The values with the tick they were last used at.
+    entries: HashMap<K, (V, u64)>,
+    tick: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    /// A cache of size 0 keeps nothing.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (value, last_used) = self.entries.get_mut(key)?;
+        *last_used = self.tick;
+        Some(value.clone())
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            self.evict();
+        }
+        self.entries.insert(key, (value, self.tick));
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict();
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.keys()
+    }
+
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+/// Sets how many factorials are kept, see `CALCULATION_CACHE_SIZE`.
+pub(crate) fn set_factorial_cache_size(size: usize) {
+    FACTORIALS
+        .lock()
+        .expect("Factorial cache lock poisoned")
+        .set_capacity(size);
+}
+
+/// Like [`math::factorial`], but reuses results calculated before.
+/// If only a smaller one of the same level is known, like 998! when asked for 1000!, it is continued from there.
+pub(crate) fn factorial(n: u64, k: u64) -> BigInt {
+    let key = (n, k);
+    let base = {
+        let mut factorials = FACTORIALS.lock().expect("Factorial cache lock poisoned");
+        if let Some(factorial) = factorials.get(&key) {
+            return factorial;
+        }
+        let base = factorials
+            .keys()
+            .filter(|(m, level)| *level == k && *m < n && (n - m).is_multiple_of(k))
+            .map(|(m, _)| *m)
+            .max();
+        base.and_then(|m| Some((m, factorials.get(&(m, k))?)))
+    };
+    // Not holding the lock while calculating, so other threads aren't blocked by a big factorial
+    let factorial = match base {
+        Some((m, base)) => base * math::factorial_from(m, n, k),
+        None => math::factorial(n, k),
+    };
+    FACTORIALS
+        .lock()
+        .expect("Factorial cache lock poisoned")
+        .insert(key, factorial.clone());
+    factorial
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_cache_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some("one"));
+        cache.insert(3, "three");
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&3), Some("three"));
+
+        // Replacing a value doesn't evict anything
+        cache.insert(3, "drei");
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&3), Some("drei"));
+
+        cache.set_capacity(1);
+        assert_eq!(cache.entries.len(), 1);
+        assert_eq!(cache.get(&3), Some("drei"));
+    }
+
+    #[test]
+    fn test_lru_cache_disabled() {
+        let mut cache = LruCache::new(0);
+        cache.insert(1, "one");
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn test_cached_factorial() {
+        assert_eq!(factorial(100, 1), math::factorial(100, 1));
+        assert_eq!(factorial(100, 1), math::factorial(100, 1));
+        assert_eq!(factorial(100, 2), math::factorial(100, 2));
+        // Continued from the ones above
+        assert_eq!(factorial(103, 1), math::factorial(103, 1));
+        assert_eq!(factorial(104, 2), math::factorial(104, 2));
+        assert_eq!(factorial(105, 2), math::factorial(105, 2));
+    }
+}
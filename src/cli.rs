@@ -0,0 +1,734 @@
+use crate::commands::Commands;
+use crate::error::FactorionError;
+use crate::reddit_comment::{
+    RedditComment, Status, Strictness, DEFAULT_MAX_DECIMALS, DEFAULT_MAX_JOBS_PER_COMMENT,
+    DEFAULT_UNIT_SKIP_LIST, FOOTER_TEXT,
+};
+use fancy_regex::Regex;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+Usage: factorion-bot [command]
+
+Without a command, the bot polls reddit and replies to comments.
+
+Commands:
+  calc <text>                       Print the results of the calculations in the text
+  parse <text>                      Print what the parser found in the text
+  reply <text>                      Print the reply the bot would post to the text
+  explain <text>                    Print which parts of the text were calculated, and why others weren't
+  repl                              Calculate the lines typed in, see :help in it
+  batch                             Read one comment per line from stdin, print a JSON object for each
+  bench <file>                      Time parsing and formatting the comments in the file, one per line
+  b-file <operation> <start> <end>  Export exact values in the OEIS b-file format
+  shadow-report                     Summarize the replies stored in shadow mode
+  --print-config                    Print the effective configuration
+  -h, --help                        Print this help
+
+Options for calc, parse, reply, explain, repl, batch and bench:
+  --commands <names>                Comma separated commands, like `short,digits`
+  --max-length <n>                  Split replies into up to n comments (default 1)
+
+Options for every command:
+  --errors-json                     Describe why the exit code isn't 0 as a JSON object on stderr
+
+Exit codes:
+  0  Success
+  1  calc, parse, reply or explain found nothing to calculate
+  2  calc, parse, reply or explain found calculations over the limits
+  3  Something went wrong
+  4  Invalid arguments
+";
+
+const REPL_HELP: &str = "\
+Every line is calculated like a comment, end it with \\ to continue it on the next line.
+  :commands <names>  Use these commands from now on, `:commands none` for none
+  :history           List the lines calculated so far
+  :<n>               Calculate line n of the history again
+  :quit              Leave, like end of input does
+";
+
+/// What the binary was asked to do, picked by its first argument.
+#[derive(Debug, PartialEq)]
+pub(crate) enum CliCommand {
+    /// Run the bot.
+    Bot,
+    Help,
+    PrintConfig,
+    ShadowReport,
+    /// The arguments following `b-file`.
+    BFile(Vec<String>),
+    Calc(Input),
+    Parse(Input),
+    Reply(Input),
+    Explain(Input),
+    /// The options given to `repl`, without a text.
+    Repl(Input),
+    /// The options given to `batch`, without a text.
+    Batch(Input),
+    /// The options given to `bench`, with the path of the corpus as text.
+    Bench(Input),
+}
+
+/// How a command went, told by the exit code so scripts can branch on it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Success,
+    /// The text has nothing the bot would calculate.
+    NothingFound,
+    /// Some calculations were too big, or the comment asked for too many or too expensive ones.
+    LimitsExceeded,
+}
+
+impl Outcome {
+    const LIMITS: [Status; 3] = [
+        Status::NumberTooBig,
+        Status::TooManyCalculations,
+        Status::TooExpensive,
+    ];
+
+    fn of(comment: &RedditComment) -> Self {
+        if Self::LIMITS
+            .iter()
+            .any(|status| comment.status.contains(status))
+        {
+            Outcome::LimitsExceeded
+        } else if comment.status.contains(&Status::FactorialsFound) {
+            Outcome::Success
+        } else {
+            Outcome::NothingFound
+        }
+    }
+}
+
+/// The exit code of a command, and for any but 0 the JSON object `--errors-json` writes.
+fn exit_status(result: &Result<Outcome, FactorionError>) -> (u8, Option<serde_json::Value>) {
+    let (code, error, message) = match result {
+        Ok(Outcome::Success) => return (0, None),
+        Ok(Outcome::NothingFound) => (1, "nothing_found", "No calculations found".to_string()),
+        Ok(Outcome::LimitsExceeded) => (
+            2,
+            "limits_exceeded",
+            "Some calculations are over the limits".to_string(),
+        ),
+        Err(e @ FactorionError::Usage(_)) => (4, e.kind(), e.to_string()),
+        Err(e) => (3, e.kind(), e.to_string()),
+    };
+    let json = serde_json::json!({
+        "exit_code": code,
+        "error": error,
+        "message": message,
+    });
+    (code, Some(json))
+}
+
+/// Exits with the code for the result, reporting errors on stderr, as JSON with `errors_json`.
+pub(crate) fn exit(result: Result<Outcome, FactorionError>, errors_json: bool) -> ! {
+    let (code, json) = exit_status(&result);
+    match (json, &result) {
+        (Some(json), _) if errors_json => eprintln!("{json}"),
+        (_, Err(e)) => eprintln!("Error: {e}"),
+        _ => {}
+    }
+    // Exiting skips flushing stdout
+    let _ = std::io::stdout().flush();
+    std::process::exit(code.into())
+}
+
+/// Removes a flag that can be given anywhere in the arguments, returning whether it was there.
+pub(crate) fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != before
+}
+
+/// A text given on the command line, read like a comment.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Input {
+    pub(crate) text: String,
+    /// Applied as if the comment's subreddit had them enabled.
+    pub(crate) commands: Commands,
+    pub(crate) max_reply_chain_length: usize,
+}
+
+impl CliCommand {
+    /// Reads the arguments, without the name of the binary.
+    pub(crate) fn parse(args: &[String]) -> Result<Self, FactorionError> {
+        let Some((command, rest)) = args.split_first() else {
+            return Ok(CliCommand::Bot);
+        };
+        Ok(match command.as_str() {
+            "-h" | "--help" | "help" => CliCommand::Help,
+            "--print-config" => CliCommand::PrintConfig,
+            "shadow-report" => CliCommand::ShadowReport,
+            "b-file" => CliCommand::BFile(rest.to_vec()),
+            "calc" => CliCommand::Calc(Input::parse(rest)?),
+            "parse" => CliCommand::Parse(Input::parse(rest)?),
+            "reply" => CliCommand::Reply(Input::parse(rest)?),
+            "explain" => CliCommand::Explain(Input::parse(rest)?),
+            "repl" => CliCommand::Repl(Input::parse_without_text(command, rest)?),
+            "batch" => CliCommand::Batch(Input::parse_without_text(command, rest)?),
+            "bench" => CliCommand::Bench(Input::parse(rest)?),
+            unknown => {
+                return Err(FactorionError::Usage(format!(
+                    "Unknown command {unknown}, see --help"
+                )))
+            }
+        })
+    }
+}
+
+impl Input {
+    /// Reads the options, joining everything else into the text, which has to be there.
+    fn parse(args: &[String]) -> Result<Self, FactorionError> {
+        let input = Self::parse_options(args)?;
+        if input.text.is_empty() {
+            return Err(FactorionError::Usage(
+                "Missing text, see --help".to_string(),
+            ));
+        }
+        Ok(input)
+    }
+
+    /// Reads the options of a `command` that takes its texts from stdin.
+    fn parse_without_text(command: &str, args: &[String]) -> Result<Self, FactorionError> {
+        let input = Self::parse_options(args)?;
+        if !input.text.is_empty() {
+            return Err(FactorionError::Usage(format!(
+                "Unexpected text for {command}: {}",
+                input.text
+            )));
+        }
+        Ok(input)
+    }
+
+    /// Reads the options, joining everything else into the text.
+    fn parse_options(args: &[String]) -> Result<Self, FactorionError> {
+        let mut words = vec![];
+        let mut commands = Commands::NONE;
+        let mut max_reply_chain_length = 1;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| FactorionError::Usage(format!("Missing value for {arg}")))
+            };
+            match arg.as_str() {
+                "--commands" => {
+                    commands = Commands::from_names(value()?).map_err(FactorionError::Usage)?
+                }
+                "--max-length" => {
+                    let length = value()?;
+                    max_reply_chain_length = length
+                        .parse()
+                        .ok()
+                        .filter(|length| *length > 0)
+                        .ok_or_else(|| {
+                            FactorionError::Usage(format!("Invalid length: {length}"))
+                        })?;
+                }
+                _ => words.push(arg.as_str()),
+            }
+        }
+        Ok(Self {
+            text: words.join(" "),
+            commands,
+            max_reply_chain_length,
+        })
+    }
+
+    /// Parses the text like a comment in a subreddit with the default settings.
+    pub(crate) fn comment(&self) -> Result<RedditComment, FactorionError> {
+        RedditComment::try_new_with_settings(
+            &self.text,
+            "cli",
+            Strictness::default(),
+            self.commands,
+            &default_unit_skip_list(),
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .map_err(|e| FactorionError::Usage(format!("Failed to parse the text: {e}")))
+    }
+}
+
+/// The output of a command working on a text and how it went, or `None` for the other commands.
+pub(crate) fn run(command: &CliCommand) -> Result<Option<(String, Outcome)>, FactorionError> {
+    Ok(Some(match command {
+        CliCommand::Help => (USAGE.to_string(), Outcome::Success),
+        CliCommand::Calc(input) => format_replies(input, "")?,
+        CliCommand::Reply(input) => format_replies(input, FOOTER_TEXT)?,
+        CliCommand::Parse(input) => {
+            let comment = input.comment()?;
+            let output = format!(
+                "status: {:?}\ncommands: {}\nfound: {}\n",
+                comment.status,
+                comment.commands,
+                comment.sources().join(", ")
+            );
+            (output, Outcome::of(&comment))
+        }
+        CliCommand::Explain(input) => explain(input)?,
+        CliCommand::Bench(input) => {
+            let corpus =
+                std::fs::read_to_string(&input.text).map_err(|source| FactorionError::Io {
+                    path: "<corpus>",
+                    source,
+                })?;
+            (bench(input, &corpus)?, Outcome::Success)
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// Times parsing (which includes the calculations) and formatting the reply for every line of the corpus,
+/// summarized as percentiles, to spot performance regressions.
+fn bench(options: &Input, corpus: &str) -> Result<String, FactorionError> {
+    let mut parse_times = vec![];
+    let mut format_times = vec![];
+    for line in corpus.lines().filter(|line| !line.trim().is_empty()) {
+        let input = Input {
+            text: line.to_string(),
+            commands: options.commands,
+            max_reply_chain_length: options.max_reply_chain_length,
+        };
+        let start = Instant::now();
+        let comment = input.comment()?;
+        parse_times.push(start.elapsed());
+        if comment.status.contains(&Status::FactorialsFound) {
+            let start = Instant::now();
+            comment.get_reply_chain_with_footer(FOOTER_TEXT, input.max_reply_chain_length);
+            format_times.push(start.elapsed());
+        }
+    }
+    if parse_times.is_empty() {
+        return Err(FactorionError::Usage(format!(
+            "No comments in {}",
+            options.text
+        )));
+    }
+    Ok(format!(
+        "{} comments, {} with calculations\nparse:  {}\nformat: {}\n",
+        parse_times.len(),
+        format_times.len(),
+        percentiles(parse_times),
+        percentiles(format_times)
+    ))
+}
+
+/// Summarizes the times like `p50 1ms  p90 2ms  p99 3ms  max 4ms`.
+fn percentiles(mut times: Vec<Duration>) -> String {
+    times.sort();
+    let Some(max) = times.last() else {
+        return "-".to_string();
+    };
+    // Nearest rank, so every percentile is one of the measured times
+    let percentile = |p: usize| times[(times.len() * p).div_ceil(100).max(1) - 1];
+    format!(
+        "p50 {:?}  p90 {:?}  p99 {:?}  max {max:?}",
+        percentile(50),
+        percentile(90),
+        percentile(99)
+    )
+}
+
+fn default_unit_skip_list() -> Vec<String> {
+    DEFAULT_UNIT_SKIP_LIST
+        .iter()
+        .map(|unit| unit.to_string())
+        .collect()
+}
+
+/// Lists the expressions that were calculated, and every factorial-looking candidate that wasn't, with the likely reason,
+/// to answer "why didn't the bot reply to my comment?".
+fn explain(input: &Input) -> Result<(String, Outcome), FactorionError> {
+    let comment = input.comment()?;
+    let body = &comment.parsed_body;
+    let mut explanation = format!(
+        "text after normalizing: {body:?}\ncommands: {}\nstatus: {:?}\n",
+        comment.commands, comment.status
+    );
+    explanation.push_str("calculated:\n");
+    for span in &comment.spans {
+        explanation.push_str(&format!("  {span:?} {}\n", &body[span.clone()]));
+    }
+
+    let candidate_regex = Regex::new(r"\d+!+").expect("Invalid candidate regex");
+    let mut dropped = String::new();
+    for candidate in candidate_regex.find_iter(body) {
+        let candidate = candidate.map_err(|e| FactorionError::Usage(e.to_string()))?;
+        let (start, end) = (candidate.start(), candidate.end());
+        if comment
+            .spans
+            .iter()
+            .any(|span| span.start <= start && end <= span.end)
+        {
+            continue;
+        }
+        let reason = if RedditComment::is_false_positive(body, start, end, Strictness::default()) {
+            "looks like part of a word or an exclamation, not a factorial"
+        } else if comment.commands.contains(Commands::SKIP_UNITS)
+            && RedditComment::has_unit_context(body, start, end, &default_unit_skip_list())
+                .unwrap_or(false)
+        {
+            "next to a currency or unit"
+        } else {
+            "not calculated on its own, e.g. part of a bigger expression, too big or over the limits"
+        };
+        dropped.push_str(&format!(
+            "  {:?} {} - {reason}\n",
+            start..end,
+            candidate.as_str()
+        ));
+    }
+    if !dropped.is_empty() {
+        explanation.push_str("dropped:\n");
+        explanation.push_str(&dropped);
+    }
+    Ok((explanation, Outcome::of(&comment)))
+}
+
+/// Calculates every line read from `input` like `calc`, until the input ends or `:quit` is typed.
+/// Keeps a history of the lines, which can be calculated again with `:<n>`.
+pub(crate) fn repl(
+    options: &Input,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), FactorionError> {
+    let io_error = |source| FactorionError::Io {
+        path: "<stdin>",
+        source,
+    };
+    let mut commands = options.commands;
+    let mut history: Vec<String> = vec![];
+    let mut text = String::new();
+    write!(output, "> ").map_err(io_error)?;
+    output.flush().map_err(io_error)?;
+    for line in input.lines() {
+        let line = line.map_err(io_error)?;
+        if let Some(continued) = line.strip_suffix('\\') {
+            text.push_str(continued);
+            text.push('\n');
+            write!(output, ". ").map_err(io_error)?;
+            output.flush().map_err(io_error)?;
+            continue;
+        }
+        text.push_str(&line);
+        let entry = std::mem::take(&mut text);
+        let entry = entry.trim();
+        let response = match entry.strip_prefix(':') {
+            Some("quit") => break,
+            Some("help") => REPL_HELP.to_string(),
+            Some("history") => history
+                .iter()
+                .enumerate()
+                .map(|(number, entry)| format!("{}: {entry}\n", number + 1))
+                .collect(),
+            Some(command) if command.starts_with("commands") => {
+                let names = command["commands".len()..].trim();
+                let names = if names.eq_ignore_ascii_case("none") {
+                    ""
+                } else {
+                    names
+                };
+                match Commands::from_names(names) {
+                    Ok(names) => {
+                        commands = names;
+                        format!("Using the commands: {commands}\n")
+                    }
+                    Err(e) => format!("{e}\n"),
+                }
+            }
+            Some(number) => match number
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| history.get(number.checked_sub(1)?))
+            {
+                Some(entry) => calculate_line(entry.clone(), commands, options),
+                None => format!("Unknown command :{number}, see :help\n"),
+            },
+            None if entry.is_empty() => String::new(),
+            None => {
+                history.push(entry.to_string());
+                calculate_line(entry.to_string(), commands, options)
+            }
+        };
+        write!(output, "{response}> ").map_err(io_error)?;
+        output.flush().map_err(io_error)?;
+    }
+    writeln!(output).map_err(io_error)
+}
+
+/// Parses every line of `input` as a comment, writing a JSON object per line to `output`
+/// with what was found, the statuses and the replies without footer, for regression tests against real comments.
+pub(crate) fn batch(
+    options: &Input,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), FactorionError> {
+    let io_error = |source| FactorionError::Io {
+        path: "<stdin>",
+        source,
+    };
+    for line in input.lines() {
+        let line = line.map_err(io_error)?;
+        let input = Input {
+            text: line,
+            commands: options.commands,
+            max_reply_chain_length: options.max_reply_chain_length,
+        };
+        let result = match input.comment() {
+            Ok(comment) => {
+                let replies = if comment.status.contains(&Status::FactorialsFound) {
+                    comment.get_reply_chain_with_footer("", input.max_reply_chain_length)
+                } else {
+                    vec![]
+                };
+                serde_json::json!({
+                    "comment": input.text,
+                    "found": comment.sources(),
+                    "status": comment
+                        .status
+                        .iter()
+                        .map(|status| format!("{status:?}"))
+                        .collect::<Vec<_>>(),
+                    "commands": comment.commands,
+                    "replies": replies,
+                })
+            }
+            Err(e) => serde_json::json!({
+                "comment": input.text,
+                "error": e.to_string(),
+            }),
+        };
+        writeln!(output, "{result}").map_err(io_error)?;
+    }
+    Ok(())
+}
+
+fn calculate_line(text: String, commands: Commands, options: &Input) -> String {
+    let input = Input {
+        text,
+        commands,
+        max_reply_chain_length: options.max_reply_chain_length,
+    };
+    format_replies(&input, "").map_or_else(|e| format!("{e}\n"), |(replies, _)| replies)
+}
+
+fn format_replies(input: &Input, footer: &str) -> Result<(String, Outcome), FactorionError> {
+    let comment = input.comment()?;
+    let outcome = Outcome::of(&comment);
+    if !comment.status.contains(&Status::FactorialsFound) {
+        return Ok(("No calculations found\n".to_string(), outcome));
+    }
+    let replies = comment.get_reply_chain_with_footer(footer, input.max_reply_chain_length);
+    Ok((
+        format!("{}\n", replies.join("\n\n---\n\n").trim_end()),
+        outcome,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BENCH_CORPUS_FILE_PATH: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/bench/corpus.txt");
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_cli_command() {
+        assert_eq!(CliCommand::parse(&[]).unwrap(), CliCommand::Bot);
+        assert_eq!(
+            CliCommand::parse(&args(&["--help"])).unwrap(),
+            CliCommand::Help
+        );
+        assert_eq!(
+            CliCommand::parse(&args(&["b-file", "factorial", "0", "5"])).unwrap(),
+            CliCommand::BFile(args(&["factorial", "0", "5"]))
+        );
+        assert_eq!(
+            CliCommand::parse(&args(&["calc", "5!", "--commands", "short", "and", "6!"])).unwrap(),
+            CliCommand::Calc(Input {
+                text: "5! and 6!".to_string(),
+                commands: Commands::SHORT,
+                max_reply_chain_length: 1,
+            })
+        );
+        assert!(CliCommand::parse(&args(&["frobnicate"])).is_err());
+        assert!(CliCommand::parse(&args(&["calc"])).is_err());
+        assert!(CliCommand::parse(&args(&["calc", "5!", "--commands"])).is_err());
+        assert!(CliCommand::parse(&args(&["calc", "5!", "--commands", "nonsense"])).is_err());
+        assert!(CliCommand::parse(&args(&["reply", "5!", "--max-length", "0"])).is_err());
+        assert_eq!(
+            CliCommand::parse(&args(&["repl", "--commands", "short"])).unwrap(),
+            CliCommand::Repl(Input {
+                text: String::new(),
+                commands: Commands::SHORT,
+                max_reply_chain_length: 1,
+            })
+        );
+        assert!(CliCommand::parse(&args(&["repl", "5!"])).is_err());
+        assert!(CliCommand::parse(&args(&["batch", "5!"])).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let run_args = |arguments: &[&str]| {
+            run(&CliCommand::parse(&args(arguments)).unwrap())
+                .unwrap()
+                .unwrap()
+                .0
+        };
+        assert_eq!(run_args(&["calc", "5!"]), "Factorial of 5 is 120\n");
+        assert_eq!(run_args(&["calc", "hello"]), "No calculations found\n");
+        assert!(run_args(&["reply", "5!"]).ends_with(&format!("{}\n", FOOTER_TEXT.trim_end())));
+        assert_eq!(
+            run_args(&["parse", "5! and 3!!", "--commands", "short"]),
+            "status: [FactorialsFound]\ncommands: short\nfound: 5!, 3!!\n"
+        );
+        assert!(run_args(&["--help"]).starts_with("Usage:"));
+        assert_eq!(
+            run_args(&[
+                "explain",
+                "Catch-22! costs $5! but 4!",
+                "--commands",
+                "units"
+            ]),
+            "text after normalizing: \"Catch-22! costs $5! but 4!\"\n\
+            commands: units\n\
+            status: [FactorialsFound]\n\
+            calculated:\n  24..26 4!\n\
+            dropped:\n  \
+            6..9 22! - looks like part of a word or an exclamation, not a factorial\n  \
+            17..19 5! - next to a currency or unit\n"
+        );
+        assert_eq!(run(&CliCommand::Bot).unwrap(), None);
+    }
+
+    #[test]
+    fn test_run_outcome() {
+        let outcome = |arguments: &[&str]| {
+            run(&CliCommand::parse(&args(arguments)).unwrap())
+                .unwrap()
+                .unwrap()
+                .1
+        };
+        assert_eq!(outcome(&["calc", "5!"]), Outcome::Success);
+        assert_eq!(outcome(&["reply", "hello"]), Outcome::NothingFound);
+        assert_eq!(
+            outcome(&["parse", "5! and 1000000000!"]),
+            Outcome::LimitsExceeded
+        );
+        assert_eq!(
+            outcome(&["explain", "1000000000!"]),
+            Outcome::LimitsExceeded
+        );
+        assert_eq!(outcome(&["--help"]), Outcome::Success);
+    }
+
+    #[test]
+    fn test_exit_status() {
+        assert_eq!(exit_status(&Ok(Outcome::Success)), (0, None));
+        assert_eq!(
+            exit_status(&Ok(Outcome::NothingFound)),
+            (
+                1,
+                Some(serde_json::json!({
+                    "exit_code": 1,
+                    "error": "nothing_found",
+                    "message": "No calculations found",
+                }))
+            )
+        );
+        assert_eq!(exit_status(&Ok(Outcome::LimitsExceeded)).0, 2);
+        let (code, json) =
+            exit_status(&CliCommand::parse(&args(&["frobnicate"])).map(|_| Outcome::Success));
+        assert_eq!(code, 4);
+        assert_eq!(json.unwrap()["error"], "usage");
+        let error = FactorionError::Io {
+            path: "<corpus>",
+            source: std::io::Error::other("missing"),
+        };
+        assert_eq!(exit_status(&Err(error)).0, 3);
+    }
+
+    #[test]
+    fn test_take_flag() {
+        let mut arguments = args(&["calc", "--errors-json", "5!"]);
+        assert!(take_flag(&mut arguments, "--errors-json"));
+        assert_eq!(arguments, args(&["calc", "5!"]));
+        assert!(!take_flag(&mut arguments, "--errors-json"));
+    }
+
+    #[test]
+    fn test_repl() {
+        let options = Input::parse_options(&[]).unwrap();
+        let mut output = vec![];
+        repl(
+            &options,
+            "5!\n\n3! and \\\n4!\n:commands nonsense\n:commands short\n:1\n:history\n:9\n:quit\n6!\n"
+                .as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "> Factorial of 5 is 120\n\
+            > > . Factorial of 3 is 6 \n\nFactorial of 4 is 24\n\
+            > Unknown command: nonsense\n\
+            > Using the commands: short\n\
+            > Factorial of 5 is roughly 1.20e2\n\
+            > 1: 5!\n2: 3! and \n4!\n\
+            > Unknown command :9, see :help\n\
+            > \n"
+        );
+    }
+
+    #[test]
+    fn test_batch() {
+        let options = Input::parse_options(&args(&["--commands", "short"])).unwrap();
+        let mut output = vec![];
+        batch(&options, "5! and 3!!\nhello\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"commands":["short"],"comment":"5! and 3!!","found":["5!","3!!"],"replies":["Double-Factorial of 3 is roughly 3e0 \n\nFactorial of 5 is roughly 1.20e2 \n\n"],"status":["FactorialsFound"]}
+{"commands":["short"],"comment":"hello","found":[],"replies":[],"status":["NoFactorial"]}
+"#
+        );
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let times = (1..=200).rev().map(Duration::from_millis).collect();
+        assert_eq!(
+            percentiles(times),
+            "p50 100ms  p90 180ms  p99 198ms  max 200ms"
+        );
+        assert_eq!(
+            percentiles(vec![Duration::from_millis(5)]),
+            "p50 5ms  p90 5ms  p99 5ms  max 5ms"
+        );
+        assert_eq!(percentiles(vec![]), "-");
+    }
+
+    #[test]
+    fn test_bench() {
+        let options = Input::parse(&args(&["corpus.txt"])).unwrap();
+        let report = bench(&options, "5!\n\nhello\n10!!\n").unwrap();
+        assert!(report.starts_with("3 comments, 2 with calculations\nparse:  p50 "));
+        assert!(bench(&options, "\n").is_err());
+    }
+
+    #[test]
+    fn test_bench_corpus() {
+        let options = Input::parse(&args(&[BENCH_CORPUS_FILE_PATH])).unwrap();
+        let corpus = std::fs::read_to_string(BENCH_CORPUS_FILE_PATH).unwrap();
+        let report = bench(&options, &corpus).unwrap();
+        assert!(report.starts_with("10 comments, 9 with calculations\n"));
+    }
+}
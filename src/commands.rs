@@ -0,0 +1,329 @@
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+use std::str::FromStr;
+
+/// Options changing how comments are interpreted, set per subreddit or per comment.
+/// Every command is a single bit, so they can be combined with `|`.
+/// Commands are ordered by their bits, so the order never changes when new ones are added.
+///
+/// Serialized as a list of names, like `["left", "limits"]`.
+/// Deserializing also accepts a comma separated string or the raw bitmask, see [`Commands::from_bits`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Commands(u32);
+
+impl Commands {
+    pub(crate) const NONE: Commands = Commands(0);
+    /// Read `!n` as the left factorial, the sum of all k! for k < n.
+    pub(crate) const LEFT_FACTORIAL: Commands = Commands(1);
+    /// Tell up to which number results can be printed exactly, when some were too big.
+    pub(crate) const EXACT_LIMIT_NOTE: Commands = Commands(1 << 1);
+    /// Tell how many trailing zeros factorials have, even for ones too big to calculate.
+    pub(crate) const TRAILING_ZEROS: Commands = Commands(1 << 2);
+    /// Give the prime factorization of factorials.
+    pub(crate) const PRIME_FACTORS: Commands = Commands(1 << 3);
+    /// Ignore factorials that are rather amounts of money or units, like "$5!" or "5!kg".
+    pub(crate) const SKIP_UNITS: Commands = Commands(1 << 4);
+    /// Tell the digit count and digit sum of exact results.
+    pub(crate) const DIGIT_FACTS: Commands = Commands(1 << 5);
+    /// Show the identity relating double factorials to factorials.
+    pub(crate) const IDENTITIES: Commands = Commands(1 << 6);
+    /// Ignore quoted lines, so numbers quoted from other comments don't get answered again.
+    pub(crate) const NO_QUOTES: Commands = Commands(1 << 7);
+    /// Only tell the number of digits of results, also of factorials too big to calculate.
+    pub(crate) const DIGITS_ONLY: Commands = Commands(1 << 8);
+    /// Write factorials in scientific notation, even if they would fit into the reply.
+    pub(crate) const SHORT: Commands = Commands(1 << 9);
+    /// Also give Stirling's approximation of factorials, to compare it to the exact value.
+    pub(crate) const APPROXIMATE: Commands = Commands(1 << 10);
+    /// Post results too long for one comment in full, split over a chain of replies.
+    pub(crate) const FULL: Commands = Commands(1 << 11);
+    /// Leave out the fun fact notes, like that a number is a factorion.
+    pub(crate) const NO_NOTE: Commands = Commands(1 << 12);
+    /// Compare huge results to something tangible, like how long it takes to read them aloud.
+    pub(crate) const FUN_FACTS: Commands = Commands(1 << 13);
+
+    /// The name of every command, as used in comments (`[name]` or `!name`) and in the configuration.
+    pub(crate) const NAMES: [(&'static str, Commands); 14] = [
+        ("left", Commands::LEFT_FACTORIAL),
+        ("limits", Commands::EXACT_LIMIT_NOTE),
+        ("zeros", Commands::TRAILING_ZEROS),
+        ("factors", Commands::PRIME_FACTORS),
+        ("units", Commands::SKIP_UNITS),
+        ("facts", Commands::DIGIT_FACTS),
+        ("identities", Commands::IDENTITIES),
+        ("noquotes", Commands::NO_QUOTES),
+        ("digits", Commands::DIGITS_ONLY),
+        ("short", Commands::SHORT),
+        ("approx", Commands::APPROXIMATE),
+        ("full", Commands::FULL),
+        ("nonote", Commands::NO_NOTE),
+        ("funfacts", Commands::FUN_FACTS),
+    ];
+
+    /// Builds commands from a raw bitmask, stable across versions as bits of commands are never reused.
+    /// Fails on bits no command uses.
+    pub(crate) fn from_bits(bits: u32) -> Result<Commands, String> {
+        let all = Commands::NAMES
+            .iter()
+            .fold(0, |all, (_, command)| all | command.0);
+        if bits & !all != 0 {
+            return Err(format!("Unknown command bits: {:#x}", bits & !all));
+        }
+        Ok(Commands(bits))
+    }
+
+    /// The names of all contained commands, in the order of their bits.
+    pub(crate) fn names(self) -> impl Iterator<Item = &'static str> {
+        Commands::NAMES
+            .into_iter()
+            .filter(move |(_, command)| self.contains(*command))
+            .map(|(name, _)| name)
+    }
+
+    pub(crate) fn contains(self, other: Commands) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Finds all commands a comment asks for, written as `[name]` or `!name`.
+    /// `!name` has to end there, so words like "!approximately" don't count as `!approx`.
+    pub(crate) fn from_comment_text(text: &str) -> Commands {
+        let text = text.to_lowercase();
+        Commands::NAMES
+            .iter()
+            .filter(|(name, _)| {
+                text.contains(&format!("[{name}]"))
+                    || text
+                        .match_indices(&format!("!{name}"))
+                        .any(|(start, command)| {
+                            !text[start + command.len()..]
+                                .starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                        })
+            })
+            .fold(Commands::NONE, |commands, (_, command)| commands | *command)
+    }
+
+    /// Parses a comma separated list of command names.
+    pub(crate) fn from_names(names: &str) -> Result<Commands, String> {
+        names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .try_fold(Commands::NONE, |commands, name| {
+                Commands::NAMES
+                    .iter()
+                    .find(|(known, _)| known.eq_ignore_ascii_case(name))
+                    .map(|(_, command)| commands | *command)
+                    .ok_or(format!("Unknown command: {name}"))
+            })
+    }
+}
+
+/// Writes the names comma separated, the same format [`FromStr`] reads.
+impl fmt::Display for Commands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.names().collect::<Vec<_>>().join(","))
+    }
+}
+
+impl FromStr for Commands {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Commands::from_names(s)
+    }
+}
+
+impl Serialize for Commands {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.names())
+    }
+}
+
+impl<'de> Deserialize<'de> for Commands {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(CommandsVisitor)
+    }
+}
+
+struct CommandsVisitor;
+
+impl<'de> Visitor<'de> for CommandsVisitor {
+    type Value = Commands;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of command names, a comma separated string or a bitmask")
+    }
+
+    fn visit_u64<E: de::Error>(self, bits: u64) -> Result<Commands, E> {
+        let bits = u32::try_from(bits).map_err(|_| E::custom("Command bitmask is too big"))?;
+        Commands::from_bits(bits).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, bits: i64) -> Result<Commands, E> {
+        let bits = u64::try_from(bits).map_err(|_| E::custom("Command bitmask is negative"))?;
+        self.visit_u64(bits)
+    }
+
+    fn visit_str<E: de::Error>(self, names: &str) -> Result<Commands, E> {
+        names.parse().map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Commands, A::Error> {
+        let mut commands = Commands::NONE;
+        while let Some(name) = seq.next_element::<String>()? {
+            if name.contains(',') {
+                return Err(de::Error::custom(format!("Unknown command: {name}")));
+            }
+            commands |= name.parse().map_err(de::Error::custom)?;
+        }
+        Ok(commands)
+    }
+}
+
+impl BitOr for Commands {
+    type Output = Commands;
+
+    fn bitor(self, rhs: Commands) -> Commands {
+        Commands(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Commands {
+    fn bitor_assign(&mut self, rhs: Commands) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Parses the commands per subreddit, written like `mathmemes:left;askmath:left`.
+/// Subreddit names are stored in lowercase.
+pub(crate) fn parse_subreddit_commands(config: &str) -> Result<HashMap<String, Commands>, String> {
+    config
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (subreddit, names) = entry
+                .split_once(':')
+                .ok_or(format!("Missing ':' after subreddit in: {entry}"))?;
+            Ok((subreddit.trim().to_lowercase(), names.parse()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_from_comment_text() {
+        assert_eq!(
+            Commands::from_comment_text("!5 [left]"),
+            Commands::LEFT_FACTORIAL
+        );
+        assert_eq!(
+            Commands::from_comment_text("!LEFT !5"),
+            Commands::LEFT_FACTORIAL
+        );
+        assert_eq!(Commands::from_comment_text("left !5"), Commands::NONE);
+    }
+
+    #[test]
+    fn test_commands_from_comment_text_prefix_collisions() {
+        assert_eq!(
+            Commands::from_comment_text("That's !approximately right, !fully agree"),
+            Commands::NONE
+        );
+        assert_eq!(
+            Commands::from_comment_text("!shortly !leftover"),
+            Commands::NONE
+        );
+        assert_eq!(
+            Commands::from_comment_text("!approximately, but !approx."),
+            Commands::APPROXIMATE
+        );
+        assert_eq!(
+            Commands::from_comment_text("!full!short"),
+            Commands::FULL | Commands::SHORT
+        );
+    }
+
+    #[test]
+    fn test_commands_contains() {
+        assert!(Commands::LEFT_FACTORIAL.contains(Commands::LEFT_FACTORIAL));
+        assert!(Commands::LEFT_FACTORIAL.contains(Commands::NONE));
+        assert!(!Commands::NONE.contains(Commands::LEFT_FACTORIAL));
+    }
+
+    #[test]
+    fn test_parse_subreddit_commands() {
+        let commands = parse_subreddit_commands("MathMemes:left; askmath: ;").unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands["mathmemes"], Commands::LEFT_FACTORIAL);
+        assert_eq!(commands["askmath"], Commands::NONE);
+
+        assert!(parse_subreddit_commands("mathmemes").is_err());
+        assert!(parse_subreddit_commands("mathmemes:unknown").is_err());
+    }
+
+    /// Every combination of commands, to check the representations exhaustively.
+    fn all_combinations() -> Vec<Commands> {
+        let all = Commands::NAMES
+            .iter()
+            .fold(0, |all, (_, command)| all | command.0);
+        (0..=all)
+            .filter(|bits| bits & !all == 0)
+            .map(|bits| Commands::from_bits(bits).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_commands_display_from_str_round_trip() {
+        assert_eq!(Commands::NONE.to_string(), "");
+        assert_eq!(
+            (Commands::EXACT_LIMIT_NOTE | Commands::LEFT_FACTORIAL).to_string(),
+            "left,limits"
+        );
+        for commands in all_combinations() {
+            assert_eq!(commands.to_string().parse::<Commands>(), Ok(commands));
+        }
+    }
+
+    #[test]
+    fn test_commands_serde_round_trip() {
+        assert_eq!(
+            serde_json::to_string(&(Commands::LEFT_FACTORIAL | Commands::EXACT_LIMIT_NOTE))
+                .unwrap(),
+            r#"["left","limits"]"#
+        );
+        assert_eq!(serde_json::to_string(&Commands::NONE).unwrap(), "[]");
+        for commands in all_combinations() {
+            let json = serde_json::to_string(&commands).unwrap();
+            assert_eq!(serde_json::from_str::<Commands>(&json).unwrap(), commands);
+            let json = format!("{}", commands.0);
+            assert_eq!(serde_json::from_str::<Commands>(&json).unwrap(), commands);
+            let json = format!("\"{commands}\"");
+            assert_eq!(serde_json::from_str::<Commands>(&json).unwrap(), commands);
+        }
+    }
+
+    #[test]
+    fn test_commands_deserialize_invalid() {
+        assert!(serde_json::from_str::<Commands>(r#"["unknown"]"#).is_err());
+        assert!(serde_json::from_str::<Commands>(r#"["left,limits"]"#).is_err());
+        assert!(serde_json::from_str::<Commands>("16384").is_err());
+        assert!(serde_json::from_str::<Commands>("-1").is_err());
+        assert!(Commands::from_bits(1 << 31).is_err());
+    }
+
+    #[test]
+    fn test_commands_ordering() {
+        assert!(Commands::NONE < Commands::LEFT_FACTORIAL);
+        assert!(Commands::LEFT_FACTORIAL < Commands::EXACT_LIMIT_NOTE);
+        assert!(
+            Commands::EXACT_LIMIT_NOTE < (Commands::LEFT_FACTORIAL | Commands::EXACT_LIMIT_NOTE)
+        );
+    }
+}
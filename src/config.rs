@@ -0,0 +1,509 @@
+use crate::cache::DEFAULT_CALCULATION_CACHE_SIZE;
+use crate::commands;
+use crate::error::FactorionError;
+use crate::footer::FooterRotation;
+use crate::jitter::SubredditJitter;
+use crate::karma::KarmaThrottle;
+use crate::paste::PasteService;
+use crate::reddit_comment::{
+    Strictness, DEFAULT_MAX_DECIMALS, DEFAULT_MAX_JOBS_PER_COMMENT, DEFAULT_UNIT_SKIP_LIST,
+};
+use dotenv::dotenv;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+pub(crate) const CONFIG_FILE_PATH: &str = "config.toml";
+
+/// The configuration of the bot, read from `config.toml`.
+/// Every value can be overridden by the environment variable named in its comment.
+/// Reddit credentials are only read from the environment, so they never end up in the file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    pub(crate) reddit: RedditConfig,
+    pub(crate) parsing: ParsingConfig,
+    pub(crate) replies: RepliesConfig,
+}
+
+/// Where the bot looks for comments and how often.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct RedditConfig {
+    /// `SUBREDDITS`, joined with `+` like in reddit URLs.
+    pub(crate) subreddits: String,
+    /// `SLEEP_BETWEEN_REQUESTS`, in seconds.
+    pub(crate) sleep_between_requests: u64,
+    /// `CHECK_MENTIONS`: also answer unread mentions of the bot.
+    pub(crate) check_mentions: bool,
+    /// `MARK_MENTIONS_READ`: mark answered mentions as read.
+    pub(crate) mark_mentions_read: bool,
+    /// `SHADOW_SUBREDDITS`: comma separated subreddits in which replies are only stored.
+    pub(crate) shadow_subreddits: String,
+    /// `ANSWER_NSFW`: also answer in NSFW and quarantined subreddits, e.g. when mentioned there.
+    pub(crate) answer_nsfw: bool,
+    /// `BLOCKED_USERS`: comma separated users whose comments are never answered.
+    pub(crate) blocked_users: String,
+    /// `BLOCKED_SUBREDDITS`: comma separated subreddits in which comments are never answered, e.g. for mentions.
+    pub(crate) blocked_subreddits: String,
+    /// `KARMA_THROTTLE`: the average score of recent replies per subreddit, below which only mentions are answered there,
+    /// e.g. `mathmemes:0;*:-1`.
+    pub(crate) karma_throttle: String,
+    /// `TOP_LEVEL_ONLY_SUBREDDITS`: comma separated subreddits in which only comments answering the post itself are polled.
+    pub(crate) top_level_only_subreddits: String,
+    /// `BOT_OWNERS`: comma separated users who may control the bot with private messages.
+    pub(crate) bot_owners: String,
+    /// `ALERT_WEBHOOK`: Discord or Slack webhook URL alerted when the same errors keep happening or the bot panics.
+    pub(crate) alert_webhook: String,
+}
+
+/// How comments are read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ParsingConfig {
+    /// `FACTORIAL_STRICTNESS`
+    pub(crate) factorial_strictness: Strictness,
+    /// `SUBREDDIT_COMMANDS`, e.g. `mathmemes:left;askmath:left`.
+    pub(crate) subreddit_commands: String,
+    /// `UNIT_SKIP_LIST`: comma separated currencies and units for the `units` command.
+    pub(crate) unit_skip_list: String,
+    /// `MAX_JOBS_PER_COMMENT`: how many calculations a single comment gets, the rest is skipped.
+    pub(crate) max_jobs_per_comment: usize,
+    /// `CALCULATION_CACHE_SIZE`: how many factorials are kept for comments asking for them again, 0 to keep none.
+    pub(crate) calculation_cache_size: usize,
+}
+
+/// How replies are written and posted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct RepliesConfig {
+    /// `FOOTER_CONTACTS`: comma separated contacts rotated in the footer.
+    pub(crate) footer_contacts: String,
+    /// `MAX_REPLY_CHAIN_LENGTH`: how many comments a too long reply may be split into.
+    pub(crate) max_reply_chain_length: usize,
+    /// `REPLY_JITTER`, e.g. `mathmemes:5-30:greetings;*:1-5`.
+    pub(crate) reply_jitter: String,
+    /// `MAX_DECIMALS`: how many decimals `!precision` may ask for.
+    pub(crate) max_decimals: usize,
+    /// `PASTE_SERVICE`: `gist` or a URL to upload results too long for reddit to, see [`PasteService`].
+    pub(crate) paste_service: String,
+    /// `MAX_REPLIES_PER_USER`: how many replies a single user gets per day, 0 for no limit.
+    pub(crate) max_replies_per_user: u32,
+    /// `DELETE_REPLIES_AT_SCORE`: replies voted down to this score or lower are deleted, 0 to keep all.
+    pub(crate) delete_replies_at_score: i64,
+}
+
+impl Default for RedditConfig {
+    fn default() -> Self {
+        Self {
+            subreddits: String::new(),
+            sleep_between_requests: 60,
+            check_mentions: false,
+            mark_mentions_read: false,
+            shadow_subreddits: String::new(),
+            answer_nsfw: true,
+            blocked_users: String::new(),
+            blocked_subreddits: String::new(),
+            karma_throttle: String::new(),
+            top_level_only_subreddits: String::new(),
+            bot_owners: String::new(),
+            alert_webhook: String::new(),
+        }
+    }
+}
+
+impl Default for ParsingConfig {
+    fn default() -> Self {
+        Self {
+            factorial_strictness: Strictness::default(),
+            subreddit_commands: String::new(),
+            unit_skip_list: DEFAULT_UNIT_SKIP_LIST.join(","),
+            max_jobs_per_comment: DEFAULT_MAX_JOBS_PER_COMMENT,
+            calculation_cache_size: DEFAULT_CALCULATION_CACHE_SIZE,
+        }
+    }
+}
+
+impl Default for RepliesConfig {
+    fn default() -> Self {
+        Self {
+            footer_contacts: String::new(),
+            max_reply_chain_length: 1,
+            reply_jitter: String::new(),
+            max_decimals: DEFAULT_MAX_DECIMALS,
+            paste_service: String::new(),
+            max_replies_per_user: 0,
+            delete_replies_at_score: 0,
+        }
+    }
+}
+
+impl Config {
+    /// Reads `config.toml` (or uses the defaults without one), applies the environment variables
+    /// (also from `.env`) and validates the result.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        dotenv().ok();
+        let mut config = match fs::read_to_string(CONFIG_FILE_PATH) {
+            Ok(file) => Config::parse(&file)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
+            Err(source) => {
+                return Err(FactorionError::Io {
+                    path: CONFIG_FILE_PATH,
+                    source,
+                })
+            }
+        };
+        config.apply_overrides(|variable| std::env::var(variable).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn parse(file: &str) -> Result<Self, FactorionError> {
+        toml::from_str(file).map_err(|e| FactorionError::config(CONFIG_FILE_PATH, e.message()))
+    }
+
+    /// Replaces every value that has its environment variable set, as returned by `lookup`.
+    fn apply_overrides(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), FactorionError> {
+        let reddit = &mut self.reddit;
+        override_value(&mut reddit.subreddits, "SUBREDDITS", &lookup)?;
+        override_value(
+            &mut reddit.sleep_between_requests,
+            "SLEEP_BETWEEN_REQUESTS",
+            &lookup,
+        )?;
+        override_value(&mut reddit.check_mentions, "CHECK_MENTIONS", &lookup)?;
+        override_value(
+            &mut reddit.mark_mentions_read,
+            "MARK_MENTIONS_READ",
+            &lookup,
+        )?;
+        override_value(&mut reddit.shadow_subreddits, "SHADOW_SUBREDDITS", &lookup)?;
+        override_value(&mut reddit.answer_nsfw, "ANSWER_NSFW", &lookup)?;
+        override_value(&mut reddit.blocked_users, "BLOCKED_USERS", &lookup)?;
+        override_value(
+            &mut reddit.blocked_subreddits,
+            "BLOCKED_SUBREDDITS",
+            &lookup,
+        )?;
+        override_value(&mut reddit.karma_throttle, "KARMA_THROTTLE", &lookup)?;
+        override_value(
+            &mut reddit.top_level_only_subreddits,
+            "TOP_LEVEL_ONLY_SUBREDDITS",
+            &lookup,
+        )?;
+        override_value(&mut reddit.bot_owners, "BOT_OWNERS", &lookup)?;
+        override_value(&mut reddit.alert_webhook, "ALERT_WEBHOOK", &lookup)?;
+
+        let parsing = &mut self.parsing;
+        override_value(
+            &mut parsing.factorial_strictness,
+            "FACTORIAL_STRICTNESS",
+            &lookup,
+        )?;
+        override_value(
+            &mut parsing.subreddit_commands,
+            "SUBREDDIT_COMMANDS",
+            &lookup,
+        )?;
+        override_value(&mut parsing.unit_skip_list, "UNIT_SKIP_LIST", &lookup)?;
+        override_value(
+            &mut parsing.max_jobs_per_comment,
+            "MAX_JOBS_PER_COMMENT",
+            &lookup,
+        )?;
+        override_value(
+            &mut parsing.calculation_cache_size,
+            "CALCULATION_CACHE_SIZE",
+            &lookup,
+        )?;
+
+        let replies = &mut self.replies;
+        override_value(&mut replies.footer_contacts, "FOOTER_CONTACTS", &lookup)?;
+        override_value(
+            &mut replies.max_reply_chain_length,
+            "MAX_REPLY_CHAIN_LENGTH",
+            &lookup,
+        )?;
+        override_value(&mut replies.reply_jitter, "REPLY_JITTER", &lookup)?;
+        override_value(&mut replies.max_decimals, "MAX_DECIMALS", &lookup)?;
+        override_value(&mut replies.paste_service, "PASTE_SERVICE", &lookup)?;
+        override_value(
+            &mut replies.max_replies_per_user,
+            "MAX_REPLIES_PER_USER",
+            &lookup,
+        )?;
+        override_value(
+            &mut replies.delete_replies_at_score,
+            "DELETE_REPLIES_AT_SCORE",
+            &lookup,
+        )?;
+        Ok(())
+    }
+
+    /// Checks the values that have a syntax of their own, so mistakes show up on startup.
+    fn validate(&self) -> Result<(), FactorionError> {
+        if self.reddit.subreddits.trim().is_empty() {
+            return Err(FactorionError::config("SUBREDDITS", "must be set"));
+        }
+        if self.parsing.max_jobs_per_comment == 0 {
+            return Err(FactorionError::config(
+                "MAX_JOBS_PER_COMMENT",
+                "must be at least 1",
+            ));
+        }
+        if self.replies.max_reply_chain_length == 0 {
+            return Err(FactorionError::config(
+                "MAX_REPLY_CHAIN_LENGTH",
+                "must be at least 1",
+            ));
+        }
+        let alert_webhook = self.reddit.alert_webhook.trim();
+        if !alert_webhook.is_empty() && !alert_webhook.starts_with("https://") {
+            return Err(FactorionError::config(
+                "ALERT_WEBHOOK",
+                "must be a https URL",
+            ));
+        }
+        if self.replies.delete_replies_at_score > 0 {
+            return Err(FactorionError::config(
+                "DELETE_REPLIES_AT_SCORE",
+                "must not be positive, new replies start at 1",
+            ));
+        }
+        commands::parse_subreddit_commands(&self.parsing.subreddit_commands)
+            .map_err(|e| FactorionError::config("SUBREDDIT_COMMANDS", e))?;
+        FooterRotation::new(&self.replies.footer_contacts)
+            .map_err(|e| FactorionError::config("FOOTER_CONTACTS", e))?;
+        SubredditJitter::parse(&self.replies.reply_jitter)
+            .map_err(|e| FactorionError::config("REPLY_JITTER", e))?;
+        KarmaThrottle::parse(&self.reddit.karma_throttle)
+            .map_err(|e| FactorionError::config("KARMA_THROTTLE", e))?;
+        PasteService::parse(&self.replies.paste_service)
+            .map_err(|e| FactorionError::config("PASTE_SERVICE", e))?;
+        Ok(())
+    }
+
+    /// The units of the `units` command as a list.
+    pub(crate) fn unit_skip_list(&self) -> Vec<String> {
+        self.parsing
+            .unit_skip_list
+            .split(',')
+            .map(str::trim)
+            .filter(|unit| !unit.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The blocked users and subreddits as sets, in lowercase like the names they are compared to.
+    pub(crate) fn blocklist(&self) -> (HashSet<String>, HashSet<String>) {
+        (
+            to_set(&self.reddit.blocked_users),
+            to_set(&self.reddit.blocked_subreddits),
+        )
+    }
+
+    /// The users who may control the bot, in lowercase.
+    pub(crate) fn bot_owners(&self) -> HashSet<String> {
+        to_set(&self.reddit.bot_owners)
+    }
+
+    /// The subreddits in which only top level comments are answered, in lowercase.
+    pub(crate) fn top_level_only_subreddits(&self) -> HashSet<String> {
+        to_set(&self.reddit.top_level_only_subreddits)
+    }
+
+    /// The effective configuration in the format of `config.toml`, for `--print-config`.
+    pub(crate) fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("Failed to serialize config")
+    }
+}
+
+/// A comma separated list of names as a set, in lowercase.
+fn to_set(list: &str) -> HashSet<String> {
+    list.split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn override_value<T: FromStr>(
+    value: &mut T,
+    variable: &'static str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<(), FactorionError>
+where
+    T::Err: ToString,
+{
+    if let Some(overridden) = lookup(variable) {
+        *value = overridden
+            .parse()
+            .map_err(|e: T::Err| FactorionError::config(variable, e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Notices when a config file changes, by its modification time, so it can be reloaded without a restart.
+#[derive(Debug)]
+pub(crate) struct ConfigWatcher {
+    path: &'static str,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    pub(crate) fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            modified: Self::modified(path),
+        }
+    }
+
+    fn modified(path: &str) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|file| file.modified()).ok()
+    }
+
+    /// Whether the file was changed, created or deleted since the last call.
+    pub(crate) fn changed(&mut self) -> bool {
+        let modified = Self::modified(self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r#"
+            [reddit]
+            subreddits = "mathmemes+askmath"
+            check_mentions = true
+
+            [parsing]
+            factorial_strictness = "strict"
+
+            [replies]
+            max_reply_chain_length = 3
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.reddit.subreddits, "mathmemes+askmath");
+        assert_eq!(config.reddit.sleep_between_requests, 60);
+        assert!(config.reddit.check_mentions);
+        assert_eq!(config.parsing.factorial_strictness, Strictness::Strict);
+        assert_eq!(config.replies.max_reply_chain_length, 3);
+        assert!(config.unit_skip_list().contains(&"kg".to_string()));
+
+        assert!(Config::parse("[reddit]\nsubreddit = \"typo\"").is_err());
+        assert!(Config::parse("[parsing]\nfactorial_strictness = \"loose\"").is_err());
+    }
+
+    #[test]
+    fn test_config_env_overrides() {
+        let mut config = Config::parse("[reddit]\nsubreddits = \"mathmemes\"").unwrap();
+        let env = HashMap::from([
+            ("SUBREDDITS", "askmath"),
+            ("CHECK_MENTIONS", "true"),
+            ("FACTORIAL_STRICTNESS", "Lenient"),
+            ("UNIT_SKIP_LIST", "kg, $"),
+        ]);
+        config
+            .apply_overrides(|variable| env.get(variable).map(|value| value.to_string()))
+            .unwrap();
+        assert_eq!(config.reddit.subreddits, "askmath");
+        assert!(config.reddit.check_mentions);
+        assert_eq!(config.parsing.factorial_strictness, Strictness::Lenient);
+        assert_eq!(config.unit_skip_list(), vec!["kg", "$"]);
+
+        let error = config
+            .apply_overrides(|variable| (variable == "MAX_REPLY_CHAIN_LENGTH").then(|| "x".into()))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid configuration in MAX_REPLY_CHAIN_LENGTH: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let mut config = Config::default();
+        assert!(config.validate().is_err());
+        config.reddit.subreddits = "mathmemes".to_string();
+        assert!(config.validate().is_ok());
+
+        config.replies.reply_jitter = "mathmemes:30-5".to_string();
+        assert!(config.validate().is_err());
+        config.replies.reply_jitter = String::new();
+        config.parsing.subreddit_commands = "mathmemes:nonsense".to_string();
+        assert!(config.validate().is_err());
+        config.parsing.subreddit_commands = String::new();
+        config.parsing.max_jobs_per_comment = 0;
+        assert!(config.validate().is_err());
+        config.parsing.max_jobs_per_comment = 1;
+        config.replies.paste_service = "pastebin".to_string();
+        assert!(config.validate().is_err());
+        config.replies.paste_service = String::new();
+        config.reddit.karma_throttle = "mathmemes".to_string();
+        assert!(config.validate().is_err());
+        config.reddit.karma_throttle = String::new();
+        config.replies.delete_replies_at_score = -5;
+        assert!(config.validate().is_ok());
+        config.replies.delete_replies_at_score = 1;
+        assert!(config.validate().is_err());
+        config.replies.delete_replies_at_score = 0;
+        config.reddit.alert_webhook = "discord".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_blocklist() {
+        let mut config = Config::default();
+        config.reddit.blocked_users = "Spammer, ,other".to_string();
+        config.reddit.blocked_subreddits = "Quiet".to_string();
+        let (users, subreddits) = config.blocklist();
+        assert_eq!(users.len(), 2);
+        assert!(users.contains("spammer") && users.contains("other"));
+        assert!(subreddits.contains("quiet"));
+    }
+
+    #[test]
+    fn test_print_config_roundtrip() {
+        let mut config = Config::default();
+        config.reddit.subreddits = "mathmemes".to_string();
+        config.parsing.factorial_strictness = Strictness::Strict;
+        assert_eq!(Config::parse(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_config_watcher() {
+        let path =
+            std::env::temp_dir().join(format!("factorion-config-{}.toml", std::process::id()));
+        let path: &'static str = path.to_str().unwrap().to_string().leak();
+        let _ = fs::remove_file(path);
+        let mut watcher = ConfigWatcher::new(path);
+        assert!(!watcher.changed());
+
+        fs::write(path, "[reddit]\n").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        assert!(watcher.changed());
+
+        fs::remove_file(path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+}
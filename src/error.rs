@@ -0,0 +1,199 @@
+use reqwest::header::InvalidHeaderValue;
+use thiserror::Error;
+
+/// Every error the bot can run into, from reading the configuration to talking to reddit.
+#[derive(Debug, Error)]
+pub(crate) enum FactorionError {
+    #[error("Invalid configuration in {variable}: {message}")]
+    Config {
+        variable: &'static str,
+        message: String,
+    },
+    #[error("Request to reddit failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Invalid request header: {0}")]
+    Header(#[from] InvalidHeaderValue),
+    #[error("Reddit rejected the request: {0}")]
+    Rejected(String),
+    /// Reddit is rate limiting the bot or having problems of its own, so the request can be tried again later.
+    #[error("Reddit can't handle the request right now: {0}")]
+    Unavailable(String),
+    #[error("Unexpected response from reddit: {0}")]
+    Response(#[from] serde_json::Error),
+    #[error("Failed to upload to the paste service: {0}")]
+    Upload(String),
+    #[error("Invalid arguments: {0}")]
+    Usage(String),
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        path: &'static str,
+        source: std::io::Error,
+    },
+}
+
+/// The part of the bot an error comes from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Component {
+    Config,
+    RedditApi,
+    Paste,
+    Storage,
+}
+
+impl FactorionError {
+    pub(crate) fn config(variable: &'static str, message: impl ToString) -> Self {
+        FactorionError::Config {
+            variable,
+            message: message.to_string(),
+        }
+    }
+
+    /// A short name of the kind of error, for logs and metrics.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            FactorionError::Config { .. } => "config",
+            FactorionError::Usage(_) => "usage",
+            FactorionError::Http(_) => "http",
+            FactorionError::Header(_) => "header",
+            FactorionError::Rejected(_) => "rejected",
+            FactorionError::Unavailable(_) => "unavailable",
+            FactorionError::Response(_) => "response",
+            FactorionError::Upload(_) => "upload",
+            FactorionError::Io { .. } => "io",
+        }
+    }
+
+    /// Whether trying the same thing again later might work.
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            FactorionError::Http(error) => !error.is_builder(),
+            FactorionError::Unavailable(_)
+            | FactorionError::Upload(_)
+            | FactorionError::Io { .. } => true,
+            FactorionError::Config { .. }
+            | FactorionError::Usage(_)
+            | FactorionError::Header(_)
+            | FactorionError::Rejected(_)
+            | FactorionError::Response(_) => false,
+        }
+    }
+
+    /// Whether reddit refused the request because the bot is banned, e.g. from a subreddit.
+    pub(crate) fn is_ban(&self) -> bool {
+        match self {
+            FactorionError::Rejected(message) => {
+                message.starts_with("403") || message.to_lowercase().contains("banned")
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn component(&self) -> Component {
+        match self {
+            FactorionError::Config { .. } | FactorionError::Usage(_) => Component::Config,
+            FactorionError::Http(_)
+            | FactorionError::Header(_)
+            | FactorionError::Rejected(_)
+            | FactorionError::Unavailable(_)
+            | FactorionError::Response(_) => Component::RedditApi,
+            FactorionError::Upload(_) => Component::Paste,
+            FactorionError::Io { .. } => Component::Storage,
+        }
+    }
+
+    /// Logs the error together with its kind, component and whether it is retryable,
+    /// and counts it for the alerts, see [`crate::alert`].
+    pub(crate) fn log(&self, context: &str) {
+        crate::alert::record(self, context);
+        eprintln!(
+            "{context}: {self} [kind={}, component={:?}, retryable={}]",
+            self.kind(),
+            self.component(),
+            self.is_retryable()
+        );
+    }
+}
+
+/// Why a comment could not be parsed.
+#[derive(Debug, Error)]
+pub(crate) enum ParseError {
+    /// The regex engine gave up on the comment, e.g. because of catastrophic backtracking.
+    #[error("Comment is too complex to parse: {0}")]
+    TooComplex(fancy_regex::RuntimeError),
+    #[error("Failed to parse comment: {0}")]
+    Regex(Box<fancy_regex::Error>),
+}
+
+impl From<fancy_regex::Error> for ParseError {
+    fn from(error: fancy_regex::Error) -> Self {
+        match error {
+            fancy_regex::Error::RuntimeError(
+                error @ (fancy_regex::RuntimeError::BacktrackLimitExceeded
+                | fancy_regex::RuntimeError::StackOverflow),
+            ) => ParseError::TooComplex(error),
+            _ => ParseError::Regex(Box::new(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_classification() {
+        let error = FactorionError::config("FACTORIAL_STRICTNESS", "Unknown strictness level: x");
+        assert_eq!(
+            error.to_string(),
+            "Invalid configuration in FACTORIAL_STRICTNESS: Unknown strictness level: x"
+        );
+        assert_eq!(error.kind(), "config");
+        assert_eq!(error.component(), Component::Config);
+        assert!(!error.is_retryable());
+
+        let error = FactorionError::Unavailable("RATELIMIT".to_string());
+        assert_eq!(error.kind(), "unavailable");
+        assert_eq!(error.component(), Component::RedditApi);
+        assert!(error.is_retryable());
+        assert!(!error.is_ban());
+        let error = FactorionError::Rejected("DELETED_COMMENT".to_string());
+        assert_eq!(error.kind(), "rejected");
+        assert!(!error.is_retryable());
+        assert!(!error.is_ban());
+        assert!(FactorionError::Rejected("403 Forbidden".to_string()).is_ban());
+        assert!(FactorionError::Rejected(
+            "USER_BANNED You've been banned from this community".to_string()
+        )
+        .is_ban());
+
+        let error: FactorionError = serde_json::from_str::<u32>("x").unwrap_err().into();
+        assert_eq!(error.kind(), "response");
+        assert!(!error.is_retryable());
+
+        let error = FactorionError::Io {
+            path: "comment_ids.txt",
+            source: std::io::Error::other("disk full"),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Failed to access comment_ids.txt: disk full"
+        );
+        assert_eq!(error.component(), Component::Storage);
+
+        let error = FactorionError::Upload("Not a link: nope".to_string());
+        assert_eq!(error.kind(), "upload");
+        assert_eq!(error.component(), Component::Paste);
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_parse_error_classification() {
+        let error: ParseError =
+            fancy_regex::Error::RuntimeError(fancy_regex::RuntimeError::BacktrackLimitExceeded)
+                .into();
+        assert!(matches!(error, ParseError::TooComplex(_)));
+
+        let error: ParseError = fancy_regex::Regex::new("(").unwrap_err().into();
+        assert!(matches!(error, ParseError::Regex(_)));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::reddit_comment::{FOOTER_TEXT, MAX_FOOTER_LENGTH};
+
+/// Rotates the contact mentioned in the footer of the replies between the maintainers,
+/// so questions don't all end up with the same person.
+#[derive(Debug)]
+pub(crate) struct FooterRotation {
+    contacts: Vec<String>,
+    usage: Vec<u64>,
+    next: usize,
+}
+
+impl FooterRotation {
+    /// Creates a rotation from a comma separated list of contacts.
+    /// A contact can be a user (`u/name`), a subreddit (`r/name`) or a link, e.g. to the GitHub issues.
+    pub(crate) fn new(contacts: &str) -> Result<Self, String> {
+        let contacts: Vec<String> = contacts
+            .split(',')
+            .map(str::trim)
+            .filter(|contact| !contact.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        for contact in contacts.iter() {
+            if FooterRotation::footer_for(contact).len() > MAX_FOOTER_LENGTH {
+                return Err(format!("Footer contact is too long: {contact}"));
+            }
+        }
+
+        Ok(Self {
+            usage: vec![0; contacts.len()],
+            contacts,
+            next: 0,
+        })
+    }
+
+    /// Returns the footer for the next reply. Without any contacts this is the default footer.
+    pub(crate) fn next_footer(&mut self) -> String {
+        if self.contacts.is_empty() {
+            return FOOTER_TEXT.to_string();
+        }
+
+        let index = self.next;
+        self.next = (self.next + 1) % self.contacts.len();
+        self.usage[index] += 1;
+
+        let contact = &self.contacts[index];
+        println!(
+            "Footer contact: {} (used {} times)",
+            contact, self.usage[index]
+        );
+        FooterRotation::footer_for(contact)
+    }
+
+    fn footer_for(contact: &str) -> String {
+        let how_to_contact = if contact.starts_with("u/") || contact.starts_with("/u/") {
+            format!("DM {contact}")
+        } else if contact.starts_with("r/") || contact.starts_with("/r/") {
+            format!("post in {contact}")
+        } else {
+            format!("open an issue at {contact}")
+        };
+        format!(
+            "\n*^(This action was performed by a bot. If you have any questions, please {how_to_contact}.)*"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_footer_without_contacts() {
+        let mut rotation = FooterRotation::new("").unwrap();
+        assert_eq!(rotation.next_footer(), FOOTER_TEXT);
+        assert_eq!(rotation.next_footer(), FOOTER_TEXT);
+    }
+
+    #[test]
+    fn test_footer_rotation() {
+        let mut rotation = FooterRotation::new(
+            "u/tolik518, r/factorion ,https://github.com/tolik518/factorion-bot/issues",
+        )
+        .unwrap();
+        assert_eq!(
+            rotation.next_footer(),
+            "\n*^(This action was performed by a bot. If you have any questions, please DM u/tolik518.)*"
+        );
+        assert_eq!(
+            rotation.next_footer(),
+            "\n*^(This action was performed by a bot. If you have any questions, please post in r/factorion.)*"
+        );
+        assert_eq!(
+            rotation.next_footer(),
+            "\n*^(This action was performed by a bot. If you have any questions, please open an issue at https://github.com/tolik518/factorion-bot/issues.)*"
+        );
+        assert!(rotation.next_footer().contains("u/tolik518"));
+        assert_eq!(rotation.usage, vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn test_footer_contact_too_long() {
+        let contact = format!("https://example.com/{}", "a".repeat(MAX_FOOTER_LENGTH));
+        assert!(FooterRotation::new(&contact).is_err());
+    }
+}
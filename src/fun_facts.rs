@@ -0,0 +1,94 @@
+use crate::reddit_comment::plural;
+
+/// The number of atoms in the observable universe is about 10^80, so it has 81 digits.
+const ATOMS_IN_UNIVERSE_DIGITS: u64 = 81;
+/// How many digits someone reads aloud per second.
+const DIGITS_READ_PER_SECOND: u64 = 3;
+/// How many digits fit onto a printed page.
+const DIGITS_PER_PAGE: u64 = 3_000;
+
+const DURATIONS: [(&str, u64); 4] = [
+    ("year", 365 * 24 * 60 * 60),
+    ("day", 24 * 60 * 60),
+    ("hour", 60 * 60),
+    ("minute", 60),
+];
+
+/// Comparisons making a number with that many digits tangible, like how long it takes to read it aloud.
+/// Small numbers get none.
+pub(crate) fn fun_facts(digits: u64) -> Vec<String> {
+    let mut facts = vec![];
+    if digits > ATOMS_IN_UNIVERSE_DIGITS {
+        facts.push("is bigger than the number of atoms in the observable universe".to_string());
+    }
+    let seconds = digits / DIGITS_READ_PER_SECOND;
+    if let Some((unit, length)) = DURATIONS.iter().find(|(_, length)| seconds >= *length) {
+        facts.push(format!(
+            "would take about {} to read aloud",
+            plural(seconds / length, unit)
+        ));
+    }
+    let pages = digits / DIGITS_PER_PAGE;
+    if pages > 0 {
+        facts.push(format!("would fill about {}", plural(pages, "page")));
+    }
+    facts
+}
+
+/// Joins the facts into a sentence about `subject`, like "X is …, would … and would …".
+pub(crate) fn sentence(subject: &str, facts: &[String]) -> Option<String> {
+    let (last, rest) = facts.split_last()?;
+    if rest.is_empty() {
+        return Some(format!("{subject} {last}"));
+    }
+    Some(format!("{subject} {} and {last}", rest.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fun_facts() {
+        assert!(fun_facts(20).is_empty());
+        assert_eq!(
+            fun_facts(200),
+            vec![
+                "is bigger than the number of atoms in the observable universe",
+                "would take about 1 minute to read aloud"
+            ]
+        );
+        assert_eq!(
+            fun_facts(5_565_709),
+            vec![
+                "is bigger than the number of atoms in the observable universe",
+                "would take about 21 days to read aloud",
+                "would fill about 1855 pages"
+            ]
+        );
+        assert_eq!(
+            fun_facts(u64::MAX)[1],
+            "would take about 194980805785 years to read aloud"
+        );
+    }
+
+    #[test]
+    fn test_sentence() {
+        assert_eq!(sentence("It", &[]), None);
+        assert_eq!(
+            sentence("It", &["is big".to_string()]),
+            Some("It is big".to_string())
+        );
+        assert_eq!(
+            sentence(
+                "It",
+                &[
+                    "is big".to_string(),
+                    "is long".to_string(),
+                    "is wide".to_string()
+                ]
+            ),
+            Some("It is big, is long and is wide".to_string())
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use rand::Rng;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const MAX_REDDIT_COMMENT_LENGTH: usize = 10_000;
+
+/// Openings a reply can start with, so not every reply looks exactly the same.
+const GREETINGS: [&str; 5] = [
+    "Here you go:",
+    "Sure, here it is:",
+    "I did the math:",
+    "Let me work that out for you:",
+    "Here are the numbers:",
+];
+
+/// Randomness added to replies, so they don't come in the mechanical cadence auto-moderators look for.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ReplyJitter {
+    /// Shortest extra wait before replying.
+    pub(crate) min_delay: Duration,
+    /// Longest extra wait before replying.
+    pub(crate) max_delay: Duration,
+    /// Start replies with a randomly chosen greeting.
+    pub(crate) greetings: bool,
+}
+
+impl ReplyJitter {
+    /// Parses the jitter of a subreddit, written like `5-30` (seconds) or `5-30:greetings`.
+    fn parse(config: &str) -> Result<Self, String> {
+        let (delays, options) = config.split_once(':').unwrap_or((config, ""));
+        let (min, max) = delays
+            .split_once('-')
+            .ok_or(format!("Missing '-' between the delays in: {config}"))?;
+        let parse_seconds = |seconds: &str| {
+            seconds
+                .trim()
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .map_err(|_| format!("Invalid delay: {seconds}"))
+        };
+        let (min_delay, max_delay) = (parse_seconds(min)?, parse_seconds(max)?);
+        if min_delay > max_delay {
+            return Err(format!("Minimum delay is above the maximum in: {config}"));
+        }
+
+        let greetings = match options.trim() {
+            "" => false,
+            "greetings" => true,
+            option => return Err(format!("Unknown jitter option: {option}")),
+        };
+
+        Ok(Self {
+            min_delay,
+            max_delay,
+            greetings,
+        })
+    }
+
+    /// A random wait between the minimum and maximum delay.
+    pub(crate) fn delay(&self, rng: &mut impl Rng) -> Duration {
+        rng.gen_range(self.min_delay..=self.max_delay)
+    }
+
+    /// Puts a random greeting in front of the reply, if enabled and it still fits into a comment.
+    pub(crate) fn greet(&self, reply: String, rng: &mut impl Rng) -> String {
+        if !self.greetings {
+            return reply;
+        }
+        let greeting = GREETINGS[rng.gen_range(0..GREETINGS.len())];
+        let greeted = format!("{greeting}\n\n{reply}");
+        if greeted.len() > MAX_REDDIT_COMMENT_LENGTH {
+            return reply;
+        }
+        greeted
+    }
+}
+
+/// The jitter of every subreddit, `*` is used for all subreddits without their own.
+#[derive(Debug, Default)]
+pub(crate) struct SubredditJitter(HashMap<String, ReplyJitter>);
+
+impl SubredditJitter {
+    /// Parses the jitter per subreddit, written like `mathmemes:5-30:greetings;*:1-5`.
+    /// Subreddit names are stored in lowercase.
+    pub(crate) fn parse(config: &str) -> Result<Self, String> {
+        config
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (subreddit, jitter) = entry
+                    .split_once(':')
+                    .ok_or(format!("Missing ':' after subreddit in: {entry}"))?;
+                Ok((subreddit.trim().to_lowercase(), ReplyJitter::parse(jitter)?))
+            })
+            .collect::<Result<_, String>>()
+            .map(SubredditJitter)
+    }
+
+    pub(crate) fn for_subreddit(&self, subreddit: &str) -> ReplyJitter {
+        self.0
+            .get(&subreddit.to_lowercase())
+            .or_else(|| self.0.get("*"))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_parse_subreddit_jitter() {
+        let jitter = SubredditJitter::parse("MathMemes:5-30:greetings; *:1-5").unwrap();
+        assert_eq!(
+            jitter.for_subreddit("mathmemes"),
+            ReplyJitter {
+                min_delay: Duration::from_secs(5),
+                max_delay: Duration::from_secs(30),
+                greetings: true,
+            }
+        );
+        assert_eq!(
+            jitter.for_subreddit("askmath"),
+            ReplyJitter {
+                min_delay: Duration::from_secs(1),
+                max_delay: Duration::from_secs(5),
+                greetings: false,
+            }
+        );
+        assert_eq!(
+            SubredditJitter::parse("mathmemes:1-2")
+                .unwrap()
+                .for_subreddit("askmath"),
+            ReplyJitter::default()
+        );
+
+        assert!(SubredditJitter::parse("mathmemes").is_err());
+        assert!(SubredditJitter::parse("mathmemes:5").is_err());
+        assert!(SubredditJitter::parse("mathmemes:30-5").is_err());
+        assert!(SubredditJitter::parse("mathmemes:5-30:loud").is_err());
+    }
+
+    #[test]
+    fn test_jitter_delay_is_bounded() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let jitter = ReplyJitter::parse("2-4").unwrap();
+        for _ in 0..100 {
+            let delay = jitter.delay(&mut rng);
+            assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+        }
+        assert_eq!(ReplyJitter::default().delay(&mut rng), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_jitter_greet() {
+        let mut rng = StdRng::seed_from_u64(42);
+        assert_eq!(
+            ReplyJitter::default().greet("Factorial of 5 is 120".to_string(), &mut rng),
+            "Factorial of 5 is 120"
+        );
+
+        let jitter = ReplyJitter::parse("0-0:greetings").unwrap();
+        let reply = jitter.greet("Factorial of 5 is 120".to_string(), &mut rng);
+        assert!(GREETINGS
+            .iter()
+            .any(|greeting| reply == format!("{greeting}\n\nFactorial of 5 is 120")));
+
+        let long_reply = "1".repeat(MAX_REDDIT_COMMENT_LENGTH - 5);
+        assert_eq!(jitter.greet(long_reply.clone(), &mut rng), long_reply);
+    }
+}
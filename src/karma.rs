@@ -0,0 +1,160 @@
+use crate::reddit_api::OwnComment;
+use std::collections::{BTreeSet, HashMap};
+
+/// How often the scores of the bot's replies are checked, in seconds.
+pub(crate) const KARMA_CHECK_INTERVAL: u64 = 60 * 60;
+/// Replies younger than this, in seconds, haven't been voted on enough to count.
+const SCORE_SETTLE_TIME: u64 = 60 * 60;
+/// How many scored replies a subreddit needs before it can be throttled, so one bad reply doesn't.
+const MIN_SCORED_REPLIES: usize = 5;
+
+/// Stops answering comments in subreddits where the replies of the bot are consistently downvoted,
+/// only answering mentions there until the scores recover.
+/// The threshold is the average score of the recent replies, per subreddit,
+/// written like `mathmemes:0;*:-1` with `*` for all subreddits without their own.
+#[derive(Debug, Default)]
+pub(crate) struct KarmaThrottle {
+    thresholds: HashMap<String, f64>,
+    /// Lowercase names of the subreddits in which only mentions are answered.
+    summon_only: BTreeSet<String>,
+}
+
+impl KarmaThrottle {
+    /// Subreddit names are stored in lowercase. An empty config throttles nothing.
+    pub(crate) fn parse(config: &str) -> Result<Self, String> {
+        let thresholds = config
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (subreddit, threshold) = entry
+                    .split_once(':')
+                    .ok_or(format!("Missing ':' after subreddit in: {entry}"))?;
+                let threshold = threshold
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|threshold| threshold.is_finite())
+                    .ok_or(format!("Invalid score: {threshold}"))?;
+                Ok((subreddit.trim().to_lowercase(), threshold))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self {
+            thresholds,
+            summon_only: BTreeSet::new(),
+        })
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.thresholds.is_empty()
+    }
+
+    pub(crate) fn is_summon_only(&self, subreddit: &str) -> bool {
+        self.summon_only.contains(&subreddit.to_lowercase())
+    }
+
+    fn threshold(&self, subreddit: &str) -> Option<f64> {
+        self.thresholds
+            .get(subreddit)
+            .or_else(|| self.thresholds.get("*"))
+            .copied()
+    }
+
+    /// Decides again which subreddits are summon-only, from the recent replies of the bot at unix time `now`.
+    /// Subreddits without enough scored replies keep their state.
+    /// Returns whether that changed for any subreddit.
+    pub(crate) fn update(&mut self, replies: &[OwnComment], now: u64) -> bool {
+        let mut scores: HashMap<&str, Vec<i64>> = HashMap::new();
+        for reply in replies {
+            if reply.created + SCORE_SETTLE_TIME <= now {
+                scores
+                    .entry(&reply.subreddit)
+                    .or_default()
+                    .push(reply.score);
+            }
+        }
+        let mut changed = false;
+        for (subreddit, scores) in scores {
+            if scores.len() < MIN_SCORED_REPLIES {
+                continue;
+            }
+            let average = scores.iter().sum::<i64>() as f64 / scores.len() as f64;
+            changed |= match self.threshold(subreddit) {
+                Some(threshold) if average < threshold => {
+                    self.summon_only.insert(subreddit.to_string())
+                }
+                _ => self.summon_only.remove(subreddit),
+            };
+        }
+        changed
+    }
+
+    /// The subreddits in which only mentions are answered, for the log.
+    pub(crate) fn summon_only(&self) -> impl Iterator<Item = &str> {
+        self.summon_only.iter().map(String::as_str)
+    }
+}
+
+/// The replies scoring `score` or lower, to be deleted. A `score` of 0 or more never deletes anything.
+pub(crate) fn downvoted(replies: &[OwnComment], score: i64) -> impl Iterator<Item = &OwnComment> {
+    replies
+        .iter()
+        .filter(move |reply| score < 0 && reply.score <= score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(subreddit: &str, score: i64, created: u64) -> OwnComment {
+        OwnComment {
+            id: "abc".to_string(),
+            subreddit: subreddit.to_string(),
+            score,
+            created,
+        }
+    }
+
+    #[test]
+    fn test_parse_karma_throttle() {
+        let throttle = KarmaThrottle::parse("MathMemes:0; *:-1.5").unwrap();
+        assert_eq!(throttle.threshold("mathmemes"), Some(0.0));
+        assert_eq!(throttle.threshold("askmath"), Some(-1.5));
+        assert!(throttle.is_enabled());
+        assert!(!KarmaThrottle::parse("").unwrap().is_enabled());
+        assert!(KarmaThrottle::parse("mathmemes").is_err());
+        assert!(KarmaThrottle::parse("mathmemes:low").is_err());
+        assert!(KarmaThrottle::parse("mathmemes:NaN").is_err());
+    }
+
+    #[test]
+    fn test_karma_throttle_update() {
+        let mut throttle = KarmaThrottle::parse("mathmemes:0").unwrap();
+        let now = 100_000;
+        let mut replies: Vec<_> = (0..5).map(|_| reply("mathmemes", -2, 0)).collect();
+        // Too few in askmath, and too fresh in funny
+        replies.push(reply("askmath", -10, 0));
+        replies.extend((0..5).map(|_| reply("funny", -10, now)));
+        assert!(throttle.update(&replies, now));
+        assert!(throttle.is_summon_only("MathMemes"));
+        assert!(!throttle.is_summon_only("askmath"));
+        assert!(!throttle.is_summon_only("funny"));
+        assert!(!throttle.update(&replies, now));
+
+        // Without a threshold for everything else, only mathmemes is throttled
+        let replies: Vec<_> = (0..5).map(|_| reply("funny", -10, 0)).collect();
+        assert!(!throttle.update(&replies, now));
+
+        let replies: Vec<_> = (0..5).map(|_| reply("mathmemes", 3, 0)).collect();
+        assert!(throttle.update(&replies, now));
+        assert_eq!(throttle.summon_only().count(), 0);
+    }
+
+    #[test]
+    fn test_downvoted() {
+        let replies = vec![reply("mathmemes", -5, 0), reply("mathmemes", -4, 0)];
+        assert_eq!(downvoted(&replies, -5).count(), 1);
+        assert_eq!(downvoted(&replies, -4).count(), 2);
+        assert_eq!(downvoted(&replies, 0).count(), 0);
+    }
+}
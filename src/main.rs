@@ -1,44 +1,111 @@
-use reddit_api::RedditClient;
-use reddit_comment::Status;
+use admin::{AdminCommand, AdminState};
+use alert::Webhook;
+use bot_stats::BotStats;
+use budget::ReplyBudget;
+use cli::{CliCommand, Outcome};
+use config::{Config, ConfigWatcher, CONFIG_FILE_PATH};
+use error::FactorionError;
+use footer::FooterRotation;
+use jitter::SubredditJitter;
+use karma::{KarmaThrottle, KARMA_CHECK_INTERVAL};
+use moderation::{BannedSubreddits, ModAction, ModRequest, PausedSubreddits};
+use paste::PasteService;
+use preferences::UserPreferences;
+use reddit_api::{ParseSettings, RedditClient, ReplyChainError};
+use reddit_comment::{RedditComment, Status, FOOTER_TEXT};
+use replied::RepliedIds;
+use retry::RetryQueue;
+use shadow::{ShadowMode, ShadowReply};
 use std::collections::HashSet;
-use std::error::Error;
-use std::fs::{self, OpenOptions};
+use std::fs::OpenOptions;
 use std::io::Write;
 use std::time::SystemTime;
 use time::OffsetDateTime;
 use tokio::time::{sleep, Duration};
 
+mod admin;
+mod alert;
+mod batch;
+mod bfile;
+mod bot_stats;
+mod budget;
+mod cache;
+mod cli;
+mod commands;
+mod config;
+mod error;
+mod footer;
+mod fun_facts;
+mod jitter;
+mod karma;
 mod math;
+mod moderation;
+mod parser_stats;
+mod paste;
+mod preferences;
+mod rate_limit;
 mod reddit_api;
 pub(crate) mod reddit_comment;
+mod replied;
+mod retry;
+mod shadow;
 
 const API_COMMENT_COUNT: u32 = 100;
-const COMMENT_IDS_FILE_PATH: &str = "comment_ids.txt";
+// Every reply the bot posted, so they can be found again to be deleted
+const REPLY_IDS_FILE_PATH: &str = "reply_ids.txt";
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let mut reddit_client = RedditClient::new().await?;
-    let subreddits = std::env::var("SUBREDDITS").expect("SUBREDDITS must be set.");
-    let subreddits = subreddits.as_str();
-
-    let sleep_between_requests =
-        std::env::var("SLEEP_BETWEEN_REQUESTS").expect("SLEEP_BETWEEN_REQUESTS must be set.");
-    let sleep_between_requests = sleep_between_requests.as_str().parse().unwrap();
+async fn main() -> Result<(), FactorionError> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let errors_json = cli::take_flag(&mut args, "--errors-json");
+    let command = match CliCommand::parse(&args) {
+        Ok(command) => command,
+        Err(e) => cli::exit(Err(e), errors_json),
+    };
+    if let Some(result) = run_command(&command) {
+        cli::exit(result, errors_json);
+    }
 
-    // read comment_ids from the file
-    let already_replied_to_comments: String =
-        fs::read_to_string(COMMENT_IDS_FILE_PATH).unwrap_or("".to_string());
+    let mut config = Config::load()?;
+    if command == CliCommand::PrintConfig {
+        print!("{}", config.to_toml());
+        return Ok(());
+    }
 
-    if already_replied_to_comments.is_empty() {
-        println!("No comment_ids found in the file");
-    } else {
-        println!("Found comment_ids in the file");
+    cache::set_factorial_cache_size(config.parsing.calculation_cache_size);
+    let alert_webhook = Webhook::new(&config.reddit.alert_webhook)?;
+    if let Some(webhook) = &alert_webhook {
+        webhook.alert_on_panic();
     }
+    let mut reddit_client = RedditClient::new(&config).await?;
+    let mut config_watcher = ConfigWatcher::new(CONFIG_FILE_PATH);
+
+    let mut footer_rotation = FooterRotation::new(&config.replies.footer_contacts)
+        .map_err(|e| FactorionError::config("FOOTER_CONTACTS", e))?;
+    let mut subreddit_jitter = SubredditJitter::parse(&config.replies.reply_jitter)
+        .map_err(|e| FactorionError::config("REPLY_JITTER", e))?;
+    let mut shadow_mode = ShadowMode::new(&config.reddit.shadow_subreddits);
+    let mut karma_throttle = KarmaThrottle::parse(&config.reddit.karma_throttle)
+        .map_err(|e| FactorionError::config("KARMA_THROTTLE", e))?;
+    let mut last_karma_check: u64 = 0;
+    let uploader = PasteService::parse(&config.replies.paste_service)
+        .and_then(|service| service.uploader(std::env::var("GITHUB_TOKEN").ok()))
+        .map_err(|e| FactorionError::config("PASTE_SERVICE", e))?;
 
-    let mut already_replied_to_comments: Vec<String> = already_replied_to_comments
-        .lines()
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
+    let mut already_replied_to_comments = RepliedIds::load()?;
+    println!(
+        "Found {} comment_ids in the file",
+        already_replied_to_comments.len()
+    );
+
+    let mut skipped_locked_count: u64 = 0;
+    let mut user_preferences = UserPreferences::load()?;
+    let mut paused_subreddits = PausedSubreddits::load()?;
+    let mut banned_subreddits = BannedSubreddits::load()?;
+    let mut retry_queue = RetryQueue::load()?;
+    let mut reply_budget = ReplyBudget::load()?;
+    let mut bot_stats = BotStats::load()?;
+    let mut admin = AdminState::load()?;
 
     // Polling Reddit for new comments
     loop {
@@ -49,24 +116,205 @@ async fn main() -> Result<(), Box<dyn Error>> {
             today.time()
         );
 
-        let comments = reddit_client
-            .get_comments(subreddits, API_COMMENT_COUNT, &already_replied_to_comments)
-            .await
-            .unwrap_or_default();
+        match &alert_webhook {
+            Some(webhook) => webhook.send_pending().await,
+            None => drop(alert::take_pending()),
+        }
+
+        handle_owner_messages(
+            &mut reddit_client,
+            &config,
+            &mut admin,
+            &mut paused_subreddits,
+            &already_replied_to_comments,
+            &retry_queue,
+        )
+        .await;
+
+        // Picks up changes to config.toml, keeping the old config if the new one is invalid
+        if config_watcher.changed() || std::mem::take(&mut admin.reload_config) {
+            match reload_config() {
+                Ok((new_config, settings, jitter, throttle)) => {
+                    reddit_client.set_settings(settings);
+                    subreddit_jitter = jitter;
+                    karma_throttle = throttle;
+                    last_karma_check = 0;
+                    shadow_mode = ShadowMode::new(&new_config.reddit.shadow_subreddits);
+                    cache::set_factorial_cache_size(new_config.parsing.calculation_cache_size);
+                    config = new_config;
+                    println!("Reloaded {CONFIG_FILE_PATH}");
+                }
+                Err(e) => e.log("Failed to reload the config, keeping the old one"),
+            }
+        }
+        if admin.paused {
+            println!("Paused by an owner, waiting for `resume`");
+            sleep(Duration::from_secs(config.reddit.sleep_between_requests)).await;
+            continue;
+        }
+        let max_reply_chain_length = config.replies.max_reply_chain_length;
 
-        println!("Found {} comments", comments.len());
+        let delete_at_score = config.replies.delete_replies_at_score;
+        if (karma_throttle.is_enabled() || delete_at_score < 0)
+            && last_karma_check + KARMA_CHECK_INTERVAL <= unix_time()
+        {
+            last_karma_check = unix_time();
+            match reddit_client.get_own_comments(API_COMMENT_COUNT).await {
+                Ok(own_comments) => {
+                    if karma_throttle.update(&own_comments, unix_time()) {
+                        println!(
+                            "Only answering mentions in: {:?}",
+                            karma_throttle.summon_only().collect::<Vec<_>>()
+                        );
+                    }
+                    for reply in karma::downvoted(&own_comments, delete_at_score) {
+                        // A dry run doesn't touch anything on reddit
+                        if shadow_mode.is_shadowed(&reply.subreddit) {
+                            println!(
+                                "[shadow mode] would delete reply {} in r/{} with a score of {}",
+                                reply.id, reply.subreddit, reply.score
+                            );
+                            continue;
+                        }
+                        match reddit_client.delete_comment(&reply.id).await {
+                            Ok(()) => println!(
+                                "Deleted reply {} in r/{} with a score of {}",
+                                reply.id, reply.subreddit, reply.score
+                            ),
+                            Err(e) => e.log("Failed to delete a downvoted reply"),
+                        }
+                    }
+                }
+                Err(e) => {
+                    e.log("Failed to check the scores of the replies");
+                    // Unlike reddit being down, this won't go away by itself
+                    if !e.is_retryable() {
+                        eprintln!(
+                        "Error: Without the scores of its replies, the bot can't throttle itself or delete downvoted replies. The token needs the history scope."
+                        );
+                    }
+                }
+            }
+        }
+
+        retry_replies(
+            &reddit_client,
+            &mut retry_queue,
+            &mut already_replied_to_comments,
+        )
+        .await;
+
+        let mentions = if config.reddit.check_mentions {
+            reddit_client
+                .get_mentions(
+                    API_COMMENT_COUNT,
+                    &already_replied_to_comments,
+                    &mut user_preferences,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    e.log("Failed to get the mentions");
+                    vec![]
+                })
+        } else {
+            vec![]
+        };
+        let mention_ids: Vec<String> = mentions.iter().map(|m| m.id.clone()).collect();
+
+        // Subreddits the bot got banned from aren't polled anymore
+        let active_subreddits = banned_subreddits.active_subreddits(&config.reddit.subreddits);
+        let mut comments = if active_subreddits.is_empty() {
+            vec![]
+        } else {
+            reddit_client
+                .get_comments(
+                    &active_subreddits,
+                    API_COMMENT_COUNT,
+                    &already_replied_to_comments,
+                    &mut user_preferences,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    e.log("Failed to get the comments");
+                    vec![]
+                })
+        };
+        // Subreddits downvoting the replies only get them when they ask for them
+        comments.retain(|comment| !karma_throttle.is_summon_only(&comment.subreddit));
+        let top_level_only = config.top_level_only_subreddits();
+        comments.retain(|comment| {
+            comment.is_top_level() || !top_level_only.contains(&comment.subreddit)
+        });
 
-        for comment in comments {
+        println!(
+            "Found {} comments and {} mentions",
+            comments.len(),
+            mentions.len()
+        );
+        println!("{}", reddit_client.take_parser_stats());
+
+        // Mentions go first, as someone is explicitly waiting for them
+        let comments = mentions.into_iter().chain(batch::batch_by_author(comments));
+
+        for mut comment in comments {
             let comment_id = comment.id.clone();
             let status_set: HashSet<_> = comment.status.iter().cloned().collect();
             let should_answer = status_set.contains(&Status::FactorialsFound)
                 && status_set.contains(&Status::NotReplied);
 
+            if let Some(request) = ModRequest::parse(&comment.parsed_body, &comment.subreddit) {
+                // Confirming would mean posting a reply
+                if shadow_mode.is_shadowed(&comment.subreddit) {
+                    if status_set.contains(&Status::NotReplied) {
+                        println!(
+                            "Comment ID {} -> [shadow mode] ignoring {:?} in r/{}",
+                            comment.id, request.action, request.subreddit
+                        );
+                        // Otherwise it would be ignored again on every poll
+                        already_replied_to_comments.push(comment_id);
+                    }
+                    continue;
+                }
+                if status_set.contains(&Status::NotReplied) {
+                    handle_mod_request(
+                        &mut reddit_client,
+                        &mut paused_subreddits,
+                        &mut already_replied_to_comments,
+                        comment,
+                        &request,
+                    )
+                    .await;
+                }
+                continue;
+            }
+            if paused_subreddits.is_paused(&comment.subreddit) {
+                continue;
+            }
+            if mention_ids.contains(&comment.id)
+                && status_set.contains(&Status::NotReplied)
+                && BotStats::is_request(&comment.parsed_body)
+                && !shadow_mode.is_shadowed(&comment.subreddit)
+            {
+                answer_stats_request(
+                    &reddit_client,
+                    &bot_stats,
+                    &mut already_replied_to_comments,
+                    &comment.id,
+                )
+                .await;
+                continue;
+            }
+
             if status_set.contains(&Status::NoFactorial) {
                 continue;
             }
 
-            print!("Comment ID {} -> {:?}", comment.id, comment.status);
+            print!(
+                "Comment ID {} {:?} -> {:?}",
+                comment.id,
+                comment.sources(),
+                comment.status
+            );
 
             if status_set.contains(&Status::NumberTooBig) {
                 println!(" -> {:?}", comment.factorial_list);
@@ -77,14 +325,111 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 println!(" [already replied] ");
                 continue;
             }
+            if status_set.contains(&Status::ThreadLocked) {
+                skipped_locked_count += 1;
+                println!(" [thread locked] (skipped {skipped_locked_count} in total)");
+                continue;
+            }
             if status_set.contains(&Status::FactorialsFound) {
                 println!(" -> {:?}", comment.factorial_list);
             }
+            if should_answer && shadow_mode.is_shadowed(&comment.subreddit) {
+                let replies =
+                    comment.get_reply_chain_with_footer(FOOTER_TEXT, max_reply_chain_length);
+                let shadow_reply = ShadowReply {
+                    comment_id: comment_id.clone(),
+                    subreddit: comment.subreddit.clone(),
+                    reply: replies.join("\n\n---\n\n"),
+                };
+                match shadow_mode.store(&shadow_reply) {
+                    Ok(()) => {
+                        println!(" [shadow mode] stored reply");
+                        already_replied_to_comments.push(comment_id.clone());
+                        already_replied_to_comments.extend(comment.merged_ids);
+                    }
+                    Err(e) => e.log("Failed to store shadow reply"),
+                }
+                continue;
+            }
+            if should_answer
+                && !reply_budget.allows(
+                    &comment.author,
+                    config.replies.max_replies_per_user,
+                    unix_time(),
+                )
+            {
+                println!(" [reply budget of {} used up]", comment.author);
+                already_replied_to_comments.push(comment_id.clone());
+                already_replied_to_comments.extend(comment.merged_ids);
+                continue;
+            }
             if should_answer {
-                let reply: String = comment.get_reply();
-                match reddit_client.reply_to_comment(comment, &reply).await {
-                    Ok(_) => already_replied_to_comments.push(comment_id.clone()),
-                    Err(e) => eprintln!("Failed to reply to comment: {:?}", e),
+                if let (Some(uploader), Some(text)) =
+                    (&uploader, comment.get_paste_text(max_reply_chain_length))
+                {
+                    match uploader.upload(&text).await {
+                        Ok(link) => comment.paste_link = Some(link),
+                        Err(e) => e.log("Failed to upload the results"),
+                    }
+                }
+                let jitter = subreddit_jitter.for_subreddit(&comment.subreddit);
+                let mut replies = comment.get_reply_chain_with_footer(
+                    &footer_rotation.next_footer(),
+                    max_reply_chain_length,
+                );
+                replies[0] = jitter.greet(std::mem::take(&mut replies[0]), &mut rand::thread_rng());
+                sleep(jitter.delay(&mut rand::thread_rng())).await;
+                let subreddit = comment.subreddit.clone();
+                match reddit_client.reply_to_comment(&comment_id, &replies).await {
+                    Ok(reply_ids) => {
+                        banned_subreddits.record_success(&subreddit);
+                        reply_budget.record(&comment.author, unix_time());
+                        bot_stats.record(&comment);
+                        already_replied_to_comments.push(comment_id.clone());
+                        already_replied_to_comments.extend(comment.merged_ids);
+                        // Never answer our own replies
+                        already_replied_to_comments.extend(reply_ids.iter().cloned());
+                        if let Err(e) = store_reply_ids(&reply_ids) {
+                            e.log("Failed to store reply ids");
+                        }
+                    }
+                    Err(ReplyChainError {
+                        posted_ids,
+                        error: e,
+                    }) => {
+                        e.log("Failed to reply to comment");
+                        if !posted_ids.is_empty() {
+                            // Part of the chain is posted, so the comment is answered
+                            already_replied_to_comments.push(comment_id.clone());
+                            already_replied_to_comments.extend(comment.merged_ids.iter().cloned());
+                            already_replied_to_comments.extend(posted_ids.iter().cloned());
+                            if let Err(e) = store_reply_ids(&posted_ids) {
+                                e.log("Failed to store reply ids");
+                            }
+                        }
+                        if e.is_ban() {
+                            if banned_subreddits.record_ban(&subreddit) {
+                                eprintln!(
+                                    "Warning: Banned from r/{subreddit}, not polling it anymore"
+                                );
+                                if let Err(e) = banned_subreddits.store() {
+                                    e.log("Failed to store banned subreddits");
+                                }
+                            }
+                        } else if e.is_retryable() {
+                            // The queue answers it from now on
+                            already_replied_to_comments.push(comment_id.clone());
+                            reply_budget.record(&comment.author, unix_time());
+                            already_replied_to_comments.extend(comment.merged_ids);
+                            // The rest of the chain goes below the last reply that was posted
+                            let parent_id = posted_ids.last().unwrap_or(&comment_id).clone();
+                            replies.drain(..posted_ids.len());
+                            retry_queue.push(parent_id, subreddit, replies, unix_time());
+                            if let Err(e) = retry_queue.store() {
+                                e.log("Failed to store the retry queue");
+                            }
+                        }
+                    }
                 }
                 // Sleep to not spam comments too quickly
                 sleep(Duration::from_secs(2)).await;
@@ -93,18 +438,306 @@ async fn main() -> Result<(), Box<dyn Error>> {
             println!(" [unknown] ");
         }
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(false) // This will clear the file contents if it already exists
-            .open(COMMENT_IDS_FILE_PATH)
-            .expect("Unable to open or create file");
+        if let Err(e) = user_preferences.store() {
+            e.log("Failed to store user preferences");
+        }
+        if let Err(e) = reply_budget.store() {
+            e.log("Failed to store the reply budget");
+        }
+        if let Err(e) = bot_stats.store() {
+            e.log("Failed to store the stats");
+        }
 
-        for comment_id in already_replied_to_comments.iter() {
-            writeln!(file, "{}", comment_id).expect("Unable to write to file");
+        if config.reddit.mark_mentions_read && !mention_ids.is_empty() {
+            reddit_client.mark_read("t1", &mention_ids).await;
         }
 
+        already_replied_to_comments.store()?;
+
         // Sleep to avoid hitting API rate limits
-        sleep(Duration::from_secs(sleep_between_requests)).await;
+        sleep(Duration::from_secs(config.reddit.sleep_between_requests)).await;
+    }
+}
+
+/// Loads the config again, along with the settings built from it that can change while running.
+/// Footer contacts, the paste service and the alert webhook only change with a restart.
+fn reload_config() -> Result<(Config, ParseSettings, SubredditJitter, KarmaThrottle), FactorionError>
+{
+    let config = Config::load()?;
+    let settings = ParseSettings::from_config(&config)?;
+    let jitter = SubredditJitter::parse(&config.replies.reply_jitter)
+        .map_err(|e| FactorionError::config("REPLY_JITTER", e))?;
+    let throttle = KarmaThrottle::parse(&config.reddit.karma_throttle)
+        .map_err(|e| FactorionError::config("KARMA_THROTTLE", e))?;
+    Ok((config, settings, jitter, throttle))
+}
+
+/// Runs the commands that don't poll reddit, `None` for the bot itself and `--print-config`.
+fn run_command(command: &CliCommand) -> Option<Result<Outcome, FactorionError>> {
+    let done = |result: Result<(), FactorionError>| Some(result.map(|()| Outcome::Success));
+    match command {
+        CliCommand::ShadowReport => done(shadow::print_report()),
+        CliCommand::BFile(args) => done(bfile::print_b_file(args)),
+        CliCommand::Repl(options) => done(cli::repl(
+            options,
+            std::io::stdin().lock(),
+            std::io::stdout(),
+        )),
+        CliCommand::Batch(options) => done(cli::batch(
+            options,
+            std::io::stdin().lock(),
+            std::io::stdout().lock(),
+        )),
+        _ => match cli::run(command) {
+            Ok(Some((output, outcome))) => {
+                print!("{output}");
+                Some(Ok(outcome))
+            }
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        },
+    }
+}
+
+/// Pauses or resumes the bot in a subreddit if a moderator of it asks for it, and confirms that.
+async fn handle_mod_request(
+    reddit_client: &mut RedditClient,
+    paused_subreddits: &mut PausedSubreddits,
+    already_replied_to_comments: &mut RepliedIds,
+    comment: RedditComment,
+    request: &ModRequest,
+) {
+    match reddit_client
+        .is_moderator(&request.subreddit, &comment.author)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            println!(
+                "Comment ID {} -> {} is no moderator of r/{}, ignoring {:?}",
+                comment.id, comment.author, request.subreddit, request.action
+            );
+            already_replied_to_comments.push(comment.id);
+            return;
+        }
+        Err(e) => {
+            e.log("Failed to check the moderators");
+            return;
+        }
+    }
+    println!(
+        "Comment ID {} -> {:?} in r/{}",
+        comment.id, request.action, request.subreddit
+    );
+    let confirmation = apply_mod_request(paused_subreddits, request);
+    already_replied_to_comments.push(comment.id.clone());
+    let reply = format!("{confirmation}\n\n{FOOTER_TEXT}");
+    match reddit_client.reply_to_comment(&comment.id, &[reply]).await {
+        Ok(reply_ids) => {
+            already_replied_to_comments.extend(reply_ids.iter().cloned());
+            if let Err(e) = store_reply_ids(&reply_ids) {
+                e.log("Failed to store reply ids");
+            }
+        }
+        Err(e) => e.error.log("Failed to confirm the moderator request"),
+    }
+}
+
+/// Carries out the commands the owners of the bot sent in private messages, and answers them.
+async fn handle_owner_messages(
+    reddit_client: &mut RedditClient,
+    config: &Config,
+    admin: &mut AdminState,
+    paused_subreddits: &mut PausedSubreddits,
+    already_replied_to_comments: &RepliedIds,
+    retry_queue: &RetryQueue,
+) {
+    let owners = config.bot_owners();
+    if owners.is_empty() {
+        return;
+    }
+    let messages = match reddit_client
+        .get_owner_messages(API_COMMENT_COUNT, &owners)
+        .await
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            e.log("Failed to get the messages of the owners");
+            return;
+        }
+    };
+    let mut handled_ids = vec![];
+    for message in messages {
+        handled_ids.push(message.id.clone());
+        println!(
+            "Message ID {} -> {} sent {:?}",
+            message.id, message.author, message.body
+        );
+
+        let reply = match AdminCommand::parse(&message.body) {
+            Ok(AdminCommand::Pause) => {
+                if let Err(e) = admin.set_paused(true) {
+                    e.log("Failed to store the pause");
+                }
+                "Paused, I won't answer anything until you send `resume`.".to_string()
+            }
+            Ok(AdminCommand::Resume) => {
+                if let Err(e) = admin.set_paused(false) {
+                    e.log("Failed to store the pause");
+                }
+                "Resumed.".to_string()
+            }
+            Ok(AdminCommand::ReloadConfig) => {
+                admin.reload_config = true;
+                format!("Reloading {CONFIG_FILE_PATH}, check the logs for errors.")
+            }
+            Ok(AdminCommand::Status) => {
+                let paused_in: Vec<_> = paused_subreddits
+                    .subreddits()
+                    .map(|subreddit| format!("r/{subreddit}"))
+                    .collect();
+                format!(
+                    "{}. Polling r/{}, paused in {}. Replied to {} comments, {} replies are waiting to be retried.",
+                    if admin.paused { "Paused" } else { "Running" },
+                    config.reddit.subreddits,
+                    if paused_in.is_empty() { "none".to_string() } else { paused_in.join(", ") },
+                    already_replied_to_comments.len(),
+                    retry_queue.len()
+                )
+            }
+            Ok(AdminCommand::Disable(subreddit)) => apply_mod_request(
+                paused_subreddits,
+                &ModRequest {
+                    subreddit,
+                    action: ModAction::Pause,
+                },
+            ),
+            Ok(AdminCommand::Enable(subreddit)) => apply_mod_request(
+                paused_subreddits,
+                &ModRequest {
+                    subreddit,
+                    action: ModAction::Resume,
+                },
+            ),
+            Err(usage) => usage,
+        };
+        if let Err(e) = reddit_client.reply_to_message(&message.id, &reply).await {
+            e.log("Failed to answer the owner");
+        }
+    }
+    if !handled_ids.is_empty() {
+        reddit_client.mark_read("t4", &handled_ids).await;
+    }
+}
+
+/// Pauses or resumes the bot in a subreddit, returning the confirmation.
+fn apply_mod_request(paused_subreddits: &mut PausedSubreddits, request: &ModRequest) -> String {
+    if paused_subreddits.apply(request) {
+        if let Err(e) = paused_subreddits.store() {
+            e.log("Failed to store paused subreddits");
+        }
+    }
+    request.confirmation()
+}
+
+/// Answers a mention asking for `!stats` with the totals so far.
+async fn answer_stats_request(
+    reddit_client: &RedditClient,
+    bot_stats: &BotStats,
+    already_replied_to_comments: &mut RepliedIds,
+    comment_id: &str,
+) {
+    println!("Comment ID {comment_id} -> !stats");
+    already_replied_to_comments.push(comment_id.to_string());
+    let reply = format!("{}\n\n{FOOTER_TEXT}", bot_stats.reply());
+    match reddit_client.reply_to_comment(comment_id, &[reply]).await {
+        Ok(reply_ids) => {
+            already_replied_to_comments.extend(reply_ids.iter().cloned());
+            if let Err(e) = store_reply_ids(&reply_ids) {
+                e.log("Failed to store reply ids");
+            }
+        }
+        Err(e) => e.error.log("Failed to answer the stats request"),
+    }
+    // Sleep to not spam comments too quickly
+    sleep(Duration::from_secs(2)).await;
+}
+
+/// Posts the queued replies that are due again, queueing them once more if they fail again.
+async fn retry_replies(
+    reddit_client: &RedditClient,
+    retry_queue: &mut RetryQueue,
+    already_replied_to_comments: &mut RepliedIds,
+) {
+    let due = retry_queue.take_due(unix_time());
+    if due.is_empty() {
+        return;
+    }
+    println!(
+        "Retrying {} replies, {} still waiting",
+        due.len(),
+        retry_queue.len()
+    );
+    for mut pending in due {
+        match reddit_client
+            .reply_to_comment(&pending.comment_id, &pending.replies)
+            .await
+        {
+            Ok(reply_ids) => {
+                already_replied_to_comments.extend(reply_ids.iter().cloned());
+                if let Err(e) = store_reply_ids(&reply_ids) {
+                    e.log("Failed to store reply ids");
+                }
+            }
+            Err(ReplyChainError { posted_ids, error }) => {
+                already_replied_to_comments.extend(posted_ids.iter().cloned());
+                if let Err(e) = store_reply_ids(&posted_ids) {
+                    e.log("Failed to store reply ids");
+                }
+                if error.is_retryable() && !error.is_ban() {
+                    error.log("Failed to retry reply");
+                    // The rest of the chain goes below the last reply that was posted
+                    if let Some(parent_id) = posted_ids.last() {
+                        pending.comment_id = parent_id.clone();
+                        pending.replies.drain(..posted_ids.len());
+                    }
+                    let (comment_id, subreddit) =
+                        (pending.comment_id.clone(), pending.subreddit.clone());
+                    if !retry_queue.retry_later(pending, unix_time()) {
+                        eprintln!(
+                            "Comment ID {comment_id} in r/{subreddit} -> Giving up on the reply"
+                        );
+                    }
+                } else {
+                    error.log("Failed to retry reply, giving up");
+                }
+            }
+        }
+        sleep(Duration::from_secs(2)).await;
+    }
+    if let Err(e) = retry_queue.store() {
+        e.log("Failed to store the retry queue");
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn store_reply_ids(reply_ids: &[String]) -> Result<(), FactorionError> {
+    let io_error = |source| FactorionError::Io {
+        path: REPLY_IDS_FILE_PATH,
+        source,
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(REPLY_IDS_FILE_PATH)
+        .map_err(io_error)?;
+    for reply_id in reply_ids.iter() {
+        writeln!(file, "{}", reply_id).map_err(io_error)?;
     }
+    Ok(())
 }
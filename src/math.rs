@@ -1,5 +1,5 @@
 use num_bigint::BigInt;
-use num_traits::One;
+use num_traits::{One, Signed, ToPrimitive, Zero};
 
 pub fn factorial(n: u64, k: u64) -> BigInt {
     if n <= 1 {
@@ -8,6 +8,15 @@ pub fn factorial(n: u64, k: u64) -> BigInt {
     let i_max = (n - 1) / k;
     multifactorial_recursive(n, k, 0, i_max)
 }
+/// Continues the multifactorial `m!^(k)` up to `n!^(k)`, so `factorial(n, k) == factorial(m, k) * factorial_from(m, n, k)`.
+/// `n - m` has to be a multiple of `k`.
+pub fn factorial_from(m: u64, n: u64, k: u64) -> BigInt {
+    debug_assert!(m <= n && (n - m).is_multiple_of(k));
+    if n <= 1 || n == m {
+        return BigInt::one();
+    }
+    multifactorial_recursive(n, k, 0, (n - m) / k - 1)
+}
 fn multifactorial_recursive(n: u64, k: u64, low_i: u64, high_i: u64) -> BigInt {
     if low_i > high_i {
         One::one()
@@ -25,6 +34,383 @@ fn multifactorial_recursive(n: u64, k: u64, low_i: u64, high_i: u64) -> BigInt {
     }
 }
 
+/// Calculates the primorial, the product of all primes up to and including n.
+pub fn primorial(n: u64) -> BigInt {
+    let primes = primes_up_to(n);
+    product(&primes)
+}
+
+/// Returns all primes up to and including n, using the sieve of Eratosthenes.
+pub fn primes_up_to(n: u64) -> Vec<u64> {
+    if n < 2 {
+        return vec![];
+    }
+    let n = n as usize;
+    let mut is_prime = vec![true; n + 1];
+    is_prime[0] = false;
+    is_prime[1] = false;
+    let mut i = 2;
+    while i * i <= n {
+        if is_prime[i] {
+            for multiple in (i * i..=n).step_by(i) {
+                is_prime[multiple] = false;
+            }
+        }
+        i += 1;
+    }
+    is_prime
+        .iter()
+        .enumerate()
+        .filter(|(_, is_prime)| **is_prime)
+        .map(|(prime, _)| prime as u64)
+        .collect()
+}
+
+/// Multiplies all factors, splitting them in halves to keep the big multiplications balanced.
+fn product(factors: &[u64]) -> BigInt {
+    match factors {
+        [] => BigInt::one(),
+        [factor] => BigInt::from(*factor),
+        _ => {
+            let (left, right) = factors.split_at(factors.len() / 2);
+            product(left) * product(right)
+        }
+    }
+}
+
+/// Calculates the hyperfactorial, the product of k^k for all k up to n.
+pub fn hyperfactorial(n: u64) -> BigInt {
+    product_of(1, n, &|k| BigInt::from(k).pow(k as u32))
+}
+
+/// Calculates the superfactorial, the product of k! for all k up to n.
+/// Every k appears in n-k+1 of the factorials, so this is the product of k^(n-k+1).
+pub fn superfactorial(n: u64) -> BigInt {
+    product_of(1, n, &|k| BigInt::from(k).pow((n - k + 1) as u32))
+}
+
+/// Approximates the number of digits of the hyperfactorial of n.
+pub fn hyperfactorial_digits(n: u64) -> u64 {
+    let log10: f64 = (1..=n).map(|k| k as f64 * (k as f64).log10()).sum();
+    log10.floor() as u64 + 1
+}
+
+/// Approximates the number of digits of the superfactorial of n.
+pub fn superfactorial_digits(n: u64) -> u64 {
+    let log10: f64 = (1..=n)
+        .map(|k| (n - k + 1) as f64 * (k as f64).log10())
+        .sum();
+    log10.floor() as u64 + 1
+}
+
+/// Multiplies the factors f(k) for all k from low to high, splitting the range in halves to keep the big multiplications balanced.
+fn product_of(low: u64, high: u64, f: &dyn Fn(u64) -> BigInt) -> BigInt {
+    if low > high {
+        BigInt::one()
+    } else if low == high {
+        f(low)
+    } else {
+        let mid = low + (high - low) / 2;
+        product_of(low, mid, f) * product_of(mid + 1, high, f)
+    }
+}
+
+/// Calculates the left factorial, the sum of all k! for k < n.
+pub fn left_factorial(n: u64) -> BigInt {
+    if n == 0 {
+        return BigInt::zero();
+    }
+    // Horner scheme: 0! + 1! + ... + (n-1)! = 1 + 1 * (1 + 2 * (1 + ... * (1 + (n-1))))
+    (1..n)
+        .rev()
+        .fold(BigInt::one(), |acc, k| acc * BigInt::from(k) + 1)
+}
+
+/// Approximates the number of digits of the left factorial of n, which is dominated by (n-1)!.
+pub fn left_factorial_digits(n: u64) -> u64 {
+    let log10: f64 = (1..n).map(|k| (k as f64).log10()).sum();
+    log10.floor() as u64 + 1
+}
+
+/// Calculates the alternating factorial, n! - (n-1)! + (n-2)! - ... ± 1!.
+pub fn alternating_factorial(n: u64) -> BigInt {
+    let mut factorial = BigInt::one();
+    let mut alternating_factorial = BigInt::zero();
+    // af(k) = k! - af(k-1)
+    for k in 1..=n {
+        factorial *= k;
+        alternating_factorial = &factorial - alternating_factorial;
+    }
+    alternating_factorial
+}
+
+/// Approximates the number of digits of the alternating factorial of n, which is dominated by n!.
+pub fn alternating_factorial_digits(n: u64) -> u64 {
+    let log10: f64 = (1..=n).map(|k| (k as f64).log10()).sum();
+    log10.floor() as u64 + 1
+}
+
+/// Calculates the exponential factorial, n^(n-1)^...^2^1.
+/// Already the exponential factorial of 5 has 183231 digits, so this is only feasible up to that.
+pub fn exponential_factorial(n: u64) -> BigInt {
+    (1..=n).fold(BigInt::one(), |exponent, k| {
+        BigInt::from(k).pow(exponent.to_u32().expect("Exponent is too big"))
+    })
+}
+
+/// Approximates the number of digits of the exponential factorial of n, for n >= 6.
+/// The number of digits is roughly 10^10^...^top, where `depth` is the number of tens.
+/// Returns `(depth, top)`.
+pub fn exponential_factorial_digits_tower(n: u64) -> (u64, f64) {
+    let four = exponential_factorial(4)
+        .to_f64()
+        .expect("Failed to convert to f64");
+    // log10(ef(6)) = ef(5) * log10(6) = 10^(4^... * log10(5) + log10(log10(6)))
+    let top = four * 5f64.log10() + 6f64.log10().log10();
+    // Every further step just adds another ten, the rest is negligible compared to the tower
+    (n - 5, top)
+}
+
+/// Calculates a↑↑b, a tower of b copies of a, exactly.
+/// Only feasible if the result is small, see [`tetration_tower`].
+pub fn tetration(a: u64, b: u64) -> BigInt {
+    if b == 0 {
+        return BigInt::one();
+    }
+    (1..b).fold(BigInt::from(a), |exponent, _| {
+        BigInt::from(a).pow(exponent.to_u32().expect("Exponent is too big"))
+    })
+}
+
+/// Approximates a↑↑b as a tower 10^10^...^top, for a >= 2. Returns `(tens, top)`.
+/// With no tens, top is the value itself.
+pub fn tetration_tower(a: u64, b: u64) -> (u64, f64) {
+    let log_a = (a as f64).log10();
+    let (mut tens, mut top) = (0, a as f64);
+    for _ in 1..b {
+        match tens {
+            // Stays a plain f64 for as long as possible
+            0 if top * log_a < 300.0 => top = 10f64.powf(top * log_a),
+            0 => (tens, top) = (1, top * log_a),
+            // log10(a^(10^top)) = 10^(top + log10(log10(a)))
+            1 => (tens, top) = (2, top + log_a.log10()),
+            // Further corrections vanish compared to the tower
+            _ => tens += 1,
+        }
+    }
+    (tens, top)
+}
+
+/// Approximates the number of digits of n! as a tower 10^...^top, for n = 10^log_n given only by its logarithm.
+/// Uses Stirling's formula log10(n!) ≈ n(log10(n) - log10(e)) + log10(2πn) / 2. Returns `(tens, top)`,
+/// with no tens top is log10(n!), so the digits are floor(top) + 1.
+/// Returns `None` if log_n is too big for a f64.
+pub fn factorial_digits_tower(log_n: f64) -> Option<(u64, f64)> {
+    let log_e = std::f64::consts::LOG10_E;
+    // log10(log10(n!)), the term with 2πn vanishes against it
+    let log_log = log_n + (log_n - log_e).log10();
+    if !log_log.is_finite() {
+        return None;
+    }
+    if log_log < 15.0 {
+        let n = 10f64.powf(log_n);
+        return Some((
+            0,
+            n * (log_n - log_e) + (2.0 * std::f64::consts::PI * n).log10() / 2.0,
+        ));
+    }
+    let (mut tens, mut top) = (1, log_log);
+    // Keep the top short enough to still be readable
+    while top >= 1e15 {
+        (tens, top) = (tens + 1, top.log10());
+    }
+    Some((tens, top))
+}
+
+/// Checks whether n is a factorion, a number equal to the sum of the factorials of its digits.
+/// There are only four of them: 1, 2, 145 and 40585.
+pub fn is_factorion(n: &BigInt) -> bool {
+    const DIGIT_FACTORIALS: [u64; 10] = [1, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880];
+    // From 8 digits on even 9! per digit can't keep up with the number anymore
+    let digits = n.to_string();
+    if n.is_negative() || digits.len() > 7 {
+        return false;
+    }
+    let sum: u64 = digits
+        .bytes()
+        .map(|digit| DIGIT_FACTORIALS[(digit - b'0') as usize])
+        .sum();
+    *n == BigInt::from(sum)
+}
+
+/// Calculates log10 of Stirling's approximation √(2πn)(n/e)^n of n!, for n ≥ 1.
+pub fn stirling_log10(n: u64) -> f64 {
+    let n = n as f64;
+    n * (n.log10() - std::f64::consts::LOG10_E) + (2.0 * std::f64::consts::PI * n).log10() / 2.0
+}
+
+/// Calculates log10(n) for n ≥ 1, also for numbers too big for a f64.
+pub fn log10(n: &BigInt) -> f64 {
+    let digits = n.magnitude().to_string();
+    // A f64 can't hold more precision anyway
+    let leading = digits.len().min(17);
+    let mantissa: f64 = digits[..leading].parse().expect("Digits are a number");
+    mantissa.log10() + (digits.len() - leading) as f64
+}
+
+/// Finds the n with n! ≤ x < (n+1)!, for x ≥ 1.
+/// Binary searches with Stirling's formula for log10(n!) first, then corrects the guess with exact factorials.
+pub fn inverse_factorial(x: &BigInt) -> u64 {
+    let log_e = std::f64::consts::LOG10_E;
+    let log10_factorial =
+        |n: f64| n * (n.log10() - log_e) + (2.0 * std::f64::consts::PI * n).log10() / 2.0;
+    // Only roughly, the exact correction below takes care of the rest
+    let log_x = x.bits() as f64 * std::f64::consts::LOG10_2;
+    let (mut low, mut high) = (1u64, 2u64);
+    while log10_factorial(high as f64) <= log_x {
+        high *= 2;
+    }
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if log10_factorial(mid as f64) <= log_x {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let mut n = low;
+    let mut factorial = factorial(n, 1);
+    while &factorial > x && n > 1 {
+        factorial /= n;
+        n -= 1;
+    }
+    while &(&factorial * (n + 1)) <= x {
+        n += 1;
+        factorial *= n;
+    }
+    n
+}
+
+/// Finds the n with n? ≤ x < (n+1)?, where the termial n? = 1 + 2 + ... + n = n(n+1)/2.
+/// Solves n² + n - 2x = 0, so n = ⌊(√(8x + 1) - 1) / 2⌋.
+pub fn inverse_termial(x: &BigInt) -> BigInt {
+    ((x * 8u32 + 1u32).sqrt() - 1u32) / 2u32
+}
+
+/// Calculates the termial n? = 1 + 2 + ... + n = n(n+1)/2.
+pub fn termial(n: &BigInt) -> BigInt {
+    n * (n + 1) / 2
+}
+
+/// Counts the trailing zeros of n! with Legendre's formula, without calculating n!.
+/// Every trailing zero needs a factor 5 (factors 2 are more common), so this is the sum of n / 5^i.
+pub fn factorial_trailing_zeros(n: &BigInt) -> BigInt {
+    let five = BigInt::from(5);
+    let mut zeros = BigInt::zero();
+    let mut n = n / &five;
+    while !n.is_zero() {
+        zeros += &n;
+        n /= &five;
+    }
+    zeros
+}
+
+/// Calculates the prime factorization of n! with Legendre's formula, as pairs of prime and exponent.
+/// The exponent of p is the sum of n / p^i.
+pub fn factorial_prime_factors(n: u64) -> Vec<(u64, u64)> {
+    primes_up_to(n)
+        .into_iter()
+        .map(|prime| {
+            let mut exponent = 0;
+            let mut power = n / prime;
+            while power > 0 {
+                exponent += power;
+                power /= prime;
+            }
+            (prime, exponent)
+        })
+        .collect()
+}
+
+/// Calculates the k-multifactorial of n modulo m, without calculating the factorial itself.
+pub fn factorial_mod(n: u64, k: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    // m is one of the factors of n!
+    if k == 1 && n >= m {
+        return 0;
+    }
+    let m = m as u128;
+    let mut remainder: u128 = 1;
+    let mut i = n;
+    while i > 1 {
+        remainder = remainder * (i as u128 % m) % m;
+        i = i.saturating_sub(k);
+    }
+    remainder as u64
+}
+
+/// Calculates x! = Γ(x + 1) for any real x, using the Lanczos approximation.
+/// Below 0.5 the reflection formula Γ(z)Γ(1 - z) = π / sin(πz) is used.
+/// Negative integers have no factorial, so they give NaN.
+pub fn gamma_factorial(x: f64) -> f64 {
+    gamma(x + 1.0)
+}
+
+fn gamma(z: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if z <= 0.0 && z.fract() == 0.0 {
+        return f64::NAN;
+    }
+    if z < 0.5 {
+        return std::f64::consts::PI / ((std::f64::consts::PI * z).sin() * gamma(1.0 - z));
+    }
+
+    let z = z - 1.0;
+    let t = z + G + 0.5;
+    let sum = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (z + i as f64));
+    // t^(z + 0.5) alone overflows close to 170!, so it is split around e^-t
+    let half_power = t.powf((z + 0.5) / 2.0);
+    (2.0 * std::f64::consts::PI).sqrt() * half_power * (-t).exp() * half_power * sum
+}
+
+/// Calculates the binomial coefficient "n choose k".
+/// Returns zero if k is bigger than n.
+pub fn binomial(n: u64, k: u64) -> BigInt {
+    if k > n {
+        return BigInt::zero();
+    }
+    // Use the symmetry to keep the products small
+    let k = k.min(n - k);
+    falling_factorial(n, k) / factorial(k, 1)
+}
+
+/// Calculates the product of the k factors n * (n-1) * ... * (n-k+1), which is n! / (n-k)!.
+pub fn falling_factorial(n: u64, k: u64) -> BigInt {
+    if k == 0 {
+        return BigInt::one();
+    }
+    multifactorial_recursive(n, 1, 0, k - 1)
+}
+
 /// Rounds a base 10 number string.
 /// Uses the last digit to decide the rounding direction.
 /// Rounds over 9s. This does **not** keep the length or turn rounded over digits into zeros.
@@ -65,8 +451,25 @@ pub(crate) fn round(number: &mut String) {
 mod tests {
     use super::*;
     use num_bigint::ToBigInt;
-    use num_traits::Zero;
     use std::str::FromStr;
+    #[test]
+    fn test_factorial_from() {
+        for (m, n, k) in [
+            (998, 1000, 1),
+            (5, 5, 1),
+            (0, 10, 1),
+            (1, 10, 1),
+            (3, 11, 2),
+            (0, 12, 3),
+        ] {
+            assert_eq!(
+                factorial(m, k) * factorial_from(m, n, k),
+                factorial(n, k),
+                "{m} {n} {k}"
+            );
+        }
+    }
+
     #[test]
     fn test_calculate_multi_single_factorial() {
         assert_eq!(factorial(0, 1), 1.to_bigint().unwrap());
@@ -210,6 +613,248 @@ mod tests {
         assert_eq!(result.to_string().len(), 456579);
     }
 
+    #[test]
+    fn test_calculate_primorial() {
+        assert_eq!(primorial(0), 1.to_bigint().unwrap());
+        assert_eq!(primorial(1), 1.to_bigint().unwrap());
+        assert_eq!(primorial(2), 2.to_bigint().unwrap());
+        assert_eq!(primorial(3), 6.to_bigint().unwrap());
+        assert_eq!(primorial(4), 6.to_bigint().unwrap());
+        assert_eq!(primorial(5), 30.to_bigint().unwrap());
+        assert_eq!(primorial(10), 210.to_bigint().unwrap());
+        assert_eq!(primorial(13), 30030.to_bigint().unwrap());
+        assert_eq!(primorial(23166).to_string().len(), 9997);
+        assert_eq!(primorial(23167).to_string().len(), 10002);
+    }
+
+    #[test]
+    fn test_primes_up_to() {
+        assert!(primes_up_to(1).is_empty());
+        assert_eq!(primes_up_to(2), vec![2]);
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_up_to(100_000).len(), 9592);
+    }
+
+    #[test]
+    fn test_calculate_hyperfactorial() {
+        assert_eq!(hyperfactorial(0), 1.to_bigint().unwrap());
+        assert_eq!(hyperfactorial(1), 1.to_bigint().unwrap());
+        assert_eq!(hyperfactorial(2), 4.to_bigint().unwrap());
+        assert_eq!(hyperfactorial(3), 108.to_bigint().unwrap());
+        assert_eq!(hyperfactorial(4), 27648.to_bigint().unwrap());
+        assert_eq!(hyperfactorial(5), 86400000.to_bigint().unwrap());
+        assert_eq!(hyperfactorial(104).to_string().len(), 9839);
+    }
+
+    #[test]
+    fn test_calculate_superfactorial() {
+        assert_eq!(superfactorial(0), 1.to_bigint().unwrap());
+        assert_eq!(superfactorial(1), 1.to_bigint().unwrap());
+        assert_eq!(superfactorial(2), 2.to_bigint().unwrap());
+        assert_eq!(superfactorial(3), 12.to_bigint().unwrap());
+        assert_eq!(superfactorial(4), 288.to_bigint().unwrap());
+        assert_eq!(superfactorial(5), 34560.to_bigint().unwrap());
+        assert_eq!(superfactorial(6), 24883200.to_bigint().unwrap());
+    }
+
+    #[test]
+    fn test_approximate_hyper_and_superfactorial_digits() {
+        for n in [1, 5, 10, 104, 117, 300] {
+            assert_eq!(
+                hyperfactorial_digits(n),
+                hyperfactorial(n).to_string().len() as u64
+            );
+            assert_eq!(
+                superfactorial_digits(n),
+                superfactorial(n).to_string().len() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculate_left_factorial() {
+        assert_eq!(left_factorial(0), 0.to_bigint().unwrap());
+        assert_eq!(left_factorial(1), 1.to_bigint().unwrap());
+        assert_eq!(left_factorial(2), 2.to_bigint().unwrap());
+        assert_eq!(left_factorial(3), 4.to_bigint().unwrap());
+        assert_eq!(left_factorial(4), 10.to_bigint().unwrap());
+        assert_eq!(left_factorial(5), 34.to_bigint().unwrap());
+        assert_eq!(left_factorial(10), 409114.to_bigint().unwrap());
+        assert!(left_factorial(3249).to_string().len() <= 10_000);
+        assert!(left_factorial(3250).to_string().len() > 10_000);
+    }
+
+    #[test]
+    fn test_approximate_left_factorial_digits() {
+        for n in [2, 5, 10, 100, 3250] {
+            assert_eq!(
+                left_factorial_digits(n),
+                left_factorial(n).to_string().len() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculate_alternating_factorial() {
+        assert_eq!(alternating_factorial(0), 0.to_bigint().unwrap());
+        assert_eq!(alternating_factorial(1), 1.to_bigint().unwrap());
+        assert_eq!(alternating_factorial(2), 1.to_bigint().unwrap());
+        assert_eq!(alternating_factorial(3), 5.to_bigint().unwrap());
+        assert_eq!(alternating_factorial(4), 19.to_bigint().unwrap());
+        assert_eq!(alternating_factorial(5), 101.to_bigint().unwrap());
+        assert_eq!(alternating_factorial(10), 3301819.to_bigint().unwrap());
+        assert_eq!(alternating_factorial(3249).to_string().len(), 10001);
+        assert_eq!(alternating_factorial(3250).to_string().len(), 10005);
+    }
+
+    #[test]
+    fn test_approximate_alternating_factorial_digits() {
+        for n in [2, 5, 10, 100, 3250] {
+            assert_eq!(
+                alternating_factorial_digits(n),
+                alternating_factorial(n).to_string().len() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculate_exponential_factorial() {
+        assert_eq!(exponential_factorial(0), 1.to_bigint().unwrap());
+        assert_eq!(exponential_factorial(1), 1.to_bigint().unwrap());
+        assert_eq!(exponential_factorial(2), 2.to_bigint().unwrap());
+        assert_eq!(exponential_factorial(3), 9.to_bigint().unwrap());
+        assert_eq!(exponential_factorial(4), 262144.to_bigint().unwrap());
+        assert_eq!(exponential_factorial(5).to_string().len(), 183231);
+    }
+
+    #[test]
+    fn test_exponential_factorial_digits_tower() {
+        let (depth, top) = exponential_factorial_digits_tower(6);
+        assert_eq!(depth, 1);
+        assert!((top - 183230.68388).abs() < 0.001);
+
+        let (depth, top) = exponential_factorial_digits_tower(9);
+        assert_eq!(depth, 4);
+        assert!((top - 183230.68388).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_tetration() {
+        assert_eq!(tetration(2, 0), 1.to_bigint().unwrap());
+        assert_eq!(tetration(2, 1), 2.to_bigint().unwrap());
+        assert_eq!(tetration(2, 3), 16.to_bigint().unwrap());
+        assert_eq!(tetration(2, 4), 65536.to_bigint().unwrap());
+        assert_eq!(tetration(3, 3), 7625597484987u64.to_bigint().unwrap());
+        assert_eq!(tetration(2, 5).to_string().len(), 19729);
+    }
+
+    #[test]
+    fn test_tetration_tower() {
+        assert_eq!(tetration_tower(2, 4), (0, 65536.0));
+        let (tens, top) = tetration_tower(2, 5);
+        assert_eq!(tens, 1);
+        assert!((top - 19728.301).abs() < 0.001);
+        let (tens, top) = tetration_tower(2, 6);
+        assert_eq!(tens, 2);
+        assert!((top - 19727.780).abs() < 0.001);
+        let (tens, top) = tetration_tower(2, 10);
+        assert_eq!(tens, 6);
+        assert!((top - 19727.780).abs() < 0.001);
+        assert_eq!(tetration_tower(10, 3), (1, 1e10));
+    }
+
+    #[test]
+    fn test_factorial_trailing_zeros() {
+        for n in [0u64, 4, 5, 24, 25, 100, 1000, 3249] {
+            let factorial = factorial(n, 1).to_string();
+            let zeros = factorial.len() - factorial.trim_end_matches('0').len();
+            assert_eq!(
+                factorial_trailing_zeros(&n.to_bigint().unwrap()),
+                zeros.to_bigint().unwrap()
+            );
+        }
+        assert_eq!(
+            factorial_trailing_zeros(&"1000000000000000000000".parse().unwrap()),
+            "249999999999999999997".parse::<BigInt>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_factorial_prime_factors() {
+        assert!(factorial_prime_factors(0).is_empty());
+        assert!(factorial_prime_factors(1).is_empty());
+        assert_eq!(
+            factorial_prime_factors(10),
+            vec![(2, 8), (3, 4), (5, 2), (7, 1)]
+        );
+        for n in [2, 25, 100, 500] {
+            let product = factorial_prime_factors(n)
+                .into_iter()
+                .fold(BigInt::one(), |product, (prime, exponent)| {
+                    product * BigInt::from(prime).pow(exponent as u32)
+                });
+            assert_eq!(product, factorial(n, 1));
+        }
+    }
+
+    #[test]
+    fn test_factorial_mod() {
+        for (n, k) in [(0, 1), (1, 1), (10, 1), (25, 1), (30, 2), (31, 3)] {
+            for m in [1u64, 2, 7, 97, 1000, 1_000_000_007] {
+                assert_eq!(
+                    factorial_mod(n, k, m).to_bigint().unwrap(),
+                    factorial(n, k) % m,
+                    "{n} {k} {m}"
+                );
+            }
+        }
+        assert_eq!(factorial_mod(100_000, 1, 1_000_000_007), 457_992_974);
+        assert_eq!(factorial_mod(u64::MAX, 1, 1_000_000_007), 0);
+    }
+
+    #[test]
+    fn test_gamma_factorial() {
+        let close = |a: f64, b: f64| ((a - b) / b).abs() < 1e-12;
+        for n in 0..20u64 {
+            let exact = factorial(n, 1).to_f64().unwrap();
+            assert!(close(gamma_factorial(n as f64), exact), "{n}");
+        }
+        assert!(close(
+            gamma_factorial(0.5),
+            std::f64::consts::PI.sqrt() / 2.0
+        ));
+        // (-2.5)! = Γ(-1.5) = 4√π / 3
+        assert!(close(
+            gamma_factorial(-2.5),
+            4.0 * std::f64::consts::PI.sqrt() / 3.0
+        ));
+        // (-0.5)! = √π
+        assert!(close(gamma_factorial(-0.5), std::f64::consts::PI.sqrt()));
+        assert!(gamma_factorial(-3.0).is_nan());
+    }
+
+    #[test]
+    fn test_falling_factorial() {
+        assert_eq!(falling_factorial(52, 0), 1.to_bigint().unwrap());
+        assert_eq!(falling_factorial(52, 5), 311875200.to_bigint().unwrap());
+        assert_eq!(falling_factorial(100, 100), factorial(100, 1));
+    }
+
+    #[test]
+    fn test_calculate_binomial() {
+        assert_eq!(binomial(0, 0), 1.to_bigint().unwrap());
+        assert_eq!(binomial(5, 0), 1.to_bigint().unwrap());
+        assert_eq!(binomial(5, 5), 1.to_bigint().unwrap());
+        assert_eq!(binomial(10, 3), 120.to_bigint().unwrap());
+        assert_eq!(binomial(10, 7), 120.to_bigint().unwrap());
+        assert_eq!(binomial(52, 5), 2598960.to_bigint().unwrap());
+        assert_eq!(binomial(3, 4), 0.to_bigint().unwrap());
+        assert_eq!(
+            binomial(100, 50),
+            BigInt::from_str("100891344545564193334812497256").unwrap()
+        );
+    }
+
     #[test]
     fn test_round_down() {
         let mut number = String::from("1929472373");
@@ -230,4 +875,66 @@ mod tests {
         round(&mut number);
         assert_eq!(number, "25");
     }
+    #[test]
+    fn test_factorial_digits_tower() {
+        let (tens, top) = factorial_digits_tower(6.0).unwrap();
+        assert_eq!(tens, 0);
+        assert!((top - 5565708.917).abs() < 0.01);
+        let (tens, top) = factorial_digits_tower(100.0).unwrap();
+        assert_eq!(tens, 1);
+        assert!((top - 101.998).abs() < 0.001);
+        let (tens, top) = factorial_digits_tower(1e300).unwrap();
+        assert_eq!(tens, 2);
+        assert!((top - 300.0).abs() < 0.001);
+        assert_eq!(factorial_digits_tower(f64::INFINITY), None);
+    }
+    #[test]
+    fn test_log10() {
+        assert_eq!(log10(&BigInt::from(1)), 0.0);
+        assert!((log10(&BigInt::from(1000)) - 3.0).abs() < 1e-12);
+        assert!((log10(&BigInt::from(10).pow(500)) - 500.0).abs() < 1e-9);
+        assert!((log10(&(BigInt::from(2).pow(2000))) - 602.0599913279624).abs() < 1e-9);
+    }
+    #[test]
+    fn test_inverse_factorial() {
+        assert_eq!(inverse_factorial(&BigInt::from(1)), 1);
+        assert_eq!(inverse_factorial(&BigInt::from(2)), 2);
+        assert_eq!(inverse_factorial(&BigInt::from(119)), 4);
+        assert_eq!(inverse_factorial(&BigInt::from(120)), 5);
+        assert_eq!(inverse_factorial(&BigInt::from(3628800)), 10);
+        for n in [20, 100, 1000, 3000] {
+            let x = factorial(n, 1);
+            assert_eq!(inverse_factorial(&x), n);
+            assert_eq!(inverse_factorial(&(&x - 1)), n - 1);
+            assert_eq!(inverse_factorial(&(&x + 1)), n);
+        }
+    }
+    #[test]
+    fn test_is_factorion() {
+        let factorions: Vec<u64> = (0..100_000)
+            .filter(|n| is_factorion(&BigInt::from(*n)))
+            .collect();
+        assert_eq!(factorions, vec![1, 2, 145, 40585]);
+        assert!(!is_factorion(&BigInt::from(-145)));
+        assert!(!is_factorion(&factorial(20, 1)));
+    }
+    #[test]
+    fn test_stirling_log10() {
+        // 10! = 3628800, Stirling gives about 3598696
+        assert!((stirling_log10(10) - 3598695.6f64.log10()).abs() < 1e-6);
+        for n in [1, 20, 100, 3000] {
+            let error = log10(&factorial(n, 1)) - stirling_log10(n);
+            // The relative error is about 1/(12n)
+            assert!(error > 0.0 && error < 0.04 / n as f64, "{n}");
+        }
+    }
+    #[test]
+    fn test_inverse_termial() {
+        assert_eq!(inverse_termial(&BigInt::zero()), BigInt::zero());
+        assert_eq!(inverse_termial(&BigInt::from(54)), BigInt::from(9));
+        assert_eq!(inverse_termial(&BigInt::from(55)), BigInt::from(10));
+        let n = BigInt::from(10).pow(500);
+        assert_eq!(inverse_termial(&termial(&n)), n);
+        assert_eq!(inverse_termial(&(termial(&n) - 1)), &n - 1);
+    }
 }
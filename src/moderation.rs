@@ -0,0 +1,232 @@
+use crate::error::FactorionError;
+use fancy_regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+
+pub(crate) const PAUSED_SUBREDDITS_FILE_PATH: &str = "paused_subreddits.txt";
+pub(crate) const BANNED_SUBREDDITS_FILE_PATH: &str = "banned_subreddits.txt";
+/// How many replies in a row have to fail with a ban before a subreddit is disabled.
+const BAN_FAILURES_TO_DISABLE: u32 = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ModAction {
+    Pause,
+    Resume,
+}
+
+/// A moderator asking the bot to be quiet in their subreddit, or to come back,
+/// written as `!factorion pause` or `!factorion resume`, optionally followed by `r/subreddit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ModRequest {
+    /// In lowercase, the subreddit of the comment if none is named.
+    pub(crate) subreddit: String,
+    pub(crate) action: ModAction,
+}
+
+impl ModRequest {
+    /// Finds a request in a comment posted in `subreddit`, which may be empty if unknown.
+    pub(crate) fn parse(body: &str, subreddit: &str) -> Option<Self> {
+        let request_regex =
+            Regex::new(r"(?i)!factorion\s+(pause|resume)\b(?:\s+/?r/([a-z0-9_]+))?")
+                .expect("Invalid moderation regex");
+        let captures = request_regex.captures(body).ok()??;
+        let action = if captures[1].eq_ignore_ascii_case("pause") {
+            ModAction::Pause
+        } else {
+            ModAction::Resume
+        };
+        let subreddit = captures
+            .get(2)
+            .map_or(subreddit, |named| named.as_str())
+            .to_lowercase();
+        if subreddit.is_empty() {
+            return None;
+        }
+        Some(Self { subreddit, action })
+    }
+
+    /// Confirms the request to the moderator.
+    pub(crate) fn confirmation(&self) -> String {
+        match self.action {
+            ModAction::Pause => format!(
+                "I won't answer any comments in r/{} anymore, until a moderator writes `!factorion resume`.",
+                self.subreddit
+            ),
+            ModAction::Resume => format!("I'm answering comments in r/{} again.", self.subreddit),
+        }
+    }
+}
+
+/// The subreddits in which moderators paused the bot, stored one per line.
+#[derive(Debug, Default)]
+pub(crate) struct PausedSubreddits {
+    subreddits: BTreeSet<String>,
+}
+
+impl PausedSubreddits {
+    /// Reads the paused subreddits, starting without any if there is no file yet.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        Ok(Self {
+            subreddits: load_subreddits(PAUSED_SUBREDDITS_FILE_PATH)?,
+        })
+    }
+
+    pub(crate) fn store(&self) -> Result<(), FactorionError> {
+        store_subreddits(PAUSED_SUBREDDITS_FILE_PATH, &self.subreddits)
+    }
+
+    pub(crate) fn is_paused(&self, subreddit: &str) -> bool {
+        self.subreddits.contains(&subreddit.to_lowercase())
+    }
+
+    /// The paused subreddits in alphabetical order.
+    pub(crate) fn subreddits(&self) -> impl Iterator<Item = &str> {
+        self.subreddits.iter().map(String::as_str)
+    }
+
+    /// Pauses or resumes the subreddit, returning whether anything changed.
+    pub(crate) fn apply(&mut self, request: &ModRequest) -> bool {
+        match request.action {
+            ModAction::Pause => self.subreddits.insert(request.subreddit.clone()),
+            ModAction::Resume => self.subreddits.remove(&request.subreddit),
+        }
+    }
+}
+
+/// The subreddits in which the bot got banned, so it stops polling them, stored one per line.
+/// A subreddit counts as banned once enough replies in a row failed because of a ban.
+#[derive(Debug, Default)]
+pub(crate) struct BannedSubreddits {
+    subreddits: BTreeSet<String>,
+    failures: HashMap<String, u32>,
+}
+
+impl BannedSubreddits {
+    /// Reads the banned subreddits, starting without any if there is no file yet.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        Ok(Self {
+            subreddits: load_subreddits(BANNED_SUBREDDITS_FILE_PATH)?,
+            failures: HashMap::new(),
+        })
+    }
+
+    pub(crate) fn store(&self) -> Result<(), FactorionError> {
+        store_subreddits(BANNED_SUBREDDITS_FILE_PATH, &self.subreddits)
+    }
+
+    pub(crate) fn is_banned(&self, subreddit: &str) -> bool {
+        self.subreddits.contains(&subreddit.to_lowercase())
+    }
+
+    /// Counts a reply that failed because of a ban, returning whether the subreddit just got disabled.
+    pub(crate) fn record_ban(&mut self, subreddit: &str) -> bool {
+        let subreddit = subreddit.to_lowercase();
+        if subreddit.is_empty() {
+            return false;
+        }
+        let failures = self.failures.entry(subreddit.clone()).or_default();
+        *failures += 1;
+        *failures >= BAN_FAILURES_TO_DISABLE && self.subreddits.insert(subreddit)
+    }
+
+    /// A reply went through, so earlier failures were no ban after all.
+    pub(crate) fn record_success(&mut self, subreddit: &str) {
+        self.failures.remove(&subreddit.to_lowercase());
+    }
+
+    /// The subreddits to poll, joined with `+` like in reddit URLs, without the banned ones.
+    pub(crate) fn active_subreddits(&self, subreddits: &str) -> String {
+        subreddits
+            .split('+')
+            .filter(|subreddit| !subreddit.trim().is_empty() && !self.is_banned(subreddit.trim()))
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+fn load_subreddits(path: &'static str) -> Result<BTreeSet<String>, FactorionError> {
+    match fs::read_to_string(path) {
+        Ok(file) => Ok(parse_subreddits(&file)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+        Err(source) => Err(FactorionError::Io { path, source }),
+    }
+}
+
+fn parse_subreddits(file: &str) -> BTreeSet<String> {
+    file.lines()
+        .map(|subreddit| subreddit.trim().to_lowercase())
+        .filter(|subreddit| !subreddit.is_empty())
+        .collect()
+}
+
+fn store_subreddits(
+    path: &'static str,
+    subreddits: &BTreeSet<String>,
+) -> Result<(), FactorionError> {
+    let file: String = subreddits
+        .iter()
+        .map(|subreddit| format!("{subreddit}\n"))
+        .collect();
+    fs::write(path, file).map_err(|source| FactorionError::Io { path, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mod_request() {
+        assert_eq!(
+            ModRequest::parse("!factorion pause", "mathmemes"),
+            Some(ModRequest {
+                subreddit: "mathmemes".to_string(),
+                action: ModAction::Pause
+            })
+        );
+        assert_eq!(
+            ModRequest::parse("u/factorion-bot !Factorion RESUME r/AskMath", "mathmemes"),
+            Some(ModRequest {
+                subreddit: "askmath".to_string(),
+                action: ModAction::Resume
+            })
+        );
+        assert_eq!(ModRequest::parse("!factorion pause", ""), None);
+        assert_eq!(ModRequest::parse("!factorion pauses", "mathmemes"), None);
+        assert_eq!(ModRequest::parse("factorion pause", "mathmemes"), None);
+    }
+
+    #[test]
+    fn test_paused_subreddits() {
+        let mut paused = PausedSubreddits {
+            subreddits: parse_subreddits("MathMemes\n\naskmath\n"),
+        };
+        assert!(paused.is_paused("mathmemes"));
+        assert!(paused.is_paused("AskMath"));
+        assert!(!paused.is_paused("math"));
+
+        let request = ModRequest::parse("!factorion resume", "mathmemes").unwrap();
+        assert!(paused.apply(&request));
+        assert!(!paused.apply(&request));
+        assert!(!paused.is_paused("mathmemes"));
+    }
+
+    #[test]
+    fn test_banned_subreddits() {
+        let mut banned = BannedSubreddits::default();
+        assert!(!banned.record_ban("MathMemes"));
+        assert!(!banned.record_ban("mathmemes"));
+        banned.record_success("mathmemes");
+        assert!(!banned.record_ban("mathmemes"));
+        assert!(!banned.record_ban("mathmemes"));
+        assert!(banned.record_ban("mathmemes"));
+        assert!(!banned.record_ban("mathmemes"));
+        assert!(banned.is_banned("MathMemes"));
+        assert!(!banned.record_ban(""));
+
+        assert_eq!(
+            banned.active_subreddits("askmath+MathMemes+factorion"),
+            "askmath+factorion"
+        );
+        assert_eq!(banned.active_subreddits("mathmemes"), "");
+    }
+}
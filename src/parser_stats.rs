@@ -0,0 +1,74 @@
+use crate::reddit_comment::{RedditComment, Status};
+use std::fmt;
+
+/// How the comments fetched since the last summary were parsed, to see how parser changes do on real comments.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ParserStats {
+    comments: u64,
+    /// Comments the regex engine gave up on, which were skipped.
+    failed: u64,
+    with_calculations: u64,
+    calculations: u64,
+    most_calculations: usize,
+    too_big: u64,
+    /// Comments that hit `max_jobs_per_comment` or the calculation time limit.
+    cut_short: u64,
+}
+
+impl ParserStats {
+    pub(crate) fn record(&mut self, comment: &RedditComment) {
+        self.comments += 1;
+        if comment.status.contains(&Status::FactorialsFound) {
+            self.with_calculations += 1;
+        }
+        self.calculations += comment.spans.len() as u64;
+        self.most_calculations = self.most_calculations.max(comment.spans.len());
+        if comment.status.contains(&Status::NumberTooBig) {
+            self.too_big += 1;
+        }
+        if comment.status.contains(&Status::TooManyCalculations)
+            || comment.status.contains(&Status::TooExpensive)
+        {
+            self.cut_short += 1;
+        }
+    }
+
+    pub(crate) fn record_failure(&mut self) {
+        self.comments += 1;
+        self.failed += 1;
+    }
+}
+
+impl fmt::Display for ParserStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Parsed {} comments ({} failed): {} with calculations, {} calculations (at most {} in one), {} too big, {} cut short",
+            self.comments,
+            self.failed,
+            self.with_calculations,
+            self.calculations,
+            self.most_calculations,
+            self.too_big,
+            self.cut_short
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_stats() {
+        let mut stats = ParserStats::default();
+        stats.record(&RedditComment::new("5! and 6!", "a"));
+        stats.record(&RedditComment::new("no factorial", "b"));
+        stats.record(&RedditComment::new("1000000000!", "c"));
+        stats.record_failure();
+        assert_eq!(
+            stats.to_string(),
+            "Parsed 4 comments (1 failed): 1 with calculations, 3 calculations (at most 2 in one), 1 too big, 0 cut short"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::FactorionError;
+use reqwest::header::USER_AGENT;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+const GIST_API_URL: &str = "https://api.github.com/gists";
+const GIST_FILE_NAME: &str = "factorials.md";
+/// Uploads are awaited before replying, so a hanging paste service mustn't stall the bot.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Stores results too long for reddit somewhere else, so the reply can link them.
+pub(crate) trait Uploader {
+    /// Uploads the text and returns the link to it.
+    fn upload<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FactorionError>> + Send + 'a>>;
+}
+
+/// Where results too long for reddit get uploaded to, set with `PASTE_SERVICE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PasteService {
+    /// Nothing is uploaded.
+    Disabled,
+    /// The text is posted as is to the URL, which answers with the link, like `https://paste.rs`.
+    Raw(String),
+    /// A secret GitHub gist is created, with the token from `GITHUB_TOKEN`.
+    Gist,
+}
+
+impl PasteService {
+    /// Parses `gist`, a http(s) URL, or nothing to disable uploading.
+    pub(crate) fn parse(service: &str) -> Result<Self, String> {
+        let service = service.trim();
+        if service.is_empty() {
+            Ok(PasteService::Disabled)
+        } else if service.eq_ignore_ascii_case("gist") {
+            Ok(PasteService::Gist)
+        } else if service.starts_with("https://") || service.starts_with("http://") {
+            Ok(PasteService::Raw(service.to_string()))
+        } else {
+            Err(format!("Unknown paste service: {service}"))
+        }
+    }
+
+    /// Builds the uploader, if any. Gists need a GitHub token.
+    pub(crate) fn uploader(
+        self,
+        github_token: Option<String>,
+    ) -> Result<Option<Box<dyn Uploader>>, String> {
+        let client = Client::builder()
+            .timeout(UPLOAD_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(match self {
+            PasteService::Disabled => None,
+            PasteService::Raw(url) => Some(Box::new(RawPaste { client, url })),
+            PasteService::Gist => {
+                let token = github_token.ok_or("GITHUB_TOKEN must be set for gists")?;
+                Some(Box::new(Gist { client, token }))
+            }
+        })
+    }
+}
+
+struct RawPaste {
+    client: Client,
+    url: String,
+}
+
+impl Uploader for RawPaste {
+    fn upload<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FactorionError>> + Send + 'a>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(&self.url)
+                .body(text.to_string())
+                .send()
+                .await?
+                .error_for_status()?;
+            let link = response.text().await?.trim().to_string();
+            if !link.starts_with("http") {
+                return Err(FactorionError::Upload(format!("Not a link: {link}")));
+            }
+            Ok(link)
+        })
+    }
+}
+
+struct Gist {
+    client: Client,
+    token: String,
+}
+
+impl Uploader for Gist {
+    fn upload<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FactorionError>> + Send + 'a>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(GIST_API_URL)
+                .header(USER_AGENT, "factorion-bot")
+                .bearer_auth(&self.token)
+                .json(&json!({
+                    "description": "Results of factorion-bot",
+                    "public": false,
+                    "files": { GIST_FILE_NAME: { "content": text } },
+                }))
+                .send()
+                .await?
+                .error_for_status()?;
+            let gist = response.json::<Value>().await?;
+            gist["html_url"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| FactorionError::Upload(format!("No link in response: {gist}")))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_paste_service() {
+        assert_eq!(PasteService::parse(""), Ok(PasteService::Disabled));
+        assert_eq!(PasteService::parse(" Gist "), Ok(PasteService::Gist));
+        assert_eq!(
+            PasteService::parse("https://paste.rs"),
+            Ok(PasteService::Raw("https://paste.rs".to_string()))
+        );
+        assert!(PasteService::parse("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn test_uploader() {
+        assert!(PasteService::Disabled.uploader(None).unwrap().is_none());
+        assert!(PasteService::Gist.uploader(None).is_err());
+        assert!(PasteService::Gist
+            .uploader(Some("token".to_string()))
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::commands::Commands;
+use crate::error::FactorionError;
+use fancy_regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+pub(crate) const USER_PREFERENCES_FILE_PATH: &str = "user_preferences.json";
+
+/// What a single user asked the bot to remember about them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Preferences {
+    /// Commands applied to every comment of the user, like `short`.
+    pub(crate) commands: Commands,
+    /// The user never gets replies.
+    pub(crate) opted_out: bool,
+}
+
+/// The preferences of every user, keyed by their lowercase name.
+/// Users set their commands by writing `!prefer short, digits` (or `!prefer none`),
+/// and stop getting replies with `!optout` (until `!optin`).
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct UserPreferences {
+    users: HashMap<String, Preferences>,
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl UserPreferences {
+    /// Reads the stored preferences, starting without any if there is no file yet.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        match fs::read_to_string(USER_PREFERENCES_FILE_PATH) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(FactorionError::Io {
+                path: USER_PREFERENCES_FILE_PATH,
+                source,
+            }),
+        }
+    }
+
+    /// Writes the preferences back, if they changed since the last time.
+    pub(crate) fn store(&mut self) -> Result<(), FactorionError> {
+        if !self.changed {
+            return Ok(());
+        }
+        fs::write(USER_PREFERENCES_FILE_PATH, serde_json::to_string(self)?).map_err(|source| {
+            FactorionError::Io {
+                path: USER_PREFERENCES_FILE_PATH,
+                source,
+            }
+        })?;
+        self.changed = false;
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, author: &str) -> Preferences {
+        self.users
+            .get(&author.to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Remembers the commands the author asks for with `!prefer`, and whether they opt out or in again.
+    pub(crate) fn update_from_comment(&mut self, author: &str, body: &str) {
+        if author.is_empty() {
+            return;
+        }
+        let lowercase_body = body.to_lowercase();
+        if lowercase_body.contains("!optout") {
+            self.update(author, |preferences| preferences.opted_out = true);
+        } else if lowercase_body.contains("!optin") {
+            self.update(author, |preferences| preferences.opted_out = false);
+        }
+        let prefer_regex = Regex::new(r"(?i)!prefer\s+(none\b|[a-z]+(?:\s*,\s*[a-z]+)*)")
+            .expect("Invalid prefer regex");
+        let Ok(Some(captures)) = prefer_regex.captures(body) else {
+            return;
+        };
+        let commands = if captures[1].eq_ignore_ascii_case("none") {
+            Commands::NONE
+        } else {
+            // Unknown names are most likely just words following the command
+            match Commands::from_names(&captures[1]) {
+                Ok(commands) => commands,
+                Err(_) => return,
+            }
+        };
+        self.update(author, |preferences| preferences.commands = commands);
+    }
+
+    fn update(&mut self, author: &str, change: impl FnOnce(&mut Preferences)) {
+        let preferences = self.users.entry(author.to_lowercase()).or_default();
+        let before = preferences.clone();
+        change(preferences);
+        if *preferences != before {
+            self.changed = true;
+        }
+        if *preferences == Preferences::default() {
+            self.users.remove(&author.to_lowercase());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_from_comment() {
+        let mut preferences = UserPreferences::default();
+        preferences.update_from_comment("Someone", "From now on !prefer short, digits please");
+        assert!(preferences.changed);
+        assert_eq!(
+            preferences.get("someone").commands,
+            Commands::SHORT | Commands::DIGITS_ONLY
+        );
+
+        preferences.changed = false;
+        preferences.update_from_comment("someone", "!prefer Short,digits");
+        assert!(!preferences.changed);
+        preferences.update_from_comment("someone", "!prefer nonsense");
+        assert!(!preferences.changed);
+        preferences.update_from_comment("", "!prefer short");
+        assert!(!preferences.changed);
+
+        preferences.update_from_comment("someone", "!prefer none");
+        assert!(preferences.changed);
+        assert_eq!(preferences.get("someone"), Preferences::default());
+        assert!(preferences.users.is_empty());
+    }
+
+    #[test]
+    fn test_opt_out() {
+        let mut preferences = UserPreferences::default();
+        preferences.update_from_comment("someone", "Please stop !OptOut");
+        assert!(preferences.get("Someone").opted_out);
+        preferences.update_from_comment("someone", "!prefer short");
+        assert!(preferences.get("someone").opted_out);
+        preferences.update_from_comment("someone", "I'm back !optin");
+        assert_eq!(preferences.get("someone").commands, Commands::SHORT);
+        assert!(!preferences.get("someone").opted_out);
+    }
+
+    #[test]
+    fn test_preferences_roundtrip() {
+        let mut preferences = UserPreferences::default();
+        preferences.update_from_comment("someone", "!prefer short");
+        let json = serde_json::to_string(&preferences).unwrap();
+        assert_eq!(
+            json,
+            r#"{"users":{"someone":{"commands":["short"],"opted_out":false}}}"#
+        );
+        let read: UserPreferences = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.get("Someone").commands, Commands::SHORT);
+    }
+}
@@ -0,0 +1,120 @@
+use reqwest::header::HeaderMap;
+use std::time::{Duration, Instant};
+
+/// Keeps requests within the rate limit reddit reports in the `X-Ratelimit-*` headers of every response.
+/// Works like a token bucket: every request takes one of the remaining requests,
+/// and the bucket is refilled when the rate limit period resets.
+/// As long as reddit hasn't told the limits yet, requests aren't held back.
+#[derive(Debug, Default)]
+pub(crate) struct RateGovernor {
+    /// Requests left until the reset, if known.
+    remaining: Option<f64>,
+    /// When the current rate limit period ends, if known.
+    reset_at: Option<Instant>,
+}
+
+impl RateGovernor {
+    /// Takes a request from the bucket, returning how long to wait before sending it.
+    pub(crate) fn reserve(&mut self, now: Instant) -> Duration {
+        let (Some(remaining), Some(reset_at)) = (self.remaining, self.reset_at) else {
+            return Duration::ZERO;
+        };
+        if now >= reset_at {
+            // The bucket got refilled, until the next response tells how far
+            *self = Self::default();
+            return Duration::ZERO;
+        }
+        if remaining >= 1.0 {
+            self.remaining = Some(remaining - 1.0);
+            return Duration::ZERO;
+        }
+        *self = Self::default();
+        reset_at - now
+    }
+
+    /// Takes over the limits reported in a response.
+    pub(crate) fn update(&mut self, remaining: f64, reset: Duration, now: Instant) {
+        self.remaining = Some(remaining);
+        self.reset_at = Some(now + reset);
+    }
+
+    /// Takes over `X-Ratelimit-Remaining` and `X-Ratelimit-Reset` (in seconds), if the response has both.
+    pub(crate) fn update_from_headers(&mut self, headers: &HeaderMap, now: Instant) {
+        let header =
+            |name: &str| -> Option<f64> { headers.get(name)?.to_str().ok()?.trim().parse().ok() };
+        if let (Some(remaining), Some(reset)) =
+            (header("x-ratelimit-remaining"), header("x-ratelimit-reset"))
+        {
+            if reset.is_finite() && reset >= 0.0 {
+                self.update(remaining, Duration::from_secs_f64(reset), now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_without_limits() {
+        let mut governor = RateGovernor::default();
+        let now = Instant::now();
+        for _ in 0..1000 {
+            assert_eq!(governor.reserve(now), Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_reserve_until_empty() {
+        let mut governor = RateGovernor::default();
+        let now = Instant::now();
+        governor.update(2.0, Duration::from_secs(30), now);
+        assert_eq!(governor.reserve(now), Duration::ZERO);
+        assert_eq!(governor.reserve(now), Duration::ZERO);
+        assert_eq!(
+            governor.reserve(now + Duration::from_secs(10)),
+            Duration::from_secs(20)
+        );
+        // Waited for the reset, so the next request goes through
+        assert_eq!(
+            governor.reserve(now + Duration::from_secs(30)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_reserve_with_nothing_remaining() {
+        let mut governor = RateGovernor::default();
+        let now = Instant::now();
+        governor.update(0.0, Duration::from_secs(5), now);
+        assert_eq!(governor.reserve(now), Duration::from_secs(5));
+
+        governor.update(0.0, Duration::from_secs(5), now);
+        assert_eq!(
+            governor.reserve(now + Duration::from_secs(6)),
+            Duration::ZERO
+        );
+
+        // Reddit reports fractions of requests
+        governor.update(0.5, Duration::ZERO, now);
+        assert_eq!(governor.reserve(now), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_update_from_headers() {
+        let now = Instant::now();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-used", "599".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "0.0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "42".parse().unwrap());
+        let mut governor = RateGovernor::default();
+        governor.update_from_headers(&headers, now);
+        assert_eq!(governor.reserve(now), Duration::from_secs(42));
+
+        let mut governor = RateGovernor::default();
+        headers.insert("x-ratelimit-reset", "soon".parse().unwrap());
+        governor.update_from_headers(&headers, now);
+        assert_eq!(governor.reserve(now), Duration::ZERO);
+    }
+}
@@ -1,15 +1,50 @@
 #![allow(deprecated)] // base64::encode is deprecated
 
-use crate::reddit_comment::{RedditComment, Status, MAX_COMMENT_LENGTH};
-use anyhow::{anyhow, Error};
+use crate::commands::{self, Commands};
+use crate::config::Config;
+use crate::error::FactorionError;
+use crate::parser_stats::ParserStats;
+use crate::preferences::UserPreferences;
+use crate::rate_limit::RateGovernor;
+use crate::reddit_comment::{RedditComment, Status, Strictness, MAX_COMMENT_LENGTH};
+use crate::replied::RepliedIds;
 use base64::engine::general_purpose::STANDARD_NO_PAD;
 use base64::Engine;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use dotenv::dotenv;
 use reqwest::header::{HeaderMap, CONTENT_TYPE, USER_AGENT};
-use reqwest::{Client, Response};
+use reqwest::{Client, Response, StatusCode};
 use serde::Deserialize;
 use serde_json::{from_str, json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::time::{sleep, Duration};
+
+/// A comment the bot posted, with how it was voted on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OwnComment {
+    pub(crate) id: String,
+    /// In lowercase.
+    pub(crate) subreddit: String,
+    pub(crate) score: i64,
+    /// Unix time in seconds.
+    pub(crate) created: u64,
+}
+
+/// A private message sent to the bot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PrivateMessage {
+    pub(crate) id: String,
+    pub(crate) author: String,
+    pub(crate) body: String,
+}
+
+/// A reply chain that failed partway, with the replies that were posted before.
+#[derive(Debug)]
+pub(crate) struct ReplyChainError {
+    pub(crate) posted_ids: Vec<String>,
+    pub(crate) error: FactorionError,
+}
 
 #[derive(Deserialize, Debug)]
 struct TokenResponse {
@@ -23,15 +58,66 @@ struct Token {
 
 const REDDIT_TOKEN_URL: &str = "https://ssl.reddit.com/api/v1/access_token";
 const REDDIT_COMMENT_URL: &str = "https://oauth.reddit.com/api/comment";
+const REDDIT_UNREAD_URL: &str = "https://oauth.reddit.com/message/unread";
+const REDDIT_READ_MESSAGE_URL: &str = "https://oauth.reddit.com/api/read_message";
+const REDDIT_DELETE_URL: &str = "https://oauth.reddit.com/api/del";
+/// The OAuth scopes the token is requested with: reading comments, replying, the inbox,
+/// deleting downvoted replies and reading the scores of the own comments.
+const REDDIT_SCOPES: [&str; 5] = ["read", "submit", "privatemessages", "edit", "history"];
+// How many messages get marked as read with one request
+const READ_MESSAGE_BATCH_SIZE: usize = 25;
 
 pub(crate) struct RedditClient {
     client: Client,
     token: Token,
+    settings: ParseSettings,
+    /// Shared by every request to reddit, besides getting the token.
+    governor: Mutex<RateGovernor>,
+    /// How the comments fetched since the last [`Self::take_parser_stats`] were parsed.
+    parser_stats: ParserStats,
+    /// Comments that failed to parse, so they aren't parsed and logged again on every poll.
+    unparsable_comments: HashSet<String>,
+    /// Whether a subreddit, in lowercase, is NSFW or quarantined, for the mentions.
+    nsfw_subreddits: HashMap<String, bool>,
+}
+
+/// How the fetched comments are parsed, see [`RedditComment::try_new_with_settings`],
+/// and which ones are skipped right away.
+pub(crate) struct ParseSettings {
+    pub(crate) strictness: Strictness,
+    pub(crate) subreddit_commands: HashMap<String, Commands>,
+    pub(crate) unit_skip_list: Vec<String>,
+    pub(crate) max_jobs_per_comment: usize,
+    pub(crate) max_decimals: usize,
+    /// Lowercase names of users who never get replies.
+    pub(crate) blocked_users: HashSet<String>,
+    /// Lowercase names of subreddits in which comments are never answered.
+    pub(crate) blocked_subreddits: HashSet<String>,
+    /// Whether comments in NSFW and quarantined subreddits are answered.
+    pub(crate) answer_nsfw: bool,
+}
+
+impl ParseSettings {
+    pub(crate) fn from_config(config: &Config) -> Result<Self, FactorionError> {
+        let (blocked_users, blocked_subreddits) = config.blocklist();
+        Ok(Self {
+            strictness: config.parsing.factorial_strictness,
+            subreddit_commands: commands::parse_subreddit_commands(
+                &config.parsing.subreddit_commands,
+            )
+            .map_err(|e| FactorionError::config("SUBREDDIT_COMMANDS", e))?,
+            unit_skip_list: config.unit_skip_list(),
+            max_jobs_per_comment: config.parsing.max_jobs_per_comment,
+            max_decimals: config.replies.max_decimals,
+            blocked_users,
+            blocked_subreddits,
+            answer_nsfw: config.reddit.answer_nsfw,
+        })
+    }
 }
 
 impl RedditClient {
-    pub(crate) async fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        dotenv().ok();
+    pub(crate) async fn new(config: &Config) -> Result<Self, FactorionError> {
         let client_id = std::env::var("APP_CLIENT_ID").expect("APP_CLIENT_ID must be set.");
         let secret = std::env::var("APP_SECRET").expect("APP_SECRET must be set.");
 
@@ -46,24 +132,26 @@ impl RedditClient {
 
         let client = Client::builder().default_headers(headers).build()?;
 
-        Ok(Self { client, token })
+        Ok(Self {
+            client,
+            token,
+            settings: ParseSettings::from_config(config)?,
+            governor: Mutex::new(RateGovernor::default()),
+            parser_stats: ParserStats::default(),
+            unparsable_comments: HashSet::new(),
+            nsfw_subreddits: HashMap::new(),
+        })
     }
 
     pub(crate) async fn get_comments(
         &mut self,
         subreddit: &str,
         limit: u32,
-        already_replied_to_comments: &[String],
-    ) -> Result<Vec<RedditComment>, ()> {
-        if self.is_token_expired() {
-            println!("Token expired, getting new token");
-            self.token = RedditClient::get_reddit_token(
-                std::env::var("APP_CLIENT_ID").expect("APP_CLIENT_ID must be set."),
-                std::env::var("APP_SECRET").expect("APP_SECRET must be set."),
-            )
-            .await
-            .expect("Failed to get token");
-        }
+        already_replied_to_comments: &RepliedIds,
+        preferences: &mut UserPreferences,
+    ) -> Result<Vec<RedditComment>, FactorionError> {
+        self.refresh_token_if_expired().await;
+        self.wait_for_rate_limit().await;
 
         let response = self
             .client
@@ -73,16 +161,234 @@ impl RedditClient {
             ))
             .bearer_auth(&self.token.access_token)
             .send()
-            .await
-            .expect("Failed to get comments");
+            .await?;
+        self.update_rate_limit(&response);
 
-        match RedditClient::check_response_status(&response) {
-            Ok(_) => Ok(
-                RedditClient::extract_comments(response, already_replied_to_comments)
-                    .await
-                    .expect("Failed to extract comments"),
-            ),
-            Err(_) => Err(()),
+        RedditClient::check_response_status(&response)?;
+        RedditClient::extract_comments(
+            response,
+            already_replied_to_comments,
+            &self.settings,
+            preferences,
+            &mut self.parser_stats,
+            &mut self.unparsable_comments,
+        )
+        .await
+    }
+
+    /// Fetches the unread username mentions from the inbox.
+    pub(crate) async fn get_mentions(
+        &mut self,
+        limit: u32,
+        already_replied_to_comments: &RepliedIds,
+        preferences: &mut UserPreferences,
+    ) -> Result<Vec<RedditComment>, FactorionError> {
+        self.refresh_token_if_expired().await;
+        self.wait_for_rate_limit().await;
+
+        let response = self
+            .client
+            .get(format!("{}?limit={}", REDDIT_UNREAD_URL, limit))
+            .bearer_auth(&self.token.access_token)
+            .send()
+            .await?;
+        self.update_rate_limit(&response);
+
+        RedditClient::check_response_status(&response)?;
+        let mut mentions = RedditClient::mentions(&response.json::<Value>().await?);
+        // Unlike comments, messages in the inbox don't tell whether their subreddit is NSFW
+        if !self.settings.answer_nsfw {
+            self.look_up_nsfw_subreddits(&mentions).await;
+            RedditClient::mark_nsfw(&mut mentions, &self.nsfw_subreddits);
+        }
+        Ok(RedditClient::build_comments(
+            mentions,
+            already_replied_to_comments,
+            &self.settings,
+            preferences,
+            &mut self.parser_stats,
+            &mut self.unparsable_comments,
+        ))
+    }
+
+    /// Looks up whether the subreddits of the mentions are NSFW or quarantined, unless that is known already.
+    async fn look_up_nsfw_subreddits(&mut self, mentions: &[Value]) {
+        for mention in mentions {
+            let subreddit = RedditClient::subreddit_of(mention);
+            if subreddit.is_empty() || self.nsfw_subreddits.contains_key(&subreddit) {
+                continue;
+            }
+            match self.get_subreddit_about(&subreddit).await {
+                Ok(about) => {
+                    let nsfw = RedditClient::is_nsfw_subreddit(&about);
+                    self.nsfw_subreddits.insert(subreddit, nsfw);
+                }
+                Err(e) => e.log(&format!("Failed to check whether r/{subreddit} is NSFW")),
+            }
+        }
+    }
+
+    async fn get_subreddit_about(&mut self, subreddit: &str) -> Result<Value, FactorionError> {
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .get(format!("https://oauth.reddit.com/r/{}/about", subreddit))
+            .bearer_auth(&self.token.access_token)
+            .send()
+            .await?;
+        self.update_rate_limit(&response);
+        let response = response.error_for_status()?;
+        Ok(response.json::<Value>().await?)
+    }
+
+    fn is_nsfw_subreddit(about: &Value) -> bool {
+        about["data"]["over18"].as_bool().unwrap_or(false)
+            || about["data"]["quarantine"].as_bool().unwrap_or(false)
+    }
+
+    fn subreddit_of(thing: &Value) -> String {
+        thing["data"]["subreddit"]
+            .as_str()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+
+    /// Sets `over_18` on the mentions like on comments. Subreddits that couldn't be looked up count as NSFW,
+    /// so their mentions stay unread and are tried again on the next poll.
+    fn mark_nsfw(mentions: &mut [Value], nsfw_subreddits: &HashMap<String, bool>) {
+        for mention in mentions {
+            let subreddit = RedditClient::subreddit_of(mention);
+            let nsfw = nsfw_subreddits.get(&subreddit).copied().unwrap_or(true);
+            mention["data"]["over_18"] = json!(nsfw);
+        }
+    }
+
+    /// Fetches the unread private messages sent by one of the `owners`, given in lowercase.
+    pub(crate) async fn get_owner_messages(
+        &mut self,
+        limit: u32,
+        owners: &HashSet<String>,
+    ) -> Result<Vec<PrivateMessage>, FactorionError> {
+        self.refresh_token_if_expired().await;
+        self.wait_for_rate_limit().await;
+
+        let response = self
+            .client
+            .get(format!("{}?limit={}", REDDIT_UNREAD_URL, limit))
+            .bearer_auth(&self.token.access_token)
+            .send()
+            .await?;
+        self.update_rate_limit(&response);
+        let response = response.error_for_status()?;
+        let messages = response.json::<Value>().await?;
+        Ok(RedditClient::owner_messages(&messages, owners))
+    }
+
+    fn owner_messages(listing: &Value, owners: &HashSet<String>) -> Vec<PrivateMessage> {
+        listing["data"]["children"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            // Mentions and comment replies are in the inbox as well
+            .filter(|message| message["kind"].as_str() == Some("t4"))
+            .filter_map(|message| {
+                let data = &message["data"];
+                Some(PrivateMessage {
+                    id: data["id"].as_str()?.to_string(),
+                    author: data["author"].as_str()?.to_string(),
+                    body: data["body"].as_str()?.to_string(),
+                })
+            })
+            .filter(|message| owners.contains(&message.author.to_lowercase()))
+            .collect()
+    }
+
+    /// Answers a private message.
+    pub(crate) async fn reply_to_message(
+        &self,
+        message_id: &str,
+        reply: &str,
+    ) -> Result<(), FactorionError> {
+        self.post_reply("t4", message_id, reply).await?;
+        Ok(())
+    }
+
+    /// Marks the given things of a `kind` (`t1` for comments, `t4` for messages) as read in the inbox,
+    /// so they won't be fetched as unread again.
+    /// Failing batches are only logged, as they will just be fetched again next time.
+    pub(crate) async fn mark_read(&self, kind: &str, ids: &[String]) {
+        for batch in ids.chunks(READ_MESSAGE_BATCH_SIZE) {
+            let ids = batch
+                .iter()
+                .map(|id| format!("{kind}_{id}"))
+                .collect::<Vec<String>>()
+                .join(",");
+
+            self.wait_for_rate_limit().await;
+            let response = self
+                .client
+                .post(REDDIT_READ_MESSAGE_URL)
+                .bearer_auth(&self.token.access_token)
+                .form(&[("id", ids.as_str())])
+                .send()
+                .await;
+            if let Ok(response) = &response {
+                self.update_rate_limit(response);
+            }
+
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    println!("Marked {} messages as read", batch.len())
+                }
+                Ok(response) => eprintln!(
+                    "Failed to mark messages as read. Statuscode: {:#?}",
+                    response.status()
+                ),
+                Err(e) => eprintln!("Failed to mark messages as read: {:?}", e),
+            }
+        }
+    }
+
+    /// Waits until the rate limit allows another request.
+    async fn wait_for_rate_limit(&self) {
+        let wait = self
+            .governor
+            .lock()
+            .expect("Rate governor lock poisoned")
+            .reserve(Instant::now());
+        if !wait.is_zero() {
+            println!("Rate limit reached, waiting {:?}", wait);
+            sleep(wait).await;
+        }
+    }
+
+    fn update_rate_limit(&self, response: &Response) {
+        self.governor
+            .lock()
+            .expect("Rate governor lock poisoned")
+            .update_from_headers(response.headers(), Instant::now());
+    }
+
+    /// The parser statistics collected so far, starting over.
+    pub(crate) fn take_parser_stats(&mut self) -> ParserStats {
+        std::mem::take(&mut self.parser_stats)
+    }
+
+    /// Parses the comments fetched from now on with other settings, e.g. after the config was reloaded.
+    pub(crate) fn set_settings(&mut self, settings: ParseSettings) {
+        self.settings = settings;
+    }
+
+    async fn refresh_token_if_expired(&mut self) {
+        if self.is_token_expired() {
+            println!("Token expired, getting new token");
+            self.token = RedditClient::get_reddit_token(
+                std::env::var("APP_CLIENT_ID").expect("APP_CLIENT_ID must be set."),
+                std::env::var("APP_SECRET").expect("APP_SECRET must be set."),
+            )
+            .await
+            .expect("Failed to get token");
         }
     }
 
@@ -99,16 +405,149 @@ impl RedditClient {
         expired
     }
 
+    /// Checks whether the user moderates the subreddit.
+    pub(crate) async fn is_moderator(
+        &mut self,
+        subreddit: &str,
+        user: &str,
+    ) -> Result<bool, FactorionError> {
+        self.refresh_token_if_expired().await;
+        self.wait_for_rate_limit().await;
+
+        let response = self
+            .client
+            .get(format!(
+                "https://oauth.reddit.com/r/{}/about/moderators?user={}",
+                subreddit, user
+            ))
+            .bearer_auth(&self.token.access_token)
+            .send()
+            .await?;
+        self.update_rate_limit(&response);
+        let response = response.error_for_status()?;
+        let moderators = response.json::<Value>().await?;
+        Ok(RedditClient::lists_moderator(&moderators, user))
+    }
+
+    /// Fetches the latest comments the bot posted, newest first.
+    pub(crate) async fn get_own_comments(
+        &mut self,
+        limit: u32,
+    ) -> Result<Vec<OwnComment>, FactorionError> {
+        self.refresh_token_if_expired().await;
+        self.wait_for_rate_limit().await;
+
+        let username = std::env::var("REDDIT_USERNAME").expect("REDDIT_USERNAME must be set.");
+        let response = self
+            .client
+            .get(format!(
+                "https://oauth.reddit.com/user/{}/comments?limit={}",
+                username, limit
+            ))
+            .bearer_auth(&self.token.access_token)
+            .send()
+            .await?;
+        self.update_rate_limit(&response);
+        RedditClient::check_response_status(&response)?;
+        let comments = response.json::<Value>().await?;
+        Ok(RedditClient::own_comments(&comments))
+    }
+
+    /// Deletes a comment the bot posted.
+    pub(crate) async fn delete_comment(&self, comment_id: &str) -> Result<(), FactorionError> {
+        self.wait_for_rate_limit().await;
+        let response = self
+            .client
+            .post(REDDIT_DELETE_URL)
+            .bearer_auth(&self.token.access_token)
+            .form(&[("id", format!("t1_{comment_id}"))])
+            .send()
+            .await?;
+        self.update_rate_limit(&response);
+        response.error_for_status()?;
+        Ok(())
+    }
+
+    fn own_comments(listing: &Value) -> Vec<OwnComment> {
+        listing["data"]["children"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|comment| {
+                let data = &comment["data"];
+                Some(OwnComment {
+                    id: data["id"].as_str()?.to_string(),
+                    subreddit: data["subreddit"].as_str()?.to_lowercase(),
+                    score: data["score"].as_i64()?,
+                    created: data["created_utc"].as_f64()? as u64,
+                })
+            })
+            .collect()
+    }
+
+    fn lists_moderator(moderators: &Value, user: &str) -> bool {
+        moderators["data"]["children"]
+            .as_array()
+            .is_some_and(|children| {
+                children.iter().any(|moderator| {
+                    moderator["name"]
+                        .as_str()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(user))
+                })
+            })
+    }
+
+    /// Posts the replies as a chain, the first one answering the comment and every further one the reply before.
+    /// Returns the ids of all posted replies, the chain stops early if the id of a reply is unknown.
+    /// If posting fails, the error keeps the ids of the replies posted before.
     pub(crate) async fn reply_to_comment(
         &self,
-        comment: RedditComment,
+        comment_id: &str,
+        replies: &[String],
+    ) -> Result<Vec<String>, ReplyChainError> {
+        let mut posted_ids: Vec<String> = vec![];
+        let mut parent_id = comment_id.to_string();
+
+        for (i, reply) in replies.iter().enumerate() {
+            if i > 0 {
+                // Sleep to not spam comments too quickly
+                sleep(Duration::from_secs(2)).await;
+            }
+            let posted_id = match self.post_reply("t1", &parent_id, reply).await {
+                Ok(posted_id) => posted_id,
+                Err(error) => return Err(ReplyChainError { posted_ids, error }),
+            };
+            let Some(posted_id) = posted_id else {
+                if i + 1 < replies.len() {
+                    eprintln!(
+                        "Comment ID {} -> Could not continue the reply chain, the id of the reply is unknown",
+                        comment_id
+                    );
+                }
+                break;
+            };
+            posted_ids.push(posted_id.clone());
+            parent_id = posted_id;
+        }
+
+        Ok(posted_ids)
+    }
+
+    /// Replies to the thing of a `kind` (`t1` for comments, `t4` for messages) with the given id,
+    /// returning the id of the reply if reddit tells it.
+    async fn post_reply(
+        &self,
+        kind: &str,
+        parent_id: &str,
         reply: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<Option<String>, FactorionError> {
         let params = json!({
-            "thing_id": format!("t1_{}", comment.id),
+            "thing_id": format!("{}_{}", kind, parent_id),
             "text": reply
         });
 
+        self.wait_for_rate_limit().await;
         let response = self
             .client
             .post(REDDIT_COMMENT_URL)
@@ -116,29 +555,64 @@ impl RedditClient {
             .form(&params)
             .send()
             .await?;
+        self.update_rate_limit(&response);
+
+        // Reddit answers with 403 when the bot is banned from the subreddit
+        if response.status() == StatusCode::FORBIDDEN {
+            eprintln!("Comment ID {} -> Status FAILED: 403 Forbidden", parent_id);
+            return Err(FactorionError::Rejected("403 Forbidden".to_string()));
+        }
+        // Reddit being down isn't the fault of the reply, so it can be tried again later
+        if response.status().is_server_error() || response.status() == StatusCode::TOO_MANY_REQUESTS
+        {
+            eprintln!(
+                "Comment ID {} -> Status FAILED: {}",
+                parent_id,
+                response.status()
+            );
+            return Err(FactorionError::Unavailable(response.status().to_string()));
+        }
 
         let response_text = &response.text().await?;
         let response_text = response_text.as_str();
-        let response_json =
-            from_str::<Value>(response_text).expect("Failed to convert response to json");
+        let response_json = from_str::<Value>(response_text)?;
         let response_status_err = !RedditClient::is_success(response_text);
 
         if response_status_err {
+            let error_message = RedditClient::get_error_message(response_json);
             eprintln!(
                 "Comment ID {} -> Status FAILED: {:#?}",
-                comment.id,
-                RedditClient::get_error_message(response_json)
+                parent_id, error_message
             );
-            return Err(anyhow!("Failed to reply to comment"));
+            return Err(RedditClient::reply_error(error_message));
         }
 
+        let posted_id = RedditClient::get_posted_comment_id(&response_json);
         println!(
             "Comment ID {} -> Status OK: {:#?}",
-            comment.id,
+            parent_id,
             RedditClient::get_error_message(response_json)
         );
 
-        Ok(())
+        Ok(posted_id)
+    }
+
+    /// Finds the id of the newly posted comment in the jquery response of reddit.
+    fn get_posted_comment_id(response_json: &Value) -> Option<String> {
+        response_json["jquery"]
+            .as_array()?
+            .iter()
+            .find_map(|array| array[3][0][0]["data"]["id"].as_str())
+            .map(str::to_string)
+    }
+
+    /// Only rate limits are worth trying again, other errors like a deleted comment or a locked thread stay.
+    fn reply_error(error_message: String) -> FactorionError {
+        if error_message.contains("RATELIMIT") {
+            FactorionError::Unavailable(error_message)
+        } else {
+            FactorionError::Rejected(error_message)
+        }
     }
 
     fn get_error_message(response_json: Value) -> String {
@@ -173,7 +647,7 @@ impl RedditClient {
     async fn get_reddit_token(
         client_id: String,
         client_secret: String,
-    ) -> Result<Token, Box<dyn std::error::Error>> {
+    ) -> Result<Token, FactorionError> {
         let password = std::env::var("REDDIT_PASSWORD").expect("REDDIT_PASSWORD must be set.");
         let username = std::env::var("REDDIT_USERNAME").expect("REDDIT_USERNAME must be set.");
 
@@ -184,11 +658,12 @@ impl RedditClient {
         headers.insert(USER_AGENT, user_agent.parse()?);
         headers.insert(CONTENT_TYPE, "application/x-www-form-urlencoded".parse()?);
 
+        let scope = REDDIT_SCOPES.join(" ");
         let params = [
             ("grant_type", "password"),
             ("username", username.as_str()),
             ("password", password.as_str()),
-            ("scope", "read submit"),
+            ("scope", scope.as_str()),
         ];
 
         let response = Client::new()
@@ -201,7 +676,10 @@ impl RedditClient {
 
         if !response.status().is_success() {
             println!("Failed to get token: {:#?}", response);
-            return Err("Failed to get token".into());
+            return Err(FactorionError::Rejected(format!(
+                "Failed to get token: {}",
+                response.status()
+            )));
         }
 
         let response = response.json::<TokenResponse>().await?;
@@ -241,14 +719,14 @@ impl RedditClient {
         datetime
     }
 
-    fn check_response_status(response: &Response) -> Result<(), ()> {
-        if !response.status().is_success() {
-            println!(
-                "Failed to get comments. Statuscode: {:#?}. Response: {:#?}",
-                response.status(),
-                response
-            );
-            return Err(());
+    /// Rate limits and errors of reddit itself can go away, anything else is a rejection.
+    fn check_response_status(response: &Response) -> Result<(), FactorionError> {
+        let status = response.status();
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(FactorionError::Unavailable(status.to_string()));
+        }
+        if !status.is_success() {
+            return Err(FactorionError::Rejected(status.to_string()));
         }
 
         Ok(())
@@ -256,14 +734,47 @@ impl RedditClient {
 
     async fn extract_comments(
         response: Response,
-        already_replied_to_comments: &[String],
-    ) -> Result<Vec<RedditComment>, Box<dyn std::error::Error>> {
+        already_replied_to_comments: &RepliedIds,
+        settings: &ParseSettings,
+        preferences: &mut UserPreferences,
+        stats: &mut ParserStats,
+        unparsable_comments: &mut HashSet<String>,
+    ) -> Result<Vec<RedditComment>, FactorionError> {
         let response_json = response.json::<Value>().await?;
         let comments_json = response_json["data"]["children"]
             .as_array()
             .cloned()
             .unwrap_or_default();
 
+        Ok(RedditClient::build_comments(
+            comments_json,
+            already_replied_to_comments,
+            settings,
+            preferences,
+            stats,
+            unparsable_comments,
+        ))
+    }
+
+    fn mentions(response_json: &Value) -> Vec<Value> {
+        // The inbox also contains private messages and replies, we only want the mentions
+        response_json["data"]["children"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|message| message["data"]["type"].as_str() == Some("username_mention"))
+            .collect()
+    }
+
+    fn build_comments(
+        comments_json: Vec<Value>,
+        already_replied_to_comments: &RepliedIds,
+        settings: &ParseSettings,
+        preferences: &mut UserPreferences,
+        stats: &mut ParserStats,
+        unparsable_comments: &mut HashSet<String>,
+    ) -> Vec<RedditComment> {
         let mut comments = Vec::new();
         for comment in comments_json {
             let body = comment["data"]["body"].as_str().unwrap_or("");
@@ -273,7 +784,71 @@ impl RedditClient {
                 .unwrap_or_default()
                 .to_string();
 
-            let mut comment = RedditComment::new(body, &comment_id);
+            // Replies to locked or archived threads will always fail
+            let thread_locked = comment["data"]["locked"].as_bool().unwrap_or(false)
+                || comment["data"]["archived"].as_bool().unwrap_or(false);
+
+            let subreddit = comment["data"]["subreddit"]
+                .as_str()
+                .unwrap_or_default()
+                .to_lowercase();
+            let author = comment["data"]["author"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let thread = comment["data"]["link_id"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let parent = comment["data"]["parent_id"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let nsfw = comment["data"]["over_18"].as_bool().unwrap_or(false)
+                || comment["data"]["quarantine"].as_bool().unwrap_or(false);
+            if settings.blocked_users.contains(&author.to_lowercase())
+                || settings.blocked_subreddits.contains(&subreddit)
+                || (nsfw && !settings.answer_nsfw)
+            {
+                continue;
+            }
+            preferences.update_from_comment(&author, body);
+            let user_preferences = preferences.get(&author);
+            if user_preferences.opted_out || unparsable_comments.contains(&comment_id) {
+                continue;
+            }
+            let commands = settings
+                .subreddit_commands
+                .get(&subreddit)
+                .copied()
+                .unwrap_or_default()
+                | user_preferences.commands;
+
+            let mut comment = match RedditComment::try_new_with_settings(
+                body,
+                &comment_id,
+                settings.strictness,
+                commands,
+                &settings.unit_skip_list,
+                settings.max_jobs_per_comment,
+                settings.max_decimals,
+            ) {
+                Ok(comment) => comment,
+                Err(e) => {
+                    eprintln!("Skipping comment {comment_id}: {e}");
+                    stats.record_failure();
+                    unparsable_comments.insert(comment_id);
+                    continue;
+                }
+            };
+            comment.subreddit = subreddit;
+            comment.author = author;
+            comment.thread = thread;
+            comment.parent = parent;
+
+            if thread_locked {
+                comment.add_status(Status::ThreadLocked);
+            }
 
             // set some statuses
             if !comment.status.contains(&Status::ReplyWouldBeTooLong)
@@ -287,10 +862,11 @@ impl RedditClient {
             } else {
                 comment.add_status(Status::NotReplied);
             }
+            stats.record(&comment);
             comments.push(comment);
         }
 
-        Ok(comments)
+        comments
     }
 }
 
@@ -298,6 +874,35 @@ impl RedditClient {
 mod tests {
     use super::*;
 
+    fn settings() -> ParseSettings {
+        ParseSettings {
+            strictness: Strictness::Normal,
+            subreddit_commands: HashMap::new(),
+            unit_skip_list: vec![],
+            max_jobs_per_comment: 100,
+            max_decimals: 1_000,
+            blocked_users: HashSet::new(),
+            blocked_subreddits: HashSet::new(),
+            answer_nsfw: true,
+        }
+    }
+
+    /// A listing like reddit answers `get_comments` and `get_mentions` with, containing `children`.
+    fn listing(children: Vec<Value>) -> Response {
+        let body = json!({"kind": "Listing", "data": {"children": children}});
+        Response::from(
+            http::Response::builder()
+                .status(200)
+                .body(body.to_string())
+                .unwrap(),
+        )
+    }
+
+    /// A thing of a listing, `t1` for comments, with the given fields, like `{"body": "5!", "id": "abc"}`.
+    fn thing(kind: &str, data: Value) -> Value {
+        json!({"kind": kind, "data": data})
+    }
+
     #[tokio::test]
     async fn test_extract_comments() {
         let response = Response::from(http::Response::builder().status(200).body(r#"{
@@ -328,18 +933,363 @@ mod tests {
                    ]
                }
            }"#).unwrap());
-        let comments = RedditClient::extract_comments(response, &[]).await.unwrap();
+        let comments = RedditClient::extract_comments(
+            response,
+            &RepliedIds::default(),
+            &settings(),
+            &mut UserPreferences::default(),
+            &mut ParserStats::default(),
+            &mut HashSet::new(),
+        )
+        .await
+        .unwrap();
         assert_eq!(comments.len(), 2);
         println!("{:#?}", comments);
     }
 
+    #[tokio::test]
+    async fn test_extract_comments_locked_and_archived() {
+        let response = listing(vec![
+            thing(
+                "t1",
+                json!({"body": "5!", "id": "m38msum", "locked": true, "archived": false}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "6!", "id": "m38msun", "locked": false, "archived": true}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "7!", "id": "m38msuo", "locked": false, "archived": false}),
+            ),
+        ]);
+        let comments = RedditClient::extract_comments(
+            response,
+            &RepliedIds::default(),
+            &settings(),
+            &mut UserPreferences::default(),
+            &mut ParserStats::default(),
+            &mut HashSet::new(),
+        )
+        .await
+        .unwrap();
+        assert!(comments[0].status.contains(&Status::ThreadLocked));
+        assert!(comments[1].status.contains(&Status::ThreadLocked));
+        assert!(!comments[2].status.contains(&Status::ThreadLocked));
+    }
+
+    #[tokio::test]
+    async fn test_extract_comments_thread() {
+        let response = listing(vec![
+            thing(
+                "t1",
+                json!({"body": "5!", "id": "m1", "link_id": "t3_p1", "parent_id": "t3_p1"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "6!", "id": "m2", "link_id": "t3_p1", "parent_id": "t1_m1"}),
+            ),
+        ]);
+        let comments = RedditClient::extract_comments(
+            response,
+            &RepliedIds::default(),
+            &settings(),
+            &mut UserPreferences::default(),
+            &mut ParserStats::default(),
+            &mut HashSet::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(comments[0].thread, "t3_p1");
+        assert!(comments[0].is_top_level());
+        assert_eq!(comments[1].parent, "t1_m1");
+        assert!(!comments[1].is_top_level());
+    }
+
+    #[tokio::test]
+    async fn test_extract_comments_user_preferences() {
+        let response = listing(vec![
+            thing(
+                "t1",
+                json!({"body": "!prefer short", "id": "m38msum", "author": "Someone"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "30!", "id": "m38msun", "author": "someone"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "30!", "id": "m38msuo", "author": "someone_else"}),
+            ),
+        ]);
+        let mut preferences = UserPreferences::default();
+        let comments = RedditClient::extract_comments(
+            response,
+            &RepliedIds::default(),
+            &settings(),
+            &mut preferences,
+            &mut ParserStats::default(),
+            &mut HashSet::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(preferences.get("someone").commands, Commands::SHORT);
+        assert_eq!(comments[1].commands, Commands::SHORT);
+        assert_eq!(comments[1].author, "someone");
+        assert_eq!(comments[2].commands, Commands::NONE);
+    }
+
+    #[tokio::test]
+    async fn test_extract_comments_opt_out_and_blocklist() {
+        let response = listing(vec![
+            thing(
+                "t1",
+                json!({"body": "5! and !optout", "id": "m38msum", "author": "Someone"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "6!", "id": "m38msun", "author": "someone"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "7!", "id": "m38msuo", "author": "Spammer"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "8!", "id": "m38msup", "author": "x", "subreddit": "Quiet"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "9!", "id": "m38msuq", "author": "x", "subreddit": "mathmemes"}),
+            ),
+        ]);
+        let mut settings = settings();
+        settings.blocked_users.insert("spammer".to_string());
+        settings.blocked_subreddits.insert("quiet".to_string());
+        let mut preferences = UserPreferences::default();
+        let comments = RedditClient::extract_comments(
+            response,
+            &RepliedIds::default(),
+            &settings,
+            &mut preferences,
+            &mut ParserStats::default(),
+            &mut HashSet::new(),
+        )
+        .await
+        .unwrap();
+        assert!(preferences.get("someone").opted_out);
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].id, "m38msuq");
+    }
+
+    #[tokio::test]
+    async fn test_extract_comments_nsfw() {
+        let nsfw = || {
+            listing(vec![
+                thing("t1", json!({"body": "5!", "id": "m1", "over_18": true})),
+                thing("t1", json!({"body": "6!", "id": "m2", "quarantine": true})),
+                thing("t1", json!({"body": "7!", "id": "m3", "over_18": false})),
+            ])
+        };
+        let extract = |response, settings| async move {
+            RedditClient::extract_comments(
+                response,
+                &RepliedIds::default(),
+                &settings,
+                &mut UserPreferences::default(),
+                &mut ParserStats::default(),
+                &mut HashSet::new(),
+            )
+            .await
+            .unwrap()
+        };
+        assert_eq!(extract(nsfw(), settings()).await.len(), 3);
+        let mut sfw_only = settings();
+        sfw_only.answer_nsfw = false;
+        let comments = extract(nsfw(), sfw_only).await;
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].id, "m3");
+    }
+
+    #[tokio::test]
+    async fn test_extract_comments_unparsable() {
+        let pathological = format!("{}1", "(".repeat(5000));
+        let response = || {
+            listing(vec![
+                thing("t1", json!({"body": pathological, "id": "m1"})),
+                thing("t1", json!({"body": "5!", "id": "m2"})),
+            ])
+        };
+        let mut unparsable_comments = HashSet::new();
+        let mut stats = ParserStats::default();
+        for _ in 0..2 {
+            let comments = RedditClient::extract_comments(
+                response(),
+                &RepliedIds::default(),
+                &settings(),
+                &mut UserPreferences::default(),
+                &mut stats,
+                &mut unparsable_comments,
+            )
+            .await
+            .unwrap();
+            assert_eq!(comments.len(), 1);
+            assert_eq!(comments[0].id, "m2");
+        }
+        // Only parsed and counted the first time
+        assert_eq!(unparsable_comments, HashSet::from(["m1".to_string()]));
+        assert!(stats
+            .to_string()
+            .starts_with("Parsed 3 comments (1 failed)"));
+    }
+
+    #[test]
+    fn test_lists_moderator() {
+        let moderators =
+            json!({"kind": "UserList", "data": {"children": [{"name": "SomeMod", "id": "t2_1"}]}});
+        assert!(RedditClient::lists_moderator(&moderators, "somemod"));
+        assert!(!RedditClient::lists_moderator(&moderators, "someone"));
+        assert!(!RedditClient::lists_moderator(
+            &json!({"data": {"children": []}}),
+            "somemod"
+        ));
+        assert!(!RedditClient::lists_moderator(&json!({}), "somemod"));
+    }
+
+    #[test]
+    fn test_extract_mentions() {
+        let response = json!({"kind": "Listing", "data": {"children": [
+            thing(
+                "t1",
+                json!({"body": "u/factorion-bot 5!", "id": "m38msum", "type": "username_mention"}),
+            ),
+            thing(
+                "t1",
+                json!({"body": "6!", "id": "m38msun", "type": "comment_reply"}),
+            ),
+            thing("t4", json!({"body": "7!", "id": "m38msuo"})),
+        ]}});
+        let mentions = RedditClient::build_comments(
+            RedditClient::mentions(&response),
+            &RepliedIds::default(),
+            &settings(),
+            &mut UserPreferences::default(),
+            &mut ParserStats::default(),
+            &mut HashSet::new(),
+        );
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].id, "m38msum");
+        assert!(mentions[0].status.contains(&Status::FactorialsFound));
+    }
+
+    #[test]
+    fn test_extract_mentions_nsfw() {
+        let mention = |id, subreddit| {
+            thing(
+                "t1",
+                json!({"body": "u/factorion-bot 5!", "id": id, "subreddit": subreddit, "type": "username_mention"}),
+            )
+        };
+        let response = json!({"data": {"children": [
+            mention("m1", "mathmemes"),
+            mention("m2", "SomeNsfwSub"),
+            mention("m3", "unknown"),
+        ]}});
+        let nsfw_subreddits = HashMap::from([
+            ("mathmemes".to_string(), false),
+            ("somensfwsub".to_string(), true),
+        ]);
+        let mut mentions = RedditClient::mentions(&response);
+        RedditClient::mark_nsfw(&mut mentions, &nsfw_subreddits);
+
+        let mut sfw_only = settings();
+        sfw_only.answer_nsfw = false;
+        let mentions = RedditClient::build_comments(
+            mentions,
+            &RepliedIds::default(),
+            &sfw_only,
+            &mut UserPreferences::default(),
+            &mut ParserStats::default(),
+            &mut HashSet::new(),
+        );
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].id, "m1");
+    }
+
+    #[test]
+    fn test_is_nsfw_subreddit() {
+        assert!(RedditClient::is_nsfw_subreddit(
+            &json!({"kind": "t5", "data": {"over18": true, "quarantine": false}})
+        ));
+        assert!(RedditClient::is_nsfw_subreddit(
+            &json!({"kind": "t5", "data": {"over18": false, "quarantine": true}})
+        ));
+        assert!(!RedditClient::is_nsfw_subreddit(
+            &json!({"kind": "t5", "data": {"over18": false, "quarantine": false}})
+        ));
+    }
+
+    #[test]
+    fn test_own_comments() {
+        let comments = json!({"data": {"children": [
+            thing("t1", json!({"id": "m1", "subreddit": "MathMemes", "score": -3, "created_utc": 1735058224.0})),
+            thing("t1", json!({"id": "m2", "subreddit": "askmath"})),
+        ]}});
+        assert_eq!(
+            RedditClient::own_comments(&comments),
+            vec![OwnComment {
+                id: "m1".to_string(),
+                subreddit: "mathmemes".to_string(),
+                score: -3,
+                created: 1735058224,
+            }]
+        );
+        assert!(RedditClient::own_comments(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_owner_messages() {
+        let owners = HashSet::from(["owner".to_string()]);
+        let messages = json!({"data": {"children": [
+            thing("t4", json!({"id": "a1", "author": "Owner", "body": "status"})),
+            thing("t4", json!({"id": "a2", "author": "someone", "body": "pause"})),
+            thing("t1", json!({"id": "a3", "author": "owner", "body": "u/factorion-bot pause"})),
+        ]}});
+        assert_eq!(
+            RedditClient::owner_messages(&messages, &owners),
+            vec![PrivateMessage {
+                id: "a1".to_string(),
+                author: "Owner".to_string(),
+                body: "status".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn test_check_response_status() {
         let response = Response::from(http::Response::builder().status(200).body("").unwrap());
-        assert_eq!(RedditClient::check_response_status(&response), Ok(()));
+        assert!(RedditClient::check_response_status(&response).is_ok());
 
         let response = Response::from(http::Response::builder().status(404).body("").unwrap());
-        assert_eq!(RedditClient::check_response_status(&response), Err(()));
+        assert!(matches!(
+            RedditClient::check_response_status(&response),
+            Err(FactorionError::Rejected(_))
+        ));
+
+        let response = Response::from(http::Response::builder().status(503).body("").unwrap());
+        assert!(matches!(
+            RedditClient::check_response_status(&response),
+            Err(FactorionError::Unavailable(_))
+        ));
+    }
+
+    #[test]
+    fn test_reddit_scopes() {
+        // Posting replies and messages, reading the inbox, deleting own comments via /api/del
+        // and listing them via /user/{name}/comments
+        for scope in ["read", "submit", "privatemessages", "edit", "history"] {
+            assert!(REDDIT_SCOPES.contains(&scope), "missing scope {scope}");
+        }
     }
 
     #[test]
@@ -351,4 +1301,46 @@ mod tests {
             DateTime::from_naive_utc_and_offset(NaiveDateTime::from_timestamp(1735144624, 0), Utc);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_get_posted_comment_id() {
+        let response = json!({
+            "jquery": [
+                [0, 1, "call", ["body"]],
+                [18, 19, "call", [[{"kind": "t1", "data": {"id": "m1abcde", "parent": "t1_m0abcde"}}]]],
+                [19, 20, "call", []]
+            ],
+            "success": true
+        });
+        assert_eq!(
+            RedditClient::get_posted_comment_id(&response),
+            Some("m1abcde".to_string())
+        );
+
+        let response = json!({"jquery": [[0, 1, "call", ["body"]]], "success": true});
+        assert_eq!(RedditClient::get_posted_comment_id(&response), None);
+    }
+
+    #[test]
+    fn test_reply_error() {
+        let response = json!({
+            "jquery": [
+                [10, 11, "call", [".error.RATELIMIT.field-ratelimit"]],
+                [11, 12, "attr", "text"],
+                [12, 13, "call", ["you are doing that too much. try again in 5 minutes."]]
+            ],
+            "success": false
+        });
+        let error = RedditClient::reply_error(RedditClient::get_error_message(response));
+        assert!(matches!(error, FactorionError::Unavailable(_)));
+        assert!(error.is_retryable());
+
+        let response = json!({
+            "jquery": [[10, 11, "call", [".error.DELETED_COMMENT.field-parent"]]],
+            "success": false
+        });
+        let error = RedditClient::reply_error(RedditClient::get_error_message(response));
+        assert!(matches!(error, FactorionError::Rejected(_)));
+        assert!(!error.is_retryable());
+    }
 }
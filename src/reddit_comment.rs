@@ -1,28 +1,293 @@
+use crate::cache;
+use crate::commands::Commands;
+use crate::error::ParseError;
+use crate::fun_facts;
 use crate::math;
 use fancy_regex::Regex;
 use num_bigint::BigInt;
-use num_traits::{One, ToPrimitive};
+use num_traits::{One, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Write;
+use std::ops::Range;
+use std::time::{Duration, Instant};
 
 pub(crate) const UPPER_CALCULATION_LIMIT: i64 = 100_001;
-const PLACEHOLDER: &str = "Factorial of ";
-const FOOTER_TEXT: &str =
+/// Currencies and units used with the `units` command, if no other list is configured.
+pub(crate) const DEFAULT_UNIT_SKIP_LIST: &[&str] = &[
+    "$",
+    "€",
+    "£",
+    "¥",
+    "₹",
+    "usd",
+    "eur",
+    "gbp",
+    "dollars",
+    "euros",
+    "bucks",
+    "%",
+    "kg",
+    "lbs",
+    "lb",
+    "oz",
+    "km",
+    "mi",
+    "miles",
+    "cm",
+    "mm",
+    "ft",
+    "pcs",
+    "days",
+    "business days",
+    "weeks",
+    "months",
+    "years",
+    "hours",
+    "hrs",
+    "minutes",
+    "mins",
+    "seconds",
+    "secs",
+];
+pub(crate) const FOOTER_TEXT: &str =
     "\n*^(This action was performed by a bot. Please DM me if you have any questions.)*";
-pub(crate) const MAX_COMMENT_LENGTH: i64 = 10_000 - 10 - FOOTER_TEXT.len() as i64;
+// Custom footers may not be longer than this, so they always fit into the reply
+pub(crate) const MAX_FOOTER_LENGTH: usize = 200;
+pub(crate) const MAX_COMMENT_LENGTH: i64 = 10_000 - 10 - MAX_FOOTER_LENGTH as i64;
 pub(crate) const NUMBER_DECIMALS_SCIENTIFIC: usize = 100;
+/// How many decimals `!precision` may ask for, if no other limit is configured.
+pub(crate) const DEFAULT_MAX_DECIMALS: usize = 1_000;
+// Longer numbers in inverse queries are written in scientific notation
+const MAX_INVERSE_NUMBER_LENGTH: usize = 50;
+const TOO_LONG_HEADER: &str = "If I post the whole numbers, the comment would get too long, as reddit only allows up to 10k characters.\n\n\
+    In scientific notation the results would look roughly like that:\n\n";
+// Commands in brackets right behind an expression only apply to it, like `5! [digits]` or `100!? [short, facts]`
+const INLINE_COMMANDS_PATTERN: &str = r"^\??[ \t]*\[\s*([a-zA-Z]+(?:\s*,\s*[a-zA-Z]+)*)\s*\]";
+// Ends every comment of a reply chain but the last one
+const CHAIN_CONTINUED: &str = ",\n\n_(continued in the reply below)_\n\n";
+// Ends every comment of a `full` reply chain but the last one, which may stop in the middle of a number
+const FULL_CHAIN_CONTINUED: &str = "\n\n_(continued in the reply below)_\n\n";
+
+/// Spelled-out numbers and their values, for factorials like "five factorial" or "twenty three!".
+const NUMBER_WORDS: [(&str, u64); 31] = [
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+    ("sixteen", 16),
+    ("seventeen", 17),
+    ("eighteen", 18),
+    ("nineteen", 19),
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+    ("hundred", 100),
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+];
+
+// Levels above zero are multifactorials, the others encode special operations
+pub(crate) const PRIMORIAL_LEVEL: i64 = -1;
+pub(crate) const HYPERFACTORIAL_LEVEL: i64 = -2;
+pub(crate) const SUPERFACTORIAL_LEVEL: i64 = -3;
+pub(crate) const LEFT_FACTORIAL_LEVEL: i64 = -4;
+pub(crate) const ALTERNATING_FACTORIAL_LEVEL: i64 = -5;
+pub(crate) const EXPONENTIAL_FACTORIAL_LEVEL: i64 = -6;
+pub(crate) const TETRATION_LEVEL: i64 = -7;
+
+// Above these only the number of digits is approximated
+pub(crate) const UPPER_HYPERFACTORIAL_LIMIT: u64 = 500;
+pub(crate) const UPPER_SUPERFACTORIAL_LIMIT: u64 = 500;
+pub(crate) const UPPER_LEFT_FACTORIAL_LIMIT: u64 = 10_000;
+pub(crate) const UPPER_ALTERNATING_FACTORIAL_LIMIT: u64 = 10_000;
+pub(crate) const UPPER_EXPONENTIAL_FACTORIAL_LIMIT: u64 = 5;
+// Further away from zero, factorials of negative non-integers are too close to zero for an f64
+pub(crate) const UPPER_GAMMA_LIMIT: f64 = 170.0;
+// More decimals would only show the rounding errors of an f64
+pub(crate) const GAMMA_DECIMALS: usize = 10;
+// Tetrations with more digits than this are only approximated
+pub(crate) const UPPER_TETRATION_DIGITS: f64 = 3_000.0;
+// Above this even the tower of the digits of a tetration gets too long to write down
+pub(crate) const UPPER_TETRATION_HEIGHT: u64 = 50;
+// Above this n! mod m takes too long, unless m divides n!
+pub(crate) const UPPER_MODULAR_LIMIT: u64 = 10_000_000;
+// Above this the prime factorization of a factorial gets too long for a reply
+pub(crate) const UPPER_PRIME_FACTORS_LIMIT: u64 = 1_000;
+// Above this even the tower of the digits gets too long to write down
+pub(crate) const UPPER_EXPONENTIAL_FACTORIAL_TOWER_LIMIT: u64 = 50;
+// Arithmetic in parentheses gives up on results with more bits, they are far too big for a factorial anyway
+pub(crate) const UPPER_EXPRESSION_BITS: u64 = 10_000;
+/// How many calculations a comment gets, if no other cap is configured.
+pub(crate) const DEFAULT_MAX_JOBS_PER_COMMENT: usize = 100;
+/// How long the calculations of a single comment may take, before the rest is skipped.
+/// Every calculation on its own is bounded by the limits above, but many near them add up.
+pub(crate) const CALCULATION_TIME_LIMIT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
 pub(crate) struct Factorial {
     pub(crate) number: u64,
-    pub(crate) level: u64,
+    pub(crate) level: i64,
     pub(crate) factorial: BigInt,
 }
 
+#[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
+pub(crate) struct Binomial {
+    pub(crate) n: u64,
+    pub(crate) k: u64,
+    pub(crate) coefficient: BigInt,
+}
+
+/// A result that is too big to calculate, so only its number of digits is approximated.
+#[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
+pub(crate) struct ApproximateDigits {
+    pub(crate) number: u64,
+    pub(crate) level: i64,
+    pub(crate) digits: u64,
+}
+
+/// The number of trailing zeros of a factorial, known even if the factorial is too big to calculate.
+#[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
+pub(crate) struct TrailingZeros {
+    pub(crate) number: BigInt,
+    pub(crate) zeros: BigInt,
+}
+
+/// The factorial of a negative non-integer like `(-2.5)!`, through the gamma function.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GammaFactorial {
+    pub(crate) number: f64,
+    pub(crate) factorial: f64,
+}
+
+/// A tetration a↑↑b, a tower of b copies of a.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tetration {
+    pub(crate) base: u64,
+    pub(crate) height: u64,
+    pub(crate) result: TetrationResult,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TetrationResult {
+    Exact(BigInt),
+    /// Too big to calculate, only the number of digits is approximated as a power tower.
+    DigitsTower(ApproximateDigitsTower),
+}
+
+/// A ratio of factorials n! / k!, calculated as a falling factorial.
+/// If n < k the ratio is 1 / `ratio`.
+#[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
+pub(crate) struct FactorialRatio {
+    pub(crate) numerator: u64,
+    pub(crate) denominator: u64,
+    pub(crate) ratio: BigInt,
+}
+
+/// A factorial modulo some number, like `100000! mod 1000000007`.
+#[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
+pub(crate) struct Modular {
+    pub(crate) number: BigInt,
+    pub(crate) level: i64,
+    pub(crate) modulus: u64,
+    pub(crate) remainder: u64,
+}
+
+/// The prime factorization of a factorial, as pairs of prime and exponent.
+#[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
+pub(crate) struct PrimeFactorization {
+    pub(crate) number: u64,
+    pub(crate) factors: Vec<(u64, u64)>,
+}
+
+/// A result so big that even its number of digits can only be written as a power tower,
+/// 10^10^...^top with `depth` tens.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ApproximateDigitsTower {
+    pub(crate) number: u64,
+    pub(crate) level: i64,
+    pub(crate) depth: u64,
+    pub(crate) top: f64,
+}
+
+/// An inverse query like `!inverse 120` or "what termial is 55?".
+/// `n` is the number whose factorial (or termial) is `number`, or the one just below if there is none.
+#[derive(Debug, Clone, PartialEq, Ord, Eq, Hash, PartialOrd)]
+pub(crate) struct InverseQuery {
+    pub(crate) number: BigInt,
+    /// Asks for the termial n? = 1 + 2 + ... + n instead of the factorial.
+    pub(crate) termial: bool,
+    pub(crate) n: BigInt,
+    pub(crate) exact: bool,
+}
+
+/// The factorial of a number written in e-notation like `1e999999999999!` or as a power like `(2^100)!`,
+/// too big to be calculated.
+/// `digits` is its number of digits as a power tower `(depth, top)`, like in [`ApproximateDigitsTower`].
+/// `None` if the number is too large to even approximate that.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ScientificNotationFactorial {
+    /// The number as it was written in the comment.
+    pub(crate) number: String,
+    pub(crate) digits: Option<(u64, f64)>,
+}
+
 #[derive(Debug)]
 pub(crate) struct RedditComment {
     pub(crate) id: String,
+    /// The subreddit the comment was posted in, in lowercase. Empty if unknown.
+    pub(crate) subreddit: String,
+    /// The name of the user who wrote the comment. Empty if unknown.
+    pub(crate) author: String,
+    /// The post the comment was written under, like `t3_1hbp2rk`. Empty if unknown.
+    pub(crate) thread: String,
+    /// What the comment answers, the post (`t3_…`) or another comment (`t1_…`). Empty if unknown.
+    pub(crate) parent: String,
     pub(crate) factorial_list: Vec<Factorial>,
+    pub(crate) binomial_list: Vec<Binomial>,
+    pub(crate) approximate_digits_list: Vec<ApproximateDigits>,
+    pub(crate) approximate_digits_tower_list: Vec<ApproximateDigitsTower>,
+    pub(crate) trailing_zeros_list: Vec<TrailingZeros>,
+    pub(crate) prime_factorization_list: Vec<PrimeFactorization>,
+    pub(crate) modular_list: Vec<Modular>,
+    pub(crate) gamma_list: Vec<GammaFactorial>,
+    pub(crate) tetration_list: Vec<Tetration>,
+    pub(crate) ratio_list: Vec<FactorialRatio>,
+    pub(crate) scientific_notation_list: Vec<ScientificNotationFactorial>,
+    pub(crate) inverse_list: Vec<InverseQuery>,
     pub(crate) status: Vec<Status>,
+    pub(crate) commands: Commands,
+    /// Commands written right behind an expression, by `(number, level)` of its result.
+    pub(crate) inline_commands: HashMap<(u64, i64), Commands>,
+    /// The cap on calculations the comment was parsed with, see `Status::TooManyCalculations`.
+    pub(crate) max_jobs_per_comment: usize,
+    /// The body after code, quotes, LaTeX and unicode were rewritten, as the expressions were found in it.
+    pub(crate) parsed_body: String,
+    /// Byte ranges of every expression that was calculated in `parsed_body`, in order of appearance.
+    pub(crate) spans: Vec<Range<usize>>,
+    /// How many decimals numbers in scientific notation get, see `!precision`.
+    pub(crate) decimals: usize,
+    /// Where the whole results were uploaded to, if they were too long for reddit.
+    pub(crate) paste_link: Option<String>,
+    /// Earlier comments of the same author whose calculations are answered in the reply to this one.
+    pub(crate) merged_ids: Vec<String>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -33,39 +298,182 @@ pub(crate) enum Status {
     NoFactorial,
     ReplyWouldBeTooLong,
     FactorialsFound,
-    #[allow(dead_code)]
+    ThreadLocked,
     DecimalFactorial,
+    /// The comment asked for more calculations than `max_jobs_per_comment`, the rest was skipped.
+    TooManyCalculations,
+    /// The calculations took longer than `CALCULATION_TIME_LIMIT`, the rest was skipped.
+    TooExpensive,
 }
 
-pub trait Unzip3<A, B, C> {
-    fn unzip3(self) -> (Vec<A>, Vec<B>, Vec<C>);
+/// The calculations a comment asked for: where they were found, the commands attached to them
+/// and how many more it gets, so one with thousands of factorials can't hog the bot.
+struct Jobs {
+    remaining: usize,
+    deadline: Instant,
+    spans: Vec<Range<usize>>,
+    inline_commands: HashMap<(u64, i64), Commands>,
 }
 
-impl<A, B, C> Unzip3<A, B, C> for std::vec::IntoIter<(A, B, C)> {
-    fn unzip3(self) -> (Vec<A>, Vec<B>, Vec<C>) {
-        let mut vec_a = Vec::new();
-        let mut vec_b = Vec::new();
-        let mut vec_c = Vec::new();
+impl Jobs {
+    /// Uses up one calculation for the expression at `span`.
+    /// Once none are left, the comment gets marked with `TooManyCalculations`,
+    /// and once the deadline passed, with `TooExpensive`.
+    fn take(&mut self, span: Range<usize>, status: &mut Vec<Status>) -> bool {
+        if Instant::now() >= self.deadline {
+            if !status.contains(&Status::TooExpensive) {
+                status.push(Status::TooExpensive);
+            }
+            return false;
+        }
+        if self.remaining == 0 {
+            if !status.contains(&Status::TooManyCalculations) {
+                status.push(Status::TooManyCalculations);
+            }
+            return false;
+        }
+        self.remaining -= 1;
+        self.spans.push(span);
+        true
+    }
 
-        for (a, b, c) in self {
-            vec_a.push(a);
-            vec_b.push(b);
-            vec_c.push(c);
+    /// Remembers the commands written right behind an expression, for its result `number` and `level`.
+    fn attach(&mut self, number: u64, level: i64, commands: Commands) {
+        if commands != Commands::NONE {
+            *self.inline_commands.entry((number, level)).or_default() |= commands;
         }
+    }
+}
 
-        (vec_a, vec_b, vec_c)
+/// How strict the parser is about accepting something like "22!" as a factorial.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Strictness {
+    /// Accept everything the factorial regex matches.
+    Lenient,
+    /// Also ignore numbers glued to words ("Catch-22!") and exclamations followed by text ("5!!1", "5!!!one").
+    #[default]
+    Normal,
+    /// Also ignore factorials at the end of a line, unless the comment talks about factorials.
+    Strict,
+}
+
+impl std::str::FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "lenient" => Ok(Strictness::Lenient),
+            "normal" => Ok(Strictness::Normal),
+            "strict" => Ok(Strictness::Strict),
+            _ => Err(format!("Unknown strictness level: {s}")),
+        }
     }
 }
 
 impl RedditComment {
+    #[cfg(test)]
     pub(crate) fn new(body: &str, id: &str) -> Self {
-        let factorial_regex =
-            Regex::new(r"(?<![,.!?\d])\b(\d+)(!+)(?![<\d]|&lt;)").expect("Invalid factorial regex");
+        RedditComment::try_new_with_settings(
+            body,
+            id,
+            Strictness::default(),
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .expect("Failed to parse comment")
+    }
+
+    /// Parses a comment, using the commands of its subreddit combined with the ones in the comment itself.
+    /// With the `units` command, factorials next to a currency or unit from `unit_skip_list` are ignored.
+    /// At most `max_jobs_per_comment` calculations are done, the rest is skipped.
+    /// `!precision n` asks for n decimals in scientific notation, up to `max_decimals`.
+    /// Fails instead of panicking when the regex engine gives up on a pathological comment.
+    pub(crate) fn try_new_with_settings(
+        body: &str,
+        id: &str,
+        strictness: Strictness,
+        subreddit_commands: Commands,
+        unit_skip_list: &[String],
+        max_jobs_per_comment: usize,
+        max_decimals: usize,
+    ) -> Result<Self, ParseError> {
+        let body = &RedditComment::normalize_unicode(&RedditComment::normalize_latex(
+            &RedditComment::remove_code(body)?,
+        )?)?;
+        let commands = subreddit_commands
+            | Commands::from_comment_text(&RedditComment::remove_inline_commands(body)?);
+        let body = &if commands.contains(Commands::NO_QUOTES) {
+            RedditComment::remove_quotes(body)
+        } else {
+            body.to_string()
+        };
+        let factorial_regex = Regex::new(r"(?<![,.!?0-9])\b([0-9]+)(!+)(?![<0-9]|&lt;)")
+            .expect("Invalid factorial regex");
         let mut factorial_list: Vec<Factorial> = Vec::new();
+        let mut factorial_tasks: Vec<(u64, i64)> = Vec::new();
+        let mut trailing_zeros_list: Vec<TrailingZeros> = Vec::new();
+        let mut prime_factorization_list: Vec<PrimeFactorization> = Vec::new();
+        let mut scientific_notation_list: Vec<ScientificNotationFactorial> = Vec::new();
         let mut status: Vec<Status> = vec![];
+        let mut jobs = Jobs {
+            remaining: max_jobs_per_comment,
+            deadline: Instant::now() + CALCULATION_TIME_LIMIT,
+            spans: vec![],
+            inline_commands: HashMap::new(),
+        };
+        // Chains come first, so the factorials inside them aren't counted again below
+        let chain_steps = RedditComment::extract_factorial_chains(
+            body,
+            strictness,
+            &mut scientific_notation_list,
+            &mut jobs,
+            &mut status,
+        )?;
 
         for regex_capture in factorial_regex.captures_iter(body) {
-            let regex_capture = regex_capture.expect("Failed to capture regex");
+            let regex_capture = regex_capture?;
+
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if jobs
+                .spans
+                .iter()
+                .any(|span| span.contains(&whole_match.start()))
+            {
+                continue;
+            }
+            if RedditComment::is_false_positive(
+                body,
+                whole_match.start(),
+                whole_match.end(),
+                strictness,
+            ) {
+                continue;
+            }
+            // Those are calculated as a whole, see extract_modular_factorials and extract_factorial_ratios
+            if RedditComment::is_modular(body, whole_match.end())?
+                || (regex_capture[2].len() == 1
+                    && RedditComment::is_ratio_part(body, whole_match.start(), whole_match.end())?)
+            {
+                continue;
+            }
+            if commands.contains(Commands::SKIP_UNITS)
+                && RedditComment::has_unit_context(
+                    body,
+                    whole_match.start(),
+                    whole_match.end(),
+                    unit_skip_list,
+                )?
+            {
+                continue;
+            }
+            if !jobs.take(whole_match.range(), &mut status) {
+                break;
+            }
+            let inline_commands = RedditComment::inline_commands(body, whole_match.end())?;
+            let commands = commands | inline_commands;
 
             let num = regex_capture[1]
                 .parse::<BigInt>()
@@ -73,503 +481,4449 @@ impl RedditComment {
 
             let exclamation_count = regex_capture[2]
                 .len()
-                .to_u64()
-                .expect("Failed to convert exclamation count to u64");
+                .to_i64()
+                .expect("Failed to convert exclamation count to i64");
+
+            let count_trailing_zeros =
+                commands.contains(Commands::TRAILING_ZEROS) && exclamation_count == 1;
+            if count_trailing_zeros {
+                trailing_zeros_list.push(TrailingZeros {
+                    zeros: math::factorial_trailing_zeros(&num),
+                    number: num.clone(),
+                });
+            }
 
             // Check if the number is within a reasonable range to compute
             if num > BigInt::from(UPPER_CALCULATION_LIMIT) {
-                status.push(Status::NumberTooBig);
+                if commands.contains(Commands::DIGITS_ONLY) && exclamation_count == 1 {
+                    // Only the logarithm is needed for the digits, so the factorial never gets calculated
+                    scientific_notation_list.push(ScientificNotationFactorial {
+                        digits: math::factorial_digits_tower(math::log10(&num)),
+                        number: num.to_string(),
+                    });
+                } else if !count_trailing_zeros {
+                    // The trailing zeros are still worth a reply
+                    status.push(Status::NumberTooBig);
+                }
             } else if num == BigInt::one() {
                 continue;
             } else {
-                let num = num.to_u64().expect("Failed to convert BigInt to i64");
-                let factorial = math::factorial(num, exclamation_count);
-                factorial_list.push(Factorial {
-                    number: num,
-                    level: exclamation_count,
-                    factorial,
-                });
+                let num = num.to_u64().expect("Failed to convert BigInt to u64");
+                if commands.contains(Commands::PRIME_FACTORS)
+                    && exclamation_count == 1
+                    && num <= UPPER_PRIME_FACTORS_LIMIT
+                {
+                    prime_factorization_list.push(PrimeFactorization {
+                        number: num,
+                        factors: math::factorial_prime_factors(num),
+                    });
+                }
+                factorial_tasks.push((num, exclamation_count));
+                jobs.attach(num, exclamation_count, inline_commands);
             }
         }
+        let factorials = RedditComment::calculate_factorials(&factorial_tasks, cache::factorial);
+        factorial_list.extend(
+            factorial_tasks
+                .into_iter()
+                .map(|(number, level)| Factorial {
+                    number,
+                    level,
+                    factorial: factorials[&(number, level as u64)].clone(),
+                }),
+        );
 
-        factorial_list.sort();
-        factorial_list.dedup();
-
-        if factorial_list.is_empty() {
-            status.push(Status::NoFactorial);
-        } else {
-            status.push(Status::FactorialsFound);
+        factorial_list.extend(chain_steps);
+        factorial_list.extend(RedditComment::extract_word_factorials(
+            body,
+            strictness,
+            &mut jobs,
+            &mut status,
+        )?);
+        factorial_list.extend(RedditComment::extract_primorials(
+            body,
+            &mut jobs,
+            &mut status,
+        )?);
+        let mut approximate_digits_list = RedditComment::extract_hyper_and_superfactorials(
+            body,
+            &mut factorial_list,
+            &mut jobs,
+            &mut status,
+        )?;
+        approximate_digits_list.extend(RedditComment::extract_alternating_factorials(
+            body,
+            &mut factorial_list,
+            &mut jobs,
+            &mut status,
+        )?);
+        if commands.contains(Commands::LEFT_FACTORIAL) {
+            approximate_digits_list.extend(RedditComment::extract_left_factorials(
+                body,
+                &mut factorial_list,
+                &mut jobs,
+                &mut status,
+            )?);
         }
 
-        // rewrite for Factorial struct
-        if RedditComment::factorials_are_too_long(&factorial_list) {
-            status.push(Status::ReplyWouldBeTooLong);
-        }
+        let approximate_digits_tower_list = RedditComment::extract_exponential_factorials(
+            body,
+            &mut factorial_list,
+            &mut jobs,
+            &mut status,
+        )?;
+        scientific_notation_list.extend(RedditComment::extract_scientific_notation_factorials(
+            body,
+            &mut factorial_list,
+            &mut jobs,
+            &mut status,
+        )?);
 
-        RedditComment {
+        let binomial_list = RedditComment::extract_binomials(body, &mut jobs, &mut status)?;
+        let modular_list = RedditComment::extract_modular_factorials(body, &mut jobs, &mut status)?;
+        let gamma_list = RedditComment::extract_gamma_factorials(body, &mut jobs, &mut status)?;
+        let tetration_list = RedditComment::extract_tetrations(body, &mut jobs, &mut status)?;
+        let ratio_list = RedditComment::extract_factorial_ratios(body, &mut jobs, &mut status)?;
+        let inverse_list = RedditComment::extract_inverse_queries(body, &mut jobs, &mut status)?;
+        jobs.spans.sort_by_key(|span| (span.start, span.end));
+        jobs.spans.dedup();
+
+        let mut comment = RedditComment {
             id: id.to_string(),
+            subreddit: String::new(),
+            author: String::new(),
+            thread: String::new(),
+            parent: String::new(),
             factorial_list,
+            binomial_list,
+            approximate_digits_list,
+            approximate_digits_tower_list,
+            trailing_zeros_list,
+            prime_factorization_list,
+            modular_list,
+            gamma_list,
+            tetration_list,
+            ratio_list,
+            scientific_notation_list,
+            inverse_list,
             status,
+            commands,
+            inline_commands: jobs.inline_commands,
+            max_jobs_per_comment,
+            parsed_body: body.to_string(),
+            spans: jobs.spans,
+            decimals: RedditComment::requested_precision(body)?
+                .unwrap_or(NUMBER_DECIMALS_SCIENTIFIC)
+                .min(max_decimals),
+            paste_link: None,
+            merged_ids: vec![],
+        };
+        comment.sort_results();
+
+        if comment.has_results() {
+            comment.status.push(Status::FactorialsFound);
+        } else {
+            comment.status.push(Status::NoFactorial);
         }
+        if comment.is_too_long_in_full() {
+            comment.status.push(Status::ReplyWouldBeTooLong);
+        }
+        Ok(comment)
     }
 
-    fn get_factorial_level_string(level: u64) -> &'static str {
-        match level {
-            1 => "",
-            2 => "Double-",
-            3 => "Triple-",
-            4 => "Quadruple-",
-            5 => "Quintuple-",
-            6 => "Sextuple-",
-            7 => "Septuple-",
-            8 => "Octuple-",
-            9 => "Nonuple-",
-            10 => "Decuple-",
-            11 => "Undecuple-",
-            12 => "Duodecuple-",
-            13 => "Tredecuple-",
-            14 => "Quattuordecuple-",
-            15 => "Quindecuple-",
-            16 => "Sexdecuple-",
-            17 => "Septendecuple-",
-            18 => "Octodecuple-",
-            19 => "Novemdecuple-",
-            20 => "Vigintuple-",
-            21 => "Unvigintuple-",
-            22 => "Duovigintuple-",
-            23 => "Trevigintuple-",
-            24 => "Quattuorvigintuple-",
-            25 => "Quinvigintuple-",
-            26 => "Sexvigintuple-",
-            27 => "Septenvigintuple-",
-            28 => "Octovigintuple-",
-            29 => "Novemvigintuple-",
-            30 => "Trigintuple-",
-            31 => "Untrigintuple-",
-            32 => "Duotrigintuple-",
-            33 => "Tretrigintuple-",
-            34 => "Quattuortrigintuple-",
-            35 => "Quintrigintuple-",
-            36 => "Sextrigintuple-",
-            37 => "Septentrigintuple-",
-            38 => "Octotrigintuple-",
-            39 => "Novemtrigintuple-",
-            40 => "Quadragintuple-",
-            41 => "Unquadragintuple-",
-            42 => "Duoquadragintuple-",
-            43 => "Trequadragintuple-",
-            44 => "Quattuorquadragintuple-",
-            45 => "Quinquadragintuple-",
-            _ => "n-",
+    /// Answers the calculations of an earlier comment along with the ones of this comment,
+    /// so someone writing several comments in a row gets one reply, see [`Self::merged_ids`].
+    pub(crate) fn merge(&mut self, earlier: RedditComment) {
+        self.factorial_list.extend(earlier.factorial_list);
+        self.binomial_list.splice(0..0, earlier.binomial_list);
+        self.approximate_digits_list
+            .extend(earlier.approximate_digits_list);
+        self.approximate_digits_tower_list
+            .extend(earlier.approximate_digits_tower_list);
+        self.trailing_zeros_list.extend(earlier.trailing_zeros_list);
+        self.prime_factorization_list
+            .extend(earlier.prime_factorization_list);
+        self.modular_list.extend(earlier.modular_list);
+        self.gamma_list.extend(earlier.gamma_list);
+        self.tetration_list.extend(earlier.tetration_list);
+        self.ratio_list.extend(earlier.ratio_list);
+        self.scientific_notation_list
+            .extend(earlier.scientific_notation_list);
+        self.inverse_list.extend(earlier.inverse_list);
+        self.sort_results();
+
+        for status in earlier.status {
+            if !self.status.contains(&status) {
+                self.status.push(status);
+            }
         }
+        if !self.status.contains(&Status::ReplyWouldBeTooLong) && self.is_too_long_in_full() {
+            self.status.push(Status::ReplyWouldBeTooLong);
+        }
+        self.commands |= earlier.commands;
+        for (result, commands) in earlier.inline_commands {
+            *self.inline_commands.entry(result).or_default() |= commands;
+        }
+        self.decimals = self.decimals.max(earlier.decimals);
+
+        // The earlier body goes first, so the sources stay in the order they were written
+        let offset = earlier.parsed_body.len() + 2;
+        self.parsed_body = format!("{}\n\n{}", earlier.parsed_body, self.parsed_body);
+        self.spans = earlier
+            .spans
+            .into_iter()
+            .chain(
+                self.spans
+                    .drain(..)
+                    .map(|span| span.start + offset..span.end + offset),
+            )
+            .collect();
+        self.merged_ids.push(earlier.id);
+        self.merged_ids.extend(earlier.merged_ids);
     }
 
-    fn factorials_are_too_long(factorial_list: &[Factorial]) -> bool {
-        factorial_list
+    /// Sorts the results of each kind and drops the ones asked for twice.
+    /// Binomials stay in the order they were written.
+    fn sort_results(&mut self) {
+        self.factorial_list.sort();
+        self.factorial_list.dedup();
+        self.approximate_digits_list.sort();
+        self.approximate_digits_list.dedup();
+        self.approximate_digits_tower_list
+            .sort_by_key(|tower| (tower.number, tower.level));
+        self.approximate_digits_tower_list.dedup();
+        self.scientific_notation_list
+            .sort_by(|a, b| a.number.cmp(&b.number));
+        self.scientific_notation_list.dedup();
+        self.trailing_zeros_list.sort();
+        self.trailing_zeros_list.dedup();
+        self.prime_factorization_list.sort();
+        self.prime_factorization_list.dedup();
+        self.modular_list.sort();
+        self.modular_list.dedup();
+        self.gamma_list
+            .sort_by(|a, b| a.number.total_cmp(&b.number));
+        self.gamma_list.dedup();
+        self.tetration_list
+            .sort_by_key(|tetration| (tetration.base, tetration.height));
+        self.tetration_list.dedup();
+        self.ratio_list.sort();
+        self.ratio_list.dedup();
+        self.inverse_list.sort();
+        self.inverse_list.dedup();
+    }
+
+    fn has_results(&self) -> bool {
+        !(self.factorial_list.is_empty()
+            && self.binomial_list.is_empty()
+            && self.modular_list.is_empty()
+            && self.gamma_list.is_empty()
+            && self.tetration_list.is_empty()
+            && self.ratio_list.is_empty()
+            && self.approximate_digits_list.is_empty()
+            && self.approximate_digits_tower_list.is_empty()
+            && self.scientific_notation_list.is_empty()
+            && self.trailing_zeros_list.is_empty()
+            && self.inverse_list.is_empty())
+    }
+
+    /// Whether a factorial is too long to be written in full.
+    /// Factorials written shortened don't need the room.
+    fn is_too_long_in_full(&self) -> bool {
+        let written_in_full = self.factorial_list.iter().filter(|factorial| {
+            let commands = self.commands
+                | self
+                    .inline_commands
+                    .get(&(factorial.number, factorial.level))
+                    .copied()
+                    .unwrap_or_default();
+            !commands.contains(Commands::DIGITS_ONLY) && !commands.contains(Commands::SHORT)
+        });
+        RedditComment::factorials_are_too_long(written_in_full)
+    }
+
+    /// Whether the comment answers the post itself, not another comment.
+    pub(crate) fn is_top_level(&self) -> bool {
+        self.parent.starts_with("t3_")
+    }
+
+    /// The expressions as they were written, e.g. `23!`, to quote them above the results.
+    pub(crate) fn sources(&self) -> Vec<&str> {
+        self.spans
             .iter()
-            .any(|Factorial { number, level, .. }| match level {
-                1 => *number > 3249,
-                2 => *number > 5982,
-                3 => *number > 8572,
-                4 => *number > 11077,
-                5 => *number > 13522,
-                6 => *number > 15920,
-                7 => *number > 18282,
-                8 => *number > 20613,
-                9 => *number > 22920,
-                10 => *number > 25208,
-                11 => *number > 27479,
-                12 => *number > 29735,
-                13 => *number > 31977,
-                14 => *number > 34207,
-                15 => *number > 36426,
-                16 => *number > 38635,
-                17 => *number > 40835,
-                18 => *number > 43027,
-                19 => *number > 45212,
-                20 => *number > 47390,
-                21 => *number > 49562,
-                22 => *number > 51728,
-                23 => *number > 53889,
-                24 => *number > 56045,
-                25 => *number > 58197,
-                26 => *number > 60345,
-                27 => *number > 62489,
-                28 => *number > 64630,
-                29 => *number > 66768,
-                30 => *number > 68903,
-                31 => *number > 71036,
-                32 => *number > 73167,
-                33 => *number > 75296,
-                34 => *number > 77423,
-                35 => *number > 79548,
-                36 => *number > 81672,
-                37 => *number > 83794,
-                38 => *number > 85915,
-                39 => *number > 88035,
-                40 => *number > 90154,
-                41 => *number > 92272,
-                42 => *number > 94389,
-                43 => *number > 96505,
-                44 => *number > 98620,
-                45 => *number > 100734,
-                _ => false,
+            .map(|span| &self.parsed_body[span.clone()])
+            .collect()
+    }
+
+    /// Rewrites the LaTeX math subreddits like to use in plain notation:
+    /// `\binom{n}{k}` as `C(n,k)`, `\frac{a}{b}` as `(a/b)` (or a decimal if it doesn't divide),
+    /// `\cdot` and `\times` as `*` and `\left(`, `\right)` as plain parentheses.
+    /// The `$` around math is removed, so it isn't mistaken for a currency.
+    fn normalize_latex(body: &str) -> Result<String, ParseError> {
+        // Reddit escapes backslashes in some clients, so allow them doubled
+        let math_regex =
+            Regex::new(r"\$\$?([^$\n]*?[\\!][^$\n]*?)\$\$?").expect("Invalid math regex");
+        let binom_regex =
+            Regex::new(r"\\{1,2}[dt]?binom\s*\{\s*([0-9]+)\s*\}\s*\{\s*([0-9]+)\s*\}")
+                .expect("Invalid binom regex");
+        let frac_regex = Regex::new(r"\\{1,2}[dt]?frac\s*\{\s*([0-9]+)\s*\}\s*\{\s*([0-9]+)\s*\}")
+            .expect("Invalid frac regex");
+        let operator_regex =
+            Regex::new(r"\\{1,2}(?:cdot|times)\b").expect("Invalid LaTeX operator regex");
+        let parenthesis_regex =
+            Regex::new(r"\\{1,2}(?:left|right)\s*([()])").expect("Invalid LaTeX parenthesis regex");
+        let braces_regex =
+            Regex::new(r"(?<![}\w])\{\s*([0-9]+)\s*\}(?=!)").expect("Invalid braces regex");
+
+        let body = math_regex.try_replacen(body, 0, " $1 ")?;
+        let body = binom_regex.try_replacen(&body, 0, "C($1,$2)")?;
+        let body = frac_regex.try_replacen(&body, 0, |captures: &fancy_regex::Captures| {
+            let (Ok(numerator), Ok(denominator)) =
+                (captures[1].parse::<u64>(), captures[2].parse::<u64>())
+            else {
+                return captures[0].to_string();
+            };
+            match denominator {
+                0 => captures[0].to_string(),
+                _ if numerator % denominator == 0 => format!("({numerator}/{denominator})"),
+                _ => format!("({:?})", numerator as f64 / denominator as f64),
+            }
+        })?;
+        let body = operator_regex.try_replacen(&body, 0, "*")?;
+        let body = parenthesis_regex.try_replacen(&body, 0, "$1")?;
+        Ok(braces_regex.try_replacen(&body, 0, "$1")?.into_owned())
+    }
+
+    /// Rewrites unicode superscripts and vulgar fractions in front of a factorial in plain notation,
+    /// so `10⁶!` is read as `(10^6)!` and `2½!` as `(2.5)!`.
+    fn normalize_unicode(body: &str) -> Result<String, ParseError> {
+        const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        const FRACTIONS: [(char, f64); 15] = [
+            ('½', 1.0 / 2.0),
+            ('⅓', 1.0 / 3.0),
+            ('⅔', 2.0 / 3.0),
+            ('¼', 1.0 / 4.0),
+            ('¾', 3.0 / 4.0),
+            ('⅕', 1.0 / 5.0),
+            ('⅖', 2.0 / 5.0),
+            ('⅗', 3.0 / 5.0),
+            ('⅘', 4.0 / 5.0),
+            ('⅙', 1.0 / 6.0),
+            ('⅚', 5.0 / 6.0),
+            ('⅛', 1.0 / 8.0),
+            ('⅜', 3.0 / 8.0),
+            ('⅝', 5.0 / 8.0),
+            ('⅞', 7.0 / 8.0),
+        ];
+        let superscript_regex = Regex::new(r"(?<![\w.,])([0-9]+)([⁰¹²³⁴⁵⁶⁷⁸⁹]+)(?=!)")
+            .expect("Invalid superscript regex");
+        let fraction_regex = Regex::new(r"(?<![\w.,])([0-9]*)([½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞])(?=!)")
+            .expect("Invalid fraction regex");
+
+        let body =
+            superscript_regex.try_replacen(body, 0, |captures: &fancy_regex::Captures| {
+                let exponent: String = captures[2]
+                    .chars()
+                    .filter_map(|superscript| SUPERSCRIPTS.iter().position(|&c| c == superscript))
+                    .map(|digit| char::from(b'0' + digit as u8))
+                    .collect();
+                format!("({}^{exponent})", &captures[1])
+            })?;
+        Ok(fraction_regex
+            .try_replacen(&body, 0, |captures: &fancy_regex::Captures| {
+                let whole = captures[1].parse::<f64>().unwrap_or(0.0);
+                let fraction = captures[2]
+                    .chars()
+                    .next()
+                    .expect("Fraction is one character");
+                let (_, value) = FRACTIONS
+                    .iter()
+                    .find(|(c, _)| *c == fraction)
+                    .expect("Every matched fraction is known");
+                format!("({:?})", whole + value)
+            })?
+            .into_owned())
+    }
+
+    /// Removes fenced code blocks and `inline code`, as code is full of `!` that aren't factorials.
+    /// An unclosed fence hides everything after it, like it does on reddit.
+    fn remove_code(body: &str) -> Result<String, ParseError> {
+        let code_regex = Regex::new(r"(?s)```.*?(?:```|\z)|`[^`\n]*`").expect("Invalid code regex");
+        Ok(code_regex.try_replacen(body, 0, " ")?.into_owned())
+    }
+
+    /// Removes quoted lines, starting with `>`. Spoilers like `>!5!<` also start with it, but stay.
+    fn remove_quotes(body: &str) -> String {
+        body.lines()
+            .filter(|line| {
+                let line = line.trim_start();
+                let quote = line.strip_prefix('>').or_else(|| line.strip_prefix("&gt;"));
+                quote.is_none_or(|quote| quote.starts_with('!'))
             })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
-    pub(crate) fn add_status(&mut self, status: Status) {
-        self.status.push(status);
+    /// Reads how many decimals `!precision n` asks for.
+    fn requested_precision(body: &str) -> Result<Option<usize>, ParseError> {
+        let precision_regex =
+            Regex::new(r"(?i)!precision\s+([0-9]+)\b").expect("Invalid precision regex");
+        Ok(precision_regex
+            .captures(body)?
+            .and_then(|captures| captures[1].parse().ok()))
     }
 
-    pub(crate) fn get_reply(&self) -> String {
-        let mut reply;
+    /// Reads the commands written in brackets right behind an expression ending at `end`, like `5! [digits]`.
+    /// Unknown names are ignored, so `5! [citation]` is just text.
+    fn inline_commands(body: &str, end: usize) -> Result<Commands, ParseError> {
+        let inline_regex =
+            Regex::new(INLINE_COMMANDS_PATTERN).expect("Invalid inline commands regex");
+        Ok(match inline_regex.captures(&body[end..])? {
+            Some(captures) => Commands::from_names(&captures[1]).unwrap_or_default(),
+            None => Commands::NONE,
+        })
+    }
 
-        // Normal case
-        if !(self.status.contains(&Status::ReplyWouldBeTooLong)) {
-            reply = self
-                .factorial_list
-                .iter()
-                .fold(String::new(), |mut acc, factorial| {
-                    let factorial_level_string =
-                        RedditComment::get_factorial_level_string(factorial.level);
-                    let _ = write!(
-                        acc,
-                        "{}{}{} is {} \n\n",
-                        factorial_level_string, PLACEHOLDER, factorial.number, factorial.factorial
-                    );
-                    acc
-                });
+    /// Removes the commands written behind an expression, so they don't count for the whole comment.
+    fn remove_inline_commands(body: &str) -> Result<String, ParseError> {
+        let inline_regex = Regex::new(&format!(
+            r"(?i)(!|#|\)|\bfactorial){}",
+            &INLINE_COMMANDS_PATTERN[1..]
+        ))
+        .expect("Invalid inline commands regex");
+        Ok(inline_regex.try_replacen(body, 0, "$1")?.into_owned())
+    }
 
-            reply.push_str(FOOTER_TEXT);
-            return reply;
+    /// Checks the text around a matched factorial for signs that it isn't meant as one.
+    pub(crate) fn is_false_positive(
+        body: &str,
+        start: usize,
+        end: usize,
+        strictness: Strictness,
+    ) -> bool {
+        if strictness == Strictness::Lenient {
+            return false;
         }
 
-        // Too long reply
-        let numbers: Vec<u64> = self.factorial_list.iter().map(|f| f.number).collect();
+        // Numbers that are part of a word, like "Catch-22!" or "COVID-19!"
+        let mut before = body[..start].chars().rev();
+        if let (Some('-' | '_'), Some(c)) = (before.next(), before.next()) {
+            if c.is_alphabetic() {
+                return true;
+            }
+        }
 
-        let (factorial_lengths, factorial_decimals, factorial_level_names): (
-            Vec<u64>,
-            Vec<String>,
-            Vec<&str>,
-        ) = self
-            .factorial_list
-            .iter()
-            .map(|f| {
-                let mut truncated_number = f.factorial.to_string();
-                let length = truncated_number.len();
-                truncated_number.truncate(NUMBER_DECIMALS_SCIENTIFIC + 2); // There is one digit before the decimals and the digit for rounding
+        // Exclamations that continue with text, like "5!!1" or "5!!!one"
+        let after = body[end..].trim_start_matches('!');
+        if after.starts_with(char::is_alphanumeric) {
+            return true;
+        }
+
+        if strictness == Strictness::Strict {
+            let line_ends = after.is_empty() || after.starts_with(['\n', '\r']);
+            if line_ends && !body.to_lowercase().contains("factorial") {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Checks if a matched factorial is rather an amount of something, like "$5!", "5!kg" or "5!-7! business days".
+    /// Currency symbols count in front of the number, units behind it, also behind a range like "5!-7!".
+    pub(crate) fn has_unit_context(
+        body: &str,
+        start: usize,
+        end: usize,
+        unit_skip_list: &[String],
+    ) -> Result<bool, ParseError> {
+        let before = body[..start].trim_end().to_lowercase();
+        let range_regex = Regex::new(r"^\s*[-–]\s*[0-9]+!*").expect("Invalid range regex");
+        let after = &body[end..];
+        let after = match range_regex.find(after)? {
+            Some(range) => &after[range.end()..],
+            None => after,
+        };
+        let after = after.trim_start_matches('!');
+        // Only a single space may be between number and unit
+        let after = after.strip_prefix(' ').unwrap_or(after).to_lowercase();
+
+        Ok(unit_skip_list.iter().any(|unit| {
+            let unit = unit.to_lowercase();
+            if !unit.starts_with(char::is_alphanumeric) && before.ends_with(&unit) {
+                return true;
+            }
+            after
+                .strip_prefix(&unit)
+                .is_some_and(|rest| !rest.starts_with(char::is_alphanumeric))
+        }))
+    }
+
+    /// Calculates the factorials asked for in one comment, keyed by `(n, level)`.
+    /// They are calculated from the smallest up, continuing each from the one before of the same level,
+    /// so `1000!, 999!, 998!` multiplies up to 1000! once, even without the factorial cache.
+    /// `calculate` is only used for the first factorial of each level and residue.
+    fn calculate_factorials(
+        tasks: &[(u64, i64)],
+        mut calculate: impl FnMut(u64, u64) -> BigInt,
+    ) -> HashMap<(u64, u64), BigInt> {
+        let mut tasks: Vec<(u64, u64)> = tasks.iter().map(|&(n, k)| (n, k as u64)).collect();
+        tasks.sort_by_key(|&(n, k)| (k, n));
+        tasks.dedup();
+        let mut factorials: HashMap<(u64, u64), BigInt> = HashMap::new();
+        // The last number per level and residue, as only `m` with `n - m` a multiple of `k` can be continued
+        let mut previous: HashMap<(u64, u64), u64> = HashMap::new();
+        for (n, k) in tasks {
+            let factorial = match previous.get(&(k, n % k)) {
+                Some(&m) => &factorials[&(m, k)] * math::factorial_from(m, n, k),
+                None => calculate(n, k),
+            };
+            factorials.insert((n, k), factorial);
+            previous.insert((k, n % k), n);
+        }
+        factorials
+    }
+
+    /// Finds chained factorials like `((3!)!)!` and returns every step of the chain.
+    /// Each step is calculated from the result of the previous one.
+    /// The innermost number may also be simple arithmetic, like `(5+3)!` or `((2*10)!)!`.
+    /// The digits of factorials of powers too big to be calculated, like `(2^100)!`, get approximated instead.
+    /// Once a step gets too big, like in `((((20!)!)!)!)`, the digits of it and the steps after it are approximated.
+    fn extract_factorial_chains(
+        body: &str,
+        strictness: Strictness,
+        scientific_notation_list: &mut Vec<ScientificNotationFactorial>,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<Factorial>, ParseError> {
+        let chain_regex = Regex::new(
+            r"(?<![,.!?0-9])(\(+)([0-9]+(?:\s*[-+*/^]\s*[0-9]+)*)(!*)((?:\)!+)+)(?![<0-9]|&lt;)",
+        )
+        .expect("Invalid factorial chain regex");
+        let step_regex = Regex::new(r"\)(!+)").expect("Invalid factorial chain step regex");
+        let power_regex = Regex::new(r"^([0-9]+)\s*\^\s*([0-9]+)$").expect("Invalid power regex");
+        let mut step_list: Vec<Factorial> = Vec::new();
+
+        for regex_capture in chain_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if RedditComment::is_false_positive(
+                body,
+                whole_match.start(),
+                whole_match.end(),
+                strictness,
+            ) {
+                continue;
+            }
+
+            let inner_levels = regex_capture[3].len();
+            let is_expression = regex_capture[2].contains(['+', '-', '*', '/', '^']);
+            // A lone number in parentheses like "(2)!" is rather an enumeration
+            if inner_levels == 0 && !is_expression {
+                continue;
+            }
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            // Every opened parenthesis is closed by one step, surplus ones are just decoration
+            let opened = regex_capture[1].len();
+            let mut levels: Vec<usize> = Some(inner_levels)
+                .filter(|&level| level > 0)
+                .into_iter()
+                .collect();
+            for step in step_regex.captures_iter(&regex_capture[4]).take(opened) {
+                levels.push(step?[1].len());
+            }
 
-                // Round if we had to truncate
-                if truncated_number.len() >= NUMBER_DECIMALS_SCIENTIFIC + 2 {
-                    math::round(&mut truncated_number);
+            let num = RedditComment::evaluate_expression(&regex_capture[2]);
+            let is_calculable = num
+                .as_ref()
+                .is_some_and(|num| *num <= BigInt::from(UPPER_CALCULATION_LIMIT));
+            if let Some(power) = power_regex.captures(&regex_capture[2])? {
+                if !is_calculable && levels == [1] {
+                    // Only the logarithm is needed, so the power itself never gets calculated
+                    let (base, exponent) = (&power[1], &power[2]);
+                    let log_n = exponent.parse::<f64>().expect("Failed to parse exponent")
+                        * base.parse::<f64>().expect("Failed to parse base").log10();
+                    scientific_notation_list.push(ScientificNotationFactorial {
+                        number: format!("{base}^{exponent}"),
+                        digits: math::factorial_digits_tower(log_n),
+                    });
+                    continue;
+                }
+            }
+            let Some(mut num) = num else {
+                continue;
+            };
+
+            let steps_before = step_list.len();
+            // The argument of the next step as written, like `(20!)!`, for the approximated steps
+            let mut argument = regex_capture[2].trim().to_string();
+            let mut digits: Option<Option<(u64, f64)>> = None;
+            for level in levels {
+                digits = match digits {
+                    Some(digits) => Some(digits.and_then(|(tens, top)| match tens {
+                        // The argument has about 10^top digits, so it is about 10^10^top
+                        0 => math::factorial_digits_tower(top),
+                        // Another factorial just adds another ten to the tower
+                        _ => Some((tens + 1, top)),
+                    })),
+                    None if num > BigInt::from(UPPER_CALCULATION_LIMIT) => {
+                        Some(math::factorial_digits_tower(math::log10(&num)).map(
+                            // A multifactorial is about the level-th root of the factorial
+                            |(tens, top)| match tens {
+                                0 => (tens, top / level as f64),
+                                _ => (tens, top),
+                            },
+                        ))
+                    }
+                    None => {
+                        let number = num.to_u64().expect("Failed to convert BigInt to u64");
+                        let level = level.to_i64().expect("Failed to convert level to i64");
+                        let factorial = cache::factorial(number, level as u64);
+                        if number != 1 {
+                            step_list.push(Factorial {
+                                number,
+                                level,
+                                factorial: factorial.clone(),
+                            });
+                        }
+                        num = factorial;
+                        None
+                    }
                 };
-                // Only add decimal if we have more than one digit
-                if truncated_number.len() > 1 {
-                    truncated_number.insert(1, '.'); // Decimal point
+                if let Some(digits) = digits {
+                    scientific_notation_list.push(ScientificNotationFactorial {
+                        number: argument.clone(),
+                        digits,
+                    });
                 }
+                if !argument.bytes().all(|byte| byte.is_ascii_digit()) {
+                    argument = format!("({argument})");
+                }
+                argument.push_str(&"!".repeat(level));
+            }
+            // Commands behind a chain are meant for its final result
+            if step_list.len() > steps_before {
+                let last = step_list.last().expect("Chain has a step");
+                let (number, level) = (last.number, last.level);
+                jobs.attach(
+                    number,
+                    level,
+                    RedditComment::inline_commands(body, whole_match.end())?,
+                );
+            }
+        }
 
-                let factorial_level_names = RedditComment::get_factorial_level_string(f.level);
+        Ok(step_list)
+    }
 
-                (length as u64, truncated_number, factorial_level_names)
-            })
-            .collect::<Vec<_>>() // Collect into a vector of tuples
-            .into_iter()
-            .unzip3(); // Unzip into three separate vectors
+    /// Evaluates simple arithmetic like `5+3` or `2*10^2` with `+ - * / ^`, using the usual precedence.
+    /// Returns `None` if the result is no natural number, or it gets too big to be calculated.
+    fn evaluate_expression(expression: &str) -> Option<BigInt> {
+        const OPERATORS: [char; 5] = ['+', '-', '*', '/', '^'];
+        let mut numbers: Vec<BigInt> = Vec::new();
+        let mut operators: Vec<char> = Vec::new();
+        for part in expression.split_inclusive(OPERATORS) {
+            let number = part.strip_suffix(OPERATORS).unwrap_or(part);
+            numbers.push(number.trim().parse().ok()?);
+            operators.extend(part.chars().last().filter(|c| OPERATORS.contains(c)));
+        }
 
-        if numbers.len() == 1 {
-            let factorial_level_string =
-                RedditComment::get_factorial_level_string(self.factorial_list[0].level);
-            reply = format!(
-                "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n \
-                In scientific notation the {}factorial of {} would be (roughly) {}e{} though :)\n\n",
-                factorial_level_string, numbers[0], factorial_decimals[0], factorial_lengths[0]-1 // exponent is one less than the length
-            );
-        } else {
-            let formatted_scientifics = factorial_lengths
-                .iter()
-                .zip(factorial_decimals)
+        // Powers first, from the right, as 2^3^2 is 2^(3^2)
+        while let Some(i) = operators.iter().rposition(|&operator| operator == '^') {
+            operators.remove(i);
+            let exponent = numbers.remove(i + 1);
+            // Zeros and ones stay the same for any exponent, no matter how big
+            if numbers[i] <= BigInt::one() {
+                if exponent.is_zero() {
+                    numbers[i] = BigInt::one();
+                }
+                continue;
+            }
+            let exponent = exponent.to_u32()?;
+            if numbers[i].bits() * exponent as u64 > UPPER_EXPRESSION_BITS {
+                return None;
+            }
+            numbers[i] = numbers[i].pow(exponent);
+        }
+        while let Some(i) = operators
+            .iter()
+            .position(|&operator| operator == '*' || operator == '/')
+        {
+            let right = numbers.remove(i + 1);
+            let left = &numbers[i];
+            numbers[i] = if operators.remove(i) == '*' {
+                if left.bits() + right.bits() > UPPER_EXPRESSION_BITS {
+                    return None;
+                }
+                left * right
+            } else {
+                if right.is_zero() || !(left % &right).is_zero() {
+                    return None;
+                }
+                left / right
+            };
+        }
+        let mut numbers = numbers.into_iter();
+        let first = numbers.next()?;
+        let result =
+            operators
+                .into_iter()
                 .zip(numbers)
-                .zip(factorial_level_names)
-                .map(|(((length, truncated_number), number), factorial_level)| {
-                    format!(
-                        "{factorial_level}Factorial of {number} = {truncated_number}e{}",
-                        length - 1
-                    )
-                })
-                .fold(String::new(), |a, e| {
-                    if !a.is_empty() {
-                        format!("{a},\n\n{e}")
-                    } else {
-                        e
-                    }
+                .fold(first, |result, (operator, number)| match operator {
+                    '+' => result + number,
+                    _ => result - number,
                 });
-            reply = format!(
-                "If I post the whole numbers, the comment would get too long, as reddit only allows up to 10k characters.\n\n\
-                In scientific notation the results would look roughly like that:\n\n{}\n\n:)\n\n",
-                formatted_scientifics
-            );
-        }
 
-        if reply.len() > MAX_COMMENT_LENGTH as usize {
-            reply = "Sorry, but the reply text for all those number would be _really_ long, so I'd rather not even try posting lmao\n".to_string();
+        (result >= BigInt::zero()).then_some(result)
+    }
+
+    /// Finds factorials of spelled-out numbers, like "five factorial", "twenty-three!" or "one hundred and five!!".
+    /// A single word with exclamation marks, like "ten!", is almost always excitement and not answered.
+    fn extract_word_factorials(
+        body: &str,
+        strictness: Strictness,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<Factorial>, ParseError> {
+        let number_word = format!(r"(?:{})\b", NUMBER_WORDS.map(|(word, _)| word).join("|"));
+        // Without lookarounds the regex runs in linear time, how the run of words ends is checked by hand
+        let number_words_regex = Regex::new(&format!(
+            r"(?i)\b{number_word}(?:[\s-]+(?:and[\s-]+)?{number_word})*"
+        ))
+        .expect("Invalid number words regex");
+        let mut factorial_list: Vec<Factorial> = Vec::new();
+
+        for number_words in number_words_regex.find_iter(body) {
+            let number_words = number_words?;
+            let Some((suffix_length, exclamations)) =
+                RedditComment::word_factorial_suffix(&body[number_words.end()..])
+            else {
+                continue;
+            };
+            let range = number_words.start()..number_words.end() + suffix_length;
+
+            // Saying "factorial" leaves no doubt, an exclamation mark might just be excitement
+            let single_word = !number_words
+                .as_str()
+                .contains(|c: char| c.is_whitespace() || c == '-');
+            if exclamations.is_some()
+                && (single_word
+                    || RedditComment::is_false_positive(body, range.start, range.end, strictness))
+            {
+                continue;
+            }
+            let level = exclamations.unwrap_or(1);
+            let Some(number) = RedditComment::parse_number_words(number_words.as_str()) else {
+                continue;
+            };
+            if !jobs.take(range.clone(), status) {
+                break;
+            }
+
+            if number > UPPER_CALCULATION_LIMIT as u64 {
+                status.push(Status::NumberTooBig);
+            } else if number != 1 {
+                factorial_list.push(Factorial {
+                    number,
+                    level: level as i64,
+                    factorial: cache::factorial(number, level as u64),
+                });
+                jobs.attach(
+                    number,
+                    level as i64,
+                    RedditComment::inline_commands(body, range.end)?,
+                );
+            }
         }
 
-        reply.push_str(FOOTER_TEXT);
-        reply
+        Ok(factorial_list)
     }
-}
 
-#[cfg(test)]
+    /// Checks whether spelled-out numbers are followed by " factorial" or exclamation marks.
+    /// Returns the length of that suffix, and the number of exclamation marks if there are any.
+    fn word_factorial_suffix(rest: &str) -> Option<(usize, Option<usize>)> {
+        let after_exclamations = rest.trim_start_matches('!');
+        let exclamations = rest.len() - after_exclamations.len();
+        if exclamations > 0 {
+            let followed_by_number = after_exclamations
+                .starts_with(|c: char| c == '<' || c.is_ascii_digit())
+                || after_exclamations.starts_with("&lt;");
+            return (!followed_by_number).then_some((exclamations, Some(exclamations)));
+        }
+
+        let after_space = rest.trim_start();
+        let word = after_space.get(.."factorial".len())?;
+        let ends_word = !after_space["factorial".len()..]
+            .starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        (after_space.len() < rest.len() && word.eq_ignore_ascii_case("factorial") && ends_word)
+            .then_some((rest.len() - after_space.len() + word.len(), None))
+    }
+
+    /// Reads a spelled-out number like "twenty-three" or "one hundred and five".
+    /// Returns `None` for word salad like "three twenty" or "hundred".
+    fn parse_number_words(words: &str) -> Option<u64> {
+        let (mut total, mut current, mut last_scale) = (0, 0, u64::MAX);
+        let words = words
+            .split(|c: char| c.is_whitespace() || c == '-')
+            .filter(|word| !word.is_empty() && !word.eq_ignore_ascii_case("and"));
+        for word in words {
+            let (_, value) = NUMBER_WORDS
+                .iter()
+                .find(|(number_word, _)| number_word.eq_ignore_ascii_case(word))?;
+            match *value {
+                0 if total == 0 && current == 0 => {}
+                // A scale like thousand ends a group, the groups have to get smaller
+                scale @ (1_000 | 1_000_000) if current != 0 && scale < last_scale => {
+                    total += current * scale;
+                    (current, last_scale) = (0, scale);
+                }
+                100 if (1..10).contains(&current) => current *= 100,
+                // Tens and teens fill the last two digits, units only the last one
+                value @ 10..=90 if current % 100 == 0 => current += value,
+                value @ 1..=9 if current % 10 == 0 && current % 100 != 10 => current += value,
+                _ => return None,
+            }
+        }
+        Some(total + current)
+    }
+
+    /// Finds primorials written as `n#`.
+    fn extract_primorials(
+        body: &str,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<Factorial>, ParseError> {
+        let primorial_regex = Regex::new(r"(?<![,.!?#0-9])\b([0-9]+)#(?!\w|#|[.,][0-9])")
+            .expect("Invalid primorial regex");
+        let mut primorial_list: Vec<Factorial> = Vec::new();
+
+        for regex_capture in primorial_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let num = regex_capture[1]
+                .parse::<BigInt>()
+                .expect("Failed to parse number");
+
+            if num > BigInt::from(UPPER_CALCULATION_LIMIT) {
+                status.push(Status::NumberTooBig);
+            } else if num == BigInt::one() {
+                continue;
+            } else {
+                let num = num.to_u64().expect("Failed to convert BigInt to u64");
+                primorial_list.push(Factorial {
+                    number: num,
+                    level: PRIMORIAL_LEVEL,
+                    factorial: math::primorial(num),
+                });
+                jobs.attach(
+                    num,
+                    PRIMORIAL_LEVEL,
+                    RedditComment::inline_commands(body, whole_match.end())?,
+                );
+            }
+        }
+
+        Ok(primorial_list)
+    }
+
+    /// Finds hyperfactorials written as `H(n)` and superfactorials written as `sf(n)`.
+    /// Small ones are calculated and added to the factorial list, for bigger ones only the digits get approximated.
+    fn extract_hyper_and_superfactorials(
+        body: &str,
+        factorial_list: &mut Vec<Factorial>,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<ApproximateDigits>, ParseError> {
+        let regex = Regex::new(r"(?<![\w.,])(H|sf)\(([0-9]+)\)")
+            .expect("Invalid hyperfactorial/superfactorial regex");
+        let mut approximate_digits_list: Vec<ApproximateDigits> = Vec::new();
+
+        for regex_capture in regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let num = regex_capture[2]
+                .parse::<BigInt>()
+                .expect("Failed to parse number");
+
+            if num > BigInt::from(UPPER_CALCULATION_LIMIT) {
+                status.push(Status::NumberTooBig);
+                continue;
+            } else if num == BigInt::one() {
+                continue;
+            }
+
+            let number = num.to_u64().expect("Failed to convert BigInt to u64");
+            let is_hyperfactorial = &regex_capture[1] == "H";
+            let (level, limit) = if is_hyperfactorial {
+                (HYPERFACTORIAL_LEVEL, UPPER_HYPERFACTORIAL_LIMIT)
+            } else {
+                (SUPERFACTORIAL_LEVEL, UPPER_SUPERFACTORIAL_LIMIT)
+            };
+
+            if number > limit {
+                let digits = if is_hyperfactorial {
+                    math::hyperfactorial_digits(number)
+                } else {
+                    math::superfactorial_digits(number)
+                };
+                approximate_digits_list.push(ApproximateDigits {
+                    number,
+                    level,
+                    digits,
+                });
+            } else {
+                let factorial = if is_hyperfactorial {
+                    math::hyperfactorial(number)
+                } else {
+                    math::superfactorial(number)
+                };
+                factorial_list.push(Factorial {
+                    number,
+                    level,
+                    factorial,
+                });
+                jobs.attach(
+                    number,
+                    level,
+                    RedditComment::inline_commands(body, whole_match.end())?,
+                );
+            }
+        }
+
+        Ok(approximate_digits_list)
+    }
+
+    /// Finds left factorials written as `!n`.
+    /// Small ones are calculated and added to the factorial list, for bigger ones only the digits get approximated.
+    fn extract_left_factorials(
+        body: &str,
+        factorial_list: &mut Vec<Factorial>,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<ApproximateDigits>, ParseError> {
+        let left_factorial_regex = Regex::new(r"(?<![\w!])!([0-9]+)(?![!\w]|[.,][0-9])")
+            .expect("Invalid left factorial regex");
+        let mut approximate_digits_list: Vec<ApproximateDigits> = Vec::new();
+
+        for regex_capture in left_factorial_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let num = regex_capture[1]
+                .parse::<BigInt>()
+                .expect("Failed to parse number");
+
+            if num > BigInt::from(UPPER_CALCULATION_LIMIT) {
+                status.push(Status::NumberTooBig);
+            } else if num == BigInt::one() {
+                continue;
+            } else {
+                let number = num.to_u64().expect("Failed to convert BigInt to u64");
+                if number > UPPER_LEFT_FACTORIAL_LIMIT {
+                    approximate_digits_list.push(ApproximateDigits {
+                        number,
+                        level: LEFT_FACTORIAL_LEVEL,
+                        digits: math::left_factorial_digits(number),
+                    });
+                } else {
+                    factorial_list.push(Factorial {
+                        number,
+                        level: LEFT_FACTORIAL_LEVEL,
+                        factorial: math::left_factorial(number),
+                    });
+                    jobs.attach(
+                        number,
+                        LEFT_FACTORIAL_LEVEL,
+                        RedditComment::inline_commands(body, whole_match.end())?,
+                    );
+                }
+            }
+        }
+
+        Ok(approximate_digits_list)
+    }
+
+    /// Finds alternating factorials written as `af(n)`.
+    /// Small ones are calculated and added to the factorial list, for bigger ones only the digits get approximated.
+    fn extract_alternating_factorials(
+        body: &str,
+        factorial_list: &mut Vec<Factorial>,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<ApproximateDigits>, ParseError> {
+        let alternating_factorial_regex =
+            Regex::new(r"(?<![\w.,])af\(([0-9]+)\)").expect("Invalid alternating factorial regex");
+        let mut approximate_digits_list: Vec<ApproximateDigits> = Vec::new();
+
+        for regex_capture in alternating_factorial_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let num = regex_capture[1]
+                .parse::<BigInt>()
+                .expect("Failed to parse number");
+
+            if num > BigInt::from(UPPER_CALCULATION_LIMIT) {
+                status.push(Status::NumberTooBig);
+            } else if num == BigInt::one() {
+                continue;
+            } else {
+                let number = num.to_u64().expect("Failed to convert BigInt to u64");
+                if number > UPPER_ALTERNATING_FACTORIAL_LIMIT {
+                    approximate_digits_list.push(ApproximateDigits {
+                        number,
+                        level: ALTERNATING_FACTORIAL_LEVEL,
+                        digits: math::alternating_factorial_digits(number),
+                    });
+                } else {
+                    factorial_list.push(Factorial {
+                        number,
+                        level: ALTERNATING_FACTORIAL_LEVEL,
+                        factorial: math::alternating_factorial(number),
+                    });
+                    jobs.attach(
+                        number,
+                        ALTERNATING_FACTORIAL_LEVEL,
+                        RedditComment::inline_commands(body, whole_match.end())?,
+                    );
+                }
+            }
+        }
+
+        Ok(approximate_digits_list)
+    }
+
+    /// Finds exponential factorials written as `ef(n)`.
+    /// Small ones are calculated and added to the factorial list, for bigger ones the digits get approximated as a power tower.
+    fn extract_exponential_factorials(
+        body: &str,
+        factorial_list: &mut Vec<Factorial>,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<ApproximateDigitsTower>, ParseError> {
+        let exponential_factorial_regex =
+            Regex::new(r"(?<![\w.,])ef\(([0-9]+)\)").expect("Invalid exponential factorial regex");
+        let mut approximate_digits_tower_list: Vec<ApproximateDigitsTower> = Vec::new();
+
+        for regex_capture in exponential_factorial_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let num = regex_capture[1]
+                .parse::<BigInt>()
+                .expect("Failed to parse number");
+
+            if num > BigInt::from(UPPER_EXPONENTIAL_FACTORIAL_TOWER_LIMIT) {
+                status.push(Status::NumberTooBig);
+            } else if num == BigInt::one() {
+                continue;
+            } else {
+                let number = num.to_u64().expect("Failed to convert BigInt to u64");
+                if number > UPPER_EXPONENTIAL_FACTORIAL_LIMIT {
+                    let (depth, top) = math::exponential_factorial_digits_tower(number);
+                    approximate_digits_tower_list.push(ApproximateDigitsTower {
+                        number,
+                        level: EXPONENTIAL_FACTORIAL_LEVEL,
+                        depth,
+                        top,
+                    });
+                } else {
+                    factorial_list.push(Factorial {
+                        number,
+                        level: EXPONENTIAL_FACTORIAL_LEVEL,
+                        factorial: math::exponential_factorial(number),
+                    });
+                    jobs.attach(
+                        number,
+                        EXPONENTIAL_FACTORIAL_LEVEL,
+                        RedditComment::inline_commands(body, whole_match.end())?,
+                    );
+                }
+            }
+        }
+
+        Ok(approximate_digits_tower_list)
+    }
+
+    /// Finds factorials of numbers written in e-notation, like `1e6!` or `2.5e999999999999!`.
+    /// Small ones are calculated and added to the factorial list, for bigger ones the digits get approximated as a power tower.
+    /// Only the logarithm of the number is needed for that, so the exponent may be far beyond what fits into an integer.
+    fn extract_scientific_notation_factorials(
+        body: &str,
+        factorial_list: &mut Vec<Factorial>,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<ScientificNotationFactorial>, ParseError> {
+        let scientific_notation_regex =
+            Regex::new(r"(?<![\w.,])([0-9]+)(?:\.([0-9]+))?[eE]([0-9]+)!(?![!0-9]|&lt;)")
+                .expect("Invalid scientific notation factorial regex");
+        let mut scientific_notation_list: Vec<ScientificNotationFactorial> = Vec::new();
+
+        for regex_capture in scientific_notation_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let fraction = regex_capture
+                .get(2)
+                .map_or("", |fraction| fraction.as_str())
+                .trim_end_matches('0');
+            let significant = format!("{}{}", &regex_capture[1], fraction);
+            let significant = significant.trim_start_matches('0');
+            // Parsing digits as a f64 can't fail, exponents beyond its range become infinite
+            let exponent = regex_capture[3]
+                .parse::<f64>()
+                .expect("Failed to parse exponent");
+            // Factorials of non-integers are not supported here
+            if !significant.is_empty() && fraction.len() as f64 > exponent {
+                continue;
+            }
+
+            // log10 of the number, from its leading digits and the exponent
+            let leading = &significant[..significant.len().min(15)];
+            let log_n = leading.parse::<f64>().map_or(f64::NEG_INFINITY, f64::log10)
+                + (significant.len() - leading.len()) as f64
+                + exponent
+                - fraction.len() as f64;
+
+            // Small enough to be written out, so it might be small enough to be calculated
+            if log_n < 18.0 {
+                let number = if significant.is_empty() {
+                    0
+                } else {
+                    significant.parse::<u64>().expect("Failed to parse number")
+                        * 10u64.pow(exponent as u32 - fraction.len() as u32)
+                };
+                if number <= UPPER_CALCULATION_LIMIT as u64 {
+                    if number != 1 {
+                        factorial_list.push(Factorial {
+                            number,
+                            level: 1,
+                            factorial: cache::factorial(number, 1),
+                        });
+                        jobs.attach(
+                            number,
+                            1,
+                            RedditComment::inline_commands(body, whole_match.end())?,
+                        );
+                    }
+                    continue;
+                }
+            }
+
+            scientific_notation_list.push(ScientificNotationFactorial {
+                number: regex_capture[0].trim_end_matches('!').to_string(),
+                digits: math::factorial_digits_tower(log_n),
+            });
+        }
+
+        Ok(scientific_notation_list)
+    }
+
+    /// Checks if a factorial ending at `end` is taken modulo something, like `5! mod 7` or `5! % 7`.
+    fn is_modular(body: &str, end: usize) -> Result<bool, ParseError> {
+        let modular_regex = Regex::new(r"^\s*(?:mod|%)\s*[0-9]").expect("Invalid modular regex");
+        Ok(modular_regex.is_match(&body[end..])?)
+    }
+
+    /// Checks if a factorial is part of a ratio of factorials, like either side of `52!/47!`.
+    fn is_ratio_part(body: &str, start: usize, end: usize) -> Result<bool, ParseError> {
+        let numerator_regex =
+            Regex::new(r"^\s*/\s*[0-9]+!(?![!0-9])").expect("Invalid ratio numerator regex");
+        let denominator_regex =
+            Regex::new(r"(?:^|[^!0-9])[0-9]+!\s*/\s*$").expect("Invalid ratio denominator regex");
+        Ok(
+            numerator_regex.is_match(&body[end..])?
+                || denominator_regex.is_match(&body[..start])?,
+        )
+    }
+
+    /// Finds ratios of factorials written as `n!/k!`.
+    /// Only the factors that don't cancel out are multiplied, so this works beyond the calculation limit.
+    fn extract_factorial_ratios(
+        body: &str,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<FactorialRatio>, ParseError> {
+        let ratio_regex = Regex::new(r"(?<![,.!?0-9])\b([0-9]+)!\s*/\s*([0-9]+)!(?![!0-9])")
+            .expect("Invalid factorial ratio regex");
+        let mut ratio_list: Vec<FactorialRatio> = Vec::new();
+
+        for regex_capture in ratio_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let (Ok(numerator), Ok(denominator)) = (
+                regex_capture[1].parse::<u64>(),
+                regex_capture[2].parse::<u64>(),
+            ) else {
+                status.push(Status::NumberTooBig);
+                continue;
+            };
+            let (high, low) = (numerator.max(denominator), numerator.min(denominator));
+            if high - low > UPPER_CALCULATION_LIMIT as u64 {
+                status.push(Status::NumberTooBig);
+                continue;
+            }
+
+            ratio_list.push(FactorialRatio {
+                numerator,
+                denominator,
+                ratio: math::falling_factorial(high, high - low),
+            });
+        }
+
+        Ok(ratio_list)
+    }
+
+    /// Finds factorials modulo some number, written as `n! mod m` or `n! % m`.
+    /// These work beyond the calculation limit, as the factorial itself is never calculated.
+    fn extract_modular_factorials(
+        body: &str,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<Modular>, ParseError> {
+        let modular_regex =
+            Regex::new(r"(?<![,.!?0-9])\b([0-9]+)(!+)\s*(?:mod|%)\s*([0-9]+)(?![0-9.,][0-9])")
+                .expect("Invalid modular regex");
+        let mut modular_list: Vec<Modular> = Vec::new();
+
+        for regex_capture in modular_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let num = regex_capture[1]
+                .parse::<BigInt>()
+                .expect("Failed to parse number");
+            let level = regex_capture[2].len() as u64;
+            let Ok(modulus) = regex_capture[3].parse::<u64>() else {
+                status.push(Status::NumberTooBig);
+                continue;
+            };
+            if modulus == 0 {
+                continue;
+            }
+
+            let remainder = if level == 1 && num >= BigInt::from(modulus) {
+                0
+            } else if num > BigInt::from(UPPER_MODULAR_LIMIT) {
+                status.push(Status::NumberTooBig);
+                continue;
+            } else {
+                let n = num.to_u64().expect("Failed to convert BigInt to u64");
+                math::factorial_mod(n, level, modulus)
+            };
+
+            modular_list.push(Modular {
+                number: num,
+                level: level as i64,
+                modulus,
+                remainder,
+            });
+        }
+
+        Ok(modular_list)
+    }
+
+    /// Finds factorials of non-integers written as `(-2.5)!` or `(0.5)!`.
+    /// They are calculated with the gamma function, negative integers have no factorial.
+    fn extract_gamma_factorials(
+        body: &str,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<GammaFactorial>, ParseError> {
+        let gamma_regex =
+            Regex::new(r"\((-?[0-9]+\.[0-9]+)\)!(?!!)").expect("Invalid gamma factorial regex");
+        let mut gamma_list: Vec<GammaFactorial> = Vec::new();
+
+        for regex_capture in gamma_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let number = regex_capture[1]
+                .parse::<f64>()
+                .expect("Failed to parse number");
+
+            if number.fract() == 0.0 {
+                continue;
+            }
+            status.push(Status::DecimalFactorial);
+            if number.abs() > UPPER_GAMMA_LIMIT {
+                status.push(Status::NumberTooBig);
+                continue;
+            }
+
+            gamma_list.push(GammaFactorial {
+                number,
+                factorial: math::gamma_factorial(number),
+            });
+        }
+
+        Ok(gamma_list)
+    }
+
+    /// Finds tetrations written as `a↑↑b` or `a^^b`.
+    /// Small ones are calculated, for bigger ones the digits get approximated as a power tower.
+    fn extract_tetrations(
+        body: &str,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<Tetration>, ParseError> {
+        let tetration_regex =
+            Regex::new(r"(?<![\w.,^↑])([0-9]+)\s*(?:↑↑|\^\^)\s*([0-9]+)(?![\w^↑]|[.,][0-9])")
+                .expect("Invalid tetration regex");
+        let mut tetration_list: Vec<Tetration> = Vec::new();
+
+        for regex_capture in tetration_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let (Ok(base), Ok(height)) = (
+                regex_capture[1].parse::<u64>(),
+                regex_capture[2].parse::<u64>(),
+            ) else {
+                status.push(Status::NumberTooBig);
+                continue;
+            };
+            // Towers of zeros and ones are boring
+            if base < 2 || height < 2 {
+                continue;
+            }
+            if height > UPPER_TETRATION_HEIGHT {
+                status.push(Status::NumberTooBig);
+                continue;
+            }
+
+            let (tens, top) = math::tetration_tower(base, height);
+            let digits = match tens {
+                0 => top.log10(),
+                1 => top,
+                _ => f64::INFINITY,
+            };
+            let result = if digits < UPPER_TETRATION_DIGITS {
+                TetrationResult::Exact(math::tetration(base, height))
+            } else {
+                TetrationResult::DigitsTower(ApproximateDigitsTower {
+                    number: base,
+                    level: TETRATION_LEVEL,
+                    depth: tens - 1,
+                    top,
+                })
+            };
+            tetration_list.push(Tetration {
+                base,
+                height,
+                result,
+            });
+        }
+
+        Ok(tetration_list)
+    }
+
+    /// Finds binomial coefficients written as `C(n,k)`, `n choose k` or `nCk`.
+    fn extract_binomials(
+        body: &str,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<Binomial>, ParseError> {
+        let binomial_regex = Regex::new(
+            r"(?<![\w.,])(?:C\(([0-9]+),\s*([0-9]+)\)|([0-9]+)\s+(?i:choose)\s+([0-9]+)|([0-9]+)C([0-9]+))(?!\w|[.,][0-9])",
+        )
+        .expect("Invalid binomial regex");
+        let mut binomial_list: Vec<Binomial> = Vec::new();
+
+        for regex_capture in binomial_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            // Only one of the alternatives matched, so take the first pair of numbers we find
+            let mut numbers = regex_capture.iter().skip(1).flatten().map(|m| {
+                m.as_str()
+                    .parse::<BigInt>()
+                    .expect("Failed to parse number")
+            });
+            let (Some(n), Some(k)) = (numbers.next(), numbers.next()) else {
+                continue;
+            };
+
+            if n > BigInt::from(UPPER_CALCULATION_LIMIT) {
+                status.push(Status::NumberTooBig);
+                continue;
+            }
+            if k > n {
+                continue;
+            }
+            let n = n.to_u64().expect("Failed to convert BigInt to u64");
+            let k = k.to_u64().expect("Failed to convert BigInt to u64");
+            binomial_list.push(Binomial {
+                n,
+                k,
+                coefficient: math::binomial(n, k),
+            });
+        }
+
+        binomial_list.sort();
+        binomial_list.dedup();
+        Ok(binomial_list)
+    }
+
+    /// Finds inverse queries like `!inverse 3628800`, `!inverse termial 55`, "what factorial is 120" or "what termial is 55".
+    fn extract_inverse_queries(
+        body: &str,
+        jobs: &mut Jobs,
+        status: &mut Vec<Status>,
+    ) -> Result<Vec<InverseQuery>, ParseError> {
+        let inverse_regex = Regex::new(
+            r"(?i)(?:!inverse\s+(?:(termial)\s+)?|\bwhat\s+(?:factorial|(termial))\s+is\s+)([0-9]+)(?![0-9]|[.,][0-9])",
+        )
+        .expect("Invalid inverse query regex");
+        let mut inverse_list: Vec<InverseQuery> = Vec::new();
+
+        for regex_capture in inverse_regex.captures_iter(body) {
+            let regex_capture = regex_capture?;
+            let termial = regex_capture.get(1).is_some() || regex_capture.get(2).is_some();
+            let number = regex_capture[3]
+                .parse::<BigInt>()
+                .expect("Failed to parse number");
+            // No factorial is zero
+            if !termial && number.is_zero() {
+                continue;
+            }
+            let whole_match = regex_capture.get(0).expect("Failed to get regex match");
+            if !jobs.take(whole_match.range(), status) {
+                break;
+            }
+
+            let (n, exact) = if termial {
+                let n = math::inverse_termial(&number);
+                let exact = math::termial(&n) == number;
+                (n, exact)
+            } else {
+                let n = math::inverse_factorial(&number);
+                (BigInt::from(n), math::factorial(n, 1) == number)
+            };
+            inverse_list.push(InverseQuery {
+                number,
+                termial,
+                n,
+                exact,
+            });
+        }
+
+        Ok(inverse_list)
+    }
+
+    /// Names the operation of a level, e.g. "Double-Factorial" or "Primorial".
+    /// Inside a sentence the operation itself is written in lowercase, e.g. "Double-factorial".
+    fn get_operation_name(level: i64, in_sentence: bool) -> String {
+        let (prefix, operation) = match level {
+            PRIMORIAL_LEVEL => ("", "primorial"),
+            HYPERFACTORIAL_LEVEL => ("", "hyperfactorial"),
+            SUPERFACTORIAL_LEVEL => ("", "superfactorial"),
+            LEFT_FACTORIAL_LEVEL => ("", "left factorial"),
+            ALTERNATING_FACTORIAL_LEVEL => ("", "alternating factorial"),
+            EXPONENTIAL_FACTORIAL_LEVEL => ("", "exponential factorial"),
+            _ => (
+                RedditComment::get_factorial_level_string(level),
+                "factorial",
+            ),
+        };
+        if in_sentence {
+            format!("{prefix}{operation}")
+        } else {
+            format!(
+                "{prefix}{}{}",
+                operation[..1].to_uppercase(),
+                &operation[1..]
+            )
+        }
+    }
+
+    fn get_factorial_level_string(level: i64) -> &'static str {
+        match level {
+            1 => "",
+            2 => "Double-",
+            3 => "Triple-",
+            4 => "Quadruple-",
+            5 => "Quintuple-",
+            6 => "Sextuple-",
+            7 => "Septuple-",
+            8 => "Octuple-",
+            9 => "Nonuple-",
+            10 => "Decuple-",
+            11 => "Undecuple-",
+            12 => "Duodecuple-",
+            13 => "Tredecuple-",
+            14 => "Quattuordecuple-",
+            15 => "Quindecuple-",
+            16 => "Sexdecuple-",
+            17 => "Septendecuple-",
+            18 => "Octodecuple-",
+            19 => "Novemdecuple-",
+            20 => "Vigintuple-",
+            21 => "Unvigintuple-",
+            22 => "Duovigintuple-",
+            23 => "Trevigintuple-",
+            24 => "Quattuorvigintuple-",
+            25 => "Quinvigintuple-",
+            26 => "Sexvigintuple-",
+            27 => "Septenvigintuple-",
+            28 => "Octovigintuple-",
+            29 => "Novemvigintuple-",
+            30 => "Trigintuple-",
+            31 => "Untrigintuple-",
+            32 => "Duotrigintuple-",
+            33 => "Tretrigintuple-",
+            34 => "Quattuortrigintuple-",
+            35 => "Quintrigintuple-",
+            36 => "Sextrigintuple-",
+            37 => "Septentrigintuple-",
+            38 => "Octotrigintuple-",
+            39 => "Novemtrigintuple-",
+            40 => "Quadragintuple-",
+            41 => "Unquadragintuple-",
+            42 => "Duoquadragintuple-",
+            43 => "Trequadragintuple-",
+            44 => "Quattuorquadragintuple-",
+            45 => "Quinquadragintuple-",
+            _ => "n-",
+        }
+    }
+
+    fn factorials_are_too_long<'a>(
+        factorial_list: impl IntoIterator<Item = &'a Factorial>,
+    ) -> bool {
+        factorial_list
+            .into_iter()
+            .any(|Factorial { number, level, .. }| {
+                RedditComment::max_printable_number(*level).is_some_and(|max| *number > max)
+            })
+    }
+
+    /// The biggest number for which the result of an operation still fits into a reply.
+    fn max_printable_number(level: i64) -> Option<u64> {
+        let max = match level {
+            PRIMORIAL_LEVEL => 23166,
+            HYPERFACTORIAL_LEVEL => 104,
+            SUPERFACTORIAL_LEVEL => 117,
+            LEFT_FACTORIAL_LEVEL => 3249,
+            ALTERNATING_FACTORIAL_LEVEL => 3249,
+            EXPONENTIAL_FACTORIAL_LEVEL => 4,
+            1 => 3249,
+            2 => 5982,
+            3 => 8572,
+            4 => 11077,
+            5 => 13522,
+            6 => 15920,
+            7 => 18282,
+            8 => 20613,
+            9 => 22920,
+            10 => 25208,
+            11 => 27479,
+            12 => 29735,
+            13 => 31977,
+            14 => 34207,
+            15 => 36426,
+            16 => 38635,
+            17 => 40835,
+            18 => 43027,
+            19 => 45212,
+            20 => 47390,
+            21 => 49562,
+            22 => 51728,
+            23 => 53889,
+            24 => 56045,
+            25 => 58197,
+            26 => 60345,
+            27 => 62489,
+            28 => 64630,
+            29 => 66768,
+            30 => 68903,
+            31 => 71036,
+            32 => 73167,
+            33 => 75296,
+            34 => 77423,
+            35 => 79548,
+            36 => 81672,
+            37 => 83794,
+            38 => 85915,
+            39 => 88035,
+            40 => 90154,
+            41 => 92272,
+            42 => 94389,
+            43 => 96505,
+            44 => 98620,
+            45 => 100734,
+            _ => return None,
+        };
+        Some(max)
+    }
+
+    /// Writes a calculation the way it would be written in a comment, e.g. `5!!` or `H(3)`.
+    fn format_notation(number: u64, level: i64) -> String {
+        match level {
+            PRIMORIAL_LEVEL => format!("{number}#"),
+            HYPERFACTORIAL_LEVEL => format!("H({number})"),
+            SUPERFACTORIAL_LEVEL => format!("sf({number})"),
+            LEFT_FACTORIAL_LEVEL => format!("!{number}"),
+            ALTERNATING_FACTORIAL_LEVEL => format!("af({number})"),
+            EXPONENTIAL_FACTORIAL_LEVEL => format!("ef({number})"),
+            _ => format!("{number}{}", "!".repeat(level as usize)),
+        }
+    }
+
+    /// Tells up to which number the operations that were too big can be printed exactly.
+    fn get_exact_limit_note(&self) -> Option<String> {
+        let mut levels: Vec<i64> = self
+            .factorial_list
+            .iter()
+            .filter(|f| {
+                RedditComment::max_printable_number(f.level).is_some_and(|max| f.number > max)
+            })
+            .map(|f| f.level)
+            .chain(self.approximate_digits_list.iter().map(|a| a.level))
+            .chain(self.approximate_digits_tower_list.iter().map(|a| a.level))
+            .collect();
+        levels.sort();
+        levels.dedup();
+
+        let limits: Vec<String> = levels
+            .into_iter()
+            .filter_map(|level| {
+                RedditComment::max_printable_number(level)
+                    .map(|max| RedditComment::format_notation(max, level))
+            })
+            .collect();
+        if limits.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "I can print up to {} exactly, just ask for it :)\n\n",
+            limits.join(" and ")
+        ))
+    }
+
+    /// Tells that only the first calculations were done, if the comment asked for too many or they took too long.
+    fn get_job_limit_note(&self) -> Option<String> {
+        if self.status.contains(&Status::TooExpensive) {
+            return Some(
+                "Those calculations took too long, so I stopped after the first ones.\n\n"
+                    .to_string(),
+            );
+        }
+        self.status.contains(&Status::TooManyCalculations).then(|| {
+            format!(
+                "That's a lot of calculations, so I only did the first {} of them.\n\n",
+                self.max_jobs_per_comment
+            )
+        })
+    }
+
+    /// Compares the biggest result with a known number of digits to something tangible, if the comment asks for it.
+    fn get_fun_facts_note(&self) -> Option<String> {
+        if !self.commands.contains(Commands::FUN_FACTS) {
+            return None;
+        }
+        let digits = self
+            .factorial_list
+            .iter()
+            .map(|factorial| &factorial.factorial)
+            .chain(
+                self.binomial_list
+                    .iter()
+                    .map(|binomial| &binomial.coefficient),
+            )
+            .map(|number| number.magnitude().to_string().len() as u64)
+            .chain(
+                self.approximate_digits_list
+                    .iter()
+                    .map(|approximate| approximate.digits),
+            )
+            .max()?;
+        let sentence = fun_facts::sentence("The biggest result", &fun_facts::fun_facts(digits))?;
+        Some(format!("_Fun fact: {sentence}._\n\n"))
+    }
+
+    /// Links the uploaded results, if they were too long for reddit.
+    fn get_paste_note(&self) -> Option<String> {
+        self.paste_link
+            .as_ref()
+            .map(|link| format!("You can find the whole numbers [here]({link}).\n\n"))
+    }
+
+    /// Formats a number in scientific notation with up to `decimals` decimals.
+    fn format_scientific(number: &BigInt, decimals: usize) -> String {
+        let mut truncated_number = number.to_string();
+        let length = truncated_number.len();
+        truncated_number.truncate(decimals + 2); // There is one digit before the decimals and the digit for rounding
+
+        // Round if we had to truncate
+        if truncated_number.len() >= decimals + 2 {
+            math::round(&mut truncated_number);
+        };
+        // Only add decimal if we have more than one digit
+        if truncated_number.len() > 1 {
+            truncated_number.insert(1, '.'); // Decimal point
+        }
+
+        // exponent is one less than the length
+        format!("{}e{}", truncated_number, length - 1)
+    }
+
+    fn format_approximate_digits(approximate_digits: &ApproximateDigits) -> String {
+        format!(
+            "{} of {} has approximately {}",
+            RedditComment::get_operation_name(approximate_digits.level, false),
+            approximate_digits.number,
+            plural(approximate_digits.digits, "digit")
+        )
+    }
+
+    fn format_approximate_digits_tower(tower: &ApproximateDigitsTower) -> String {
+        format!(
+            "{} of {} has approximately {} digits",
+            RedditComment::get_operation_name(tower.level, false),
+            tower.number,
+            RedditComment::format_tower(tower.depth, tower.top)
+        )
+    }
+
+    /// Writes the number of digits like `10^10^183230.68`, without any tens as a whole number.
+    fn format_tower(mut depth: u64, mut top: f64) -> String {
+        // Too big to be written as a whole number, so it gets another ten
+        if depth == 0 && top >= 1e15 {
+            (depth, top) = (1, top.log10());
+        }
+        if depth == 0 {
+            return format!("{}", top.floor() as u64 + 1);
+        }
+        format!("{}{:.2}", "10^".repeat(depth as usize), top)
+    }
+
+    fn format_scientific_notation_factorial(factorial: &ScientificNotationFactorial) -> String {
+        match factorial.digits {
+            Some((depth, top)) => format!(
+                "Factorial of {} has approximately {} digits",
+                factorial.number,
+                RedditComment::format_tower(depth, top)
+            ),
+            None => format!(
+                "Factorial of {} is too large to even describe",
+                factorial.number
+            ),
+        }
+    }
+
+    /// Writes only the number of digits of an exact result, for the `digits` command.
+    /// Unlike the approximated digits of results too big to calculate, the count is exact.
+    fn format_digit_count(name: &str, number: &BigInt) -> String {
+        format!(
+            "{name} has {}",
+            plural(number.magnitude().to_string().len(), "digit")
+        )
+    }
+
+    fn format_tetration(tetration: &Tetration) -> String {
+        match &tetration.result {
+            TetrationResult::Exact(value) => {
+                format!("{}↑↑{} is {}", tetration.base, tetration.height, value)
+            }
+            TetrationResult::DigitsTower(tower) => format!(
+                "{}↑↑{} has approximately {} digits",
+                tetration.base,
+                tetration.height,
+                RedditComment::format_tower(tower.depth, tower.top)
+            ),
+        }
+    }
+
+    fn format_trailing_zeros(trailing_zeros: &TrailingZeros) -> String {
+        format!(
+            "Factorial of {} has {}",
+            trailing_zeros.number,
+            plural(&trailing_zeros.zeros, "trailing zero")
+        )
+    }
+
+    /// Writes the factorization like `2^8 × 3^4 × 5^2 × 7`.
+    fn format_prime_factorization(factorization: &PrimeFactorization) -> String {
+        let factors = factorization
+            .factors
+            .iter()
+            .map(|(prime, exponent)| {
+                if *exponent == 1 {
+                    prime.to_string()
+                } else {
+                    format!("{prime}^{exponent}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" × ");
+        format!(
+            "Prime factorization of {}! is {}",
+            factorization.number, factors
+        )
+    }
+
+    /// The identity relating a double factorial to a factorial, if the comment asked for it:
+    /// n!! = 2^(n/2) × (n/2)! for even n and n!! = n! / (2^((n-1)/2) × ((n-1)/2)!) for odd n.
+    fn get_identity(commands: Commands, factorial: &Factorial) -> Option<String> {
+        if !commands.contains(Commands::IDENTITIES) || factorial.level != 2 {
+            return None;
+        }
+        let n = factorial.number;
+        let half = n / 2;
+        let identity = if n.is_multiple_of(2) {
+            format!("{n}!! = 2^{half} × {half}!")
+        } else {
+            format!("{n}!! = {n}! / (2^{half} × {half}!)")
+        };
+        Some(format!("_Using the identity {identity}_"))
+    }
+
+    /// Stirling's approximation of a factorial and how far off it is, if `commands` ask for it.
+    fn get_approximation(commands: Commands, factorial: &Factorial) -> Option<String> {
+        if !commands.contains(Commands::APPROXIMATE)
+            || factorial.level != 1
+            || factorial.number == 0
+        {
+            return None;
+        }
+        let log = math::stirling_log10(factorial.number);
+        let exponent = log.floor();
+        let error = 1.0 - 10f64.powf(log - math::log10(&factorial.factorial));
+        Some(format!(
+            "_Stirling's approximation gives roughly {:.4}e{}, {:.2}% less_",
+            10f64.powf(log - exponent),
+            exponent,
+            error * 100.0
+        ))
+    }
+
+    /// Points out factorions among the number and result of a factorial, unless `commands` ask for no notes.
+    /// The trivial factorions 1 and 2 are left out.
+    fn get_factorion_note(commands: Commands, factorial: &Factorial) -> Option<String> {
+        if commands.contains(Commands::NO_NOTE) {
+            return None;
+        }
+        let factorion = [BigInt::from(factorial.number), factorial.factorial.clone()]
+            .into_iter()
+            .find(|n| *n > BigInt::from(2) && math::is_factorion(n))?;
+        let sum = factorion
+            .to_string()
+            .chars()
+            .map(|digit| format!("{digit}!"))
+            .collect::<Vec<_>>()
+            .join(" + ");
+        Some(format!(
+            "_Fun fact: {factorion} is a factorion, as {sum} = {factorion}_"
+        ))
+    }
+
+    /// The commands for a factorial: the ones of the comment and the ones written right behind it.
+    fn factorial_commands(&self, factorial: &Factorial) -> Commands {
+        self.commands
+            | self
+                .inline_commands
+                .get(&(factorial.number, factorial.level))
+                .copied()
+                .unwrap_or_default()
+    }
+
+    /// The digit count and digit sum of an exact result, if `commands` ask for them.
+    fn get_digit_facts(commands: Commands, number: &BigInt) -> String {
+        if !commands.contains(Commands::DIGIT_FACTS) {
+            return String::new();
+        }
+        let digits = number.magnitude().to_string();
+        let digit_sum: u64 = digits.bytes().map(|digit| (digit - b'0') as u64).sum();
+        format!(
+            "^(({}, digit sum {})) ",
+            plural(digits.len(), "digit"),
+            digit_sum
+        )
+    }
+
+    fn format_gamma_factorial(gamma: &GammaFactorial) -> String {
+        format!(
+            "Factorial of {} is approximately {:.GAMMA_DECIMALS$e}",
+            gamma.number, gamma.factorial
+        )
+    }
+
+    fn format_factorial_ratio(ratio: &FactorialRatio) -> String {
+        let inverse = if ratio.numerator < ratio.denominator {
+            "1/"
+        } else {
+            ""
+        };
+        format!(
+            "{}!/{}! is {inverse}{}",
+            ratio.numerator, ratio.denominator, ratio.ratio
+        )
+    }
+
+    /// Writes like "120 is the factorial of 5" or "100 is between the factorials of 4 and 5".
+    fn format_inverse_query(&self, inverse: &InverseQuery) -> String {
+        // Numbers of a whole comment would drown the answer
+        let shorten = |number: &BigInt| {
+            let digits = number.to_string();
+            if digits.len() > MAX_INVERSE_NUMBER_LENGTH {
+                RedditComment::format_scientific(number, self.decimals)
+            } else {
+                digits
+            }
+        };
+        let operation = if inverse.termial {
+            "termial"
+        } else {
+            "factorial"
+        };
+        if inverse.exact {
+            format!(
+                "{} is the {operation} of {}",
+                shorten(&inverse.number),
+                shorten(&inverse.n)
+            )
+        } else {
+            format!(
+                "{} is between the {operation}s of {} and {}",
+                shorten(&inverse.number),
+                shorten(&inverse.n),
+                shorten(&(&inverse.n + 1))
+            )
+        }
+    }
+
+    fn format_modular(modular: &Modular) -> String {
+        format!(
+            "{} of {} mod {} is {}",
+            RedditComment::get_operation_name(modular.level, false),
+            modular.number,
+            modular.modulus,
+            modular.remainder
+        )
+    }
+
+    pub(crate) fn add_status(&mut self, status: Status) {
+        self.status.push(status);
+    }
+
+    pub(crate) fn get_reply(&self) -> String {
+        self.get_reply_with_footer(FOOTER_TEXT)
+    }
+
+    pub(crate) fn get_reply_with_footer(&self, footer: &str) -> String {
+        self.get_reply_chain_with_footer(footer, 1)
+            .pop()
+            .expect("A reply chain always has a reply")
+    }
+
+    /// The reply with every result written out, without the footer.
+    /// It might be too long for a comment, see `ReplyWouldBeTooLong`.
+    pub(crate) fn format_exact_reply(&self) -> String {
+        let mut reply = self
+            .factorial_list
+            .iter()
+            .fold(String::new(), |mut acc, factorial| {
+                let commands = self.factorial_commands(factorial);
+                if commands.contains(Commands::DIGITS_ONLY) {
+                    let name = format!(
+                        "{} of {}",
+                        RedditComment::get_operation_name(factorial.level, false),
+                        factorial.number
+                    );
+                    let _ = write!(
+                        acc,
+                        "{} \n\n",
+                        RedditComment::format_digit_count(&name, &factorial.factorial)
+                    );
+                    return acc;
+                }
+                if commands.contains(Commands::SHORT) {
+                    let _ = write!(
+                        acc,
+                        "{} of {} is roughly {} \n\n",
+                        RedditComment::get_operation_name(factorial.level, false),
+                        factorial.number,
+                        RedditComment::format_scientific(&factorial.factorial, self.decimals)
+                    );
+                    return acc;
+                }
+                let _ = write!(
+                    acc,
+                    "{} of {} is {} {}\n\n",
+                    RedditComment::get_operation_name(factorial.level, false),
+                    factorial.number,
+                    factorial.factorial,
+                    RedditComment::get_digit_facts(commands, &factorial.factorial)
+                );
+                if let Some(identity) = RedditComment::get_identity(commands, factorial) {
+                    let _ = write!(acc, "{identity} \n\n");
+                }
+                if let Some(approximation) = RedditComment::get_approximation(commands, factorial) {
+                    let _ = write!(acc, "{approximation} \n\n");
+                }
+                if let Some(note) = RedditComment::get_factorion_note(commands, factorial) {
+                    let _ = write!(acc, "{note} \n\n");
+                }
+                acc
+            });
+        let digits_only = self.commands.contains(Commands::DIGITS_ONLY);
+        for binomial in self.binomial_list.iter() {
+            if digits_only {
+                let name = format!(
+                    "Binomial coefficient of {} choose {}",
+                    binomial.n, binomial.k
+                );
+                let _ = write!(
+                    reply,
+                    "{} \n\n",
+                    RedditComment::format_digit_count(&name, &binomial.coefficient)
+                );
+                continue;
+            }
+            let _ = write!(
+                reply,
+                "Binomial coefficient of {} choose {} is {} {}\n\n",
+                binomial.n,
+                binomial.k,
+                binomial.coefficient,
+                RedditComment::get_digit_facts(self.commands, &binomial.coefficient)
+            );
+        }
+        for ratio in self.ratio_list.iter() {
+            // The digits of 1/x are no magnitude
+            let formatted = if digits_only && ratio.numerator >= ratio.denominator {
+                let name = format!("{}!/{}!", ratio.numerator, ratio.denominator);
+                RedditComment::format_digit_count(&name, &ratio.ratio)
+            } else {
+                RedditComment::format_factorial_ratio(ratio)
+            };
+            let _ = write!(reply, "{formatted} \n\n");
+        }
+        for tetration in self.tetration_list.iter() {
+            let formatted = match &tetration.result {
+                TetrationResult::Exact(value) if digits_only => {
+                    let name = format!("{}↑↑{}", tetration.base, tetration.height);
+                    RedditComment::format_digit_count(&name, value)
+                }
+                _ => RedditComment::format_tetration(tetration),
+            };
+            let _ = write!(reply, "{formatted} \n\n");
+        }
+        for gamma in self.gamma_list.iter() {
+            let _ = write!(
+                reply,
+                "{} \n\n",
+                RedditComment::format_gamma_factorial(gamma)
+            );
+        }
+        for modular in self.modular_list.iter() {
+            let _ = write!(reply, "{} \n\n", RedditComment::format_modular(modular));
+        }
+        for inverse in self.inverse_list.iter() {
+            let _ = write!(reply, "{} \n\n", self.format_inverse_query(inverse));
+        }
+        for approximate_digits in self.approximate_digits_list.iter() {
+            let _ = write!(
+                reply,
+                "{} \n\n",
+                RedditComment::format_approximate_digits(approximate_digits)
+            );
+        }
+        for tower in self.approximate_digits_tower_list.iter() {
+            let _ = write!(
+                reply,
+                "{} \n\n",
+                RedditComment::format_approximate_digits_tower(tower)
+            );
+        }
+        for factorial in self.scientific_notation_list.iter() {
+            let _ = write!(
+                reply,
+                "{} \n\n",
+                RedditComment::format_scientific_notation_factorial(factorial)
+            );
+        }
+        for trailing_zeros in self.trailing_zeros_list.iter() {
+            let _ = write!(
+                reply,
+                "{} \n\n",
+                RedditComment::format_trailing_zeros(trailing_zeros)
+            );
+        }
+        for factorization in self.prime_factorization_list.iter() {
+            let _ = write!(
+                reply,
+                "{} \n\n",
+                RedditComment::format_prime_factorization(factorization)
+            );
+        }
+        if self.commands.contains(Commands::EXACT_LIMIT_NOTE) {
+            if let Some(note) = self.get_exact_limit_note() {
+                reply.push_str(&note);
+            }
+        }
+        if let Some(note) = self.get_job_limit_note() {
+            reply.push_str(&note);
+        }
+        if let Some(note) = self.get_fun_facts_note() {
+            reply.push_str(&note);
+        }
+        reply
+    }
+
+    /// The whole results to upload elsewhere, if they can't be posted on reddit exactly,
+    /// not even split into a chain of up to `max_chain_length` comments with the `full` command.
+    pub(crate) fn get_paste_text(&self, max_chain_length: usize) -> Option<String> {
+        let text = self.format_exact_reply();
+        let full = self.commands.contains(Commands::FULL) && max_chain_length > 1;
+        if text.len() <= MAX_COMMENT_LENGTH as usize
+            || (full && RedditComment::split_text_into_chain(&text, max_chain_length).is_some())
+        {
+            return None;
+        }
+        Some(text)
+    }
+
+    /// Builds the reply, split into up to `max_chain_length` comments if it would be too long for one.
+    /// The first one answers the comment, every further one answers the one before.
+    /// Each of them gets the footer.
+    /// With the `full` command the exact results are split instead, if they fit into the chain.
+    pub(crate) fn get_reply_chain_with_footer(
+        &self,
+        footer: &str,
+        max_chain_length: usize,
+    ) -> Vec<String> {
+        let mut reply;
+
+        let full = self.commands.contains(Commands::FULL) && max_chain_length > 1;
+
+        // Normal case
+        if !(self.status.contains(&Status::ReplyWouldBeTooLong)) || full {
+            reply = self.format_exact_reply();
+
+            // Results that are each short enough can still add up to too much for one comment
+            if reply.len() <= MAX_COMMENT_LENGTH as usize {
+                reply.push_str(footer);
+                return vec![reply];
+            }
+            if full {
+                if let Some(chain) = RedditComment::split_text_into_chain(&reply, max_chain_length)
+                {
+                    return chain
+                        .into_iter()
+                        .map(|mut reply| {
+                            reply.push_str(footer);
+                            reply
+                        })
+                        .collect();
+                }
+            }
+        }
+
+        // Too long reply
+        // Every result gets a name for the single-result sentence, one for the list and its scientific notation
+        let scientifics: Vec<(String, String, String)> = self
+            .factorial_list
+            .iter()
+            .map(|f| {
+                (
+                    format!(
+                        "{} of {}",
+                        RedditComment::get_operation_name(f.level, true),
+                        f.number
+                    ),
+                    format!(
+                        "{} of {}",
+                        RedditComment::get_operation_name(f.level, false),
+                        f.number
+                    ),
+                    RedditComment::format_scientific(&f.factorial, self.decimals),
+                )
+            })
+            .chain(self.binomial_list.iter().map(|b| {
+                (
+                    format!("binomial coefficient of {} choose {}", b.n, b.k),
+                    format!("Binomial coefficient of {} choose {}", b.n, b.k),
+                    RedditComment::format_scientific(&b.coefficient, self.decimals),
+                )
+            }))
+            .chain(self.ratio_list.iter().map(|r| {
+                let inverse = if r.numerator < r.denominator {
+                    "1/"
+                } else {
+                    ""
+                };
+                (
+                    format!("ratio {}!/{}!", r.numerator, r.denominator),
+                    format!("{}!/{}!", r.numerator, r.denominator),
+                    format!(
+                        "{inverse}{}",
+                        RedditComment::format_scientific(&r.ratio, self.decimals)
+                    ),
+                )
+            }))
+            .collect();
+
+        let mut notes = if self.commands.contains(Commands::EXACT_LIMIT_NOTE) {
+            self.get_exact_limit_note().unwrap_or_default()
+        } else {
+            String::new()
+        };
+        notes.push_str(&self.get_job_limit_note().unwrap_or_default());
+        notes.push_str(&self.get_fun_facts_note().unwrap_or_default());
+        notes.push_str(&self.get_paste_note().unwrap_or_default());
+        let mut entries: Vec<String> = vec![];
+
+        if scientifics.len() == 1
+            && self.approximate_digits_list.is_empty()
+            && self.approximate_digits_tower_list.is_empty()
+            && self.scientific_notation_list.is_empty()
+            && self.trailing_zeros_list.is_empty()
+            && self.prime_factorization_list.is_empty()
+            && self.modular_list.is_empty()
+            && self.gamma_list.is_empty()
+            && self.tetration_list.is_empty()
+            && self.inverse_list.is_empty()
+        {
+            let (name, _, scientific) = &scientifics[0];
+            reply = format!(
+                "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n \
+                In scientific notation the {} would be (roughly) {} though :)\n\n",
+                name, scientific
+            );
+        } else {
+            entries = scientifics
+                .iter()
+                .map(|(_, name, scientific)| format!("{name} = {scientific}"))
+                .chain(self.modular_list.iter().map(RedditComment::format_modular))
+                .chain(
+                    self.inverse_list
+                        .iter()
+                        .map(|inverse| self.format_inverse_query(inverse)),
+                )
+                .chain(
+                    self.gamma_list
+                        .iter()
+                        .map(RedditComment::format_gamma_factorial),
+                )
+                .chain(
+                    self.tetration_list
+                        .iter()
+                        .map(RedditComment::format_tetration),
+                )
+                .chain(
+                    self.approximate_digits_list
+                        .iter()
+                        .map(RedditComment::format_approximate_digits),
+                )
+                .chain(
+                    self.approximate_digits_tower_list
+                        .iter()
+                        .map(RedditComment::format_approximate_digits_tower),
+                )
+                .chain(
+                    self.scientific_notation_list
+                        .iter()
+                        .map(RedditComment::format_scientific_notation_factorial),
+                )
+                .chain(
+                    self.trailing_zeros_list
+                        .iter()
+                        .map(RedditComment::format_trailing_zeros),
+                )
+                .chain(
+                    self.prime_factorization_list
+                        .iter()
+                        .map(RedditComment::format_prime_factorization),
+                )
+                .collect();
+            reply = format!("{TOO_LONG_HEADER}{}\n\n:)\n\n", entries.join(",\n\n"));
+        }
+
+        reply.push_str(&notes);
+
+        if reply.len() > MAX_COMMENT_LENGTH as usize {
+            if let Some(chain) = RedditComment::split_into_chain(&entries, &notes, max_chain_length)
+            {
+                return chain
+                    .into_iter()
+                    .map(|mut reply| {
+                        reply.push_str(footer);
+                        reply
+                    })
+                    .collect();
+            }
+            reply = "Sorry, but the reply text for all those number would be _really_ long, so I'd rather not even try posting lmao\n".to_string();
+            if let Some(note) = self.get_paste_note() {
+                reply.push_str(&format!("\n{note}"));
+            }
+        }
+
+        reply.push_str(footer);
+        vec![reply]
+    }
+
+    /// Cuts a too long reply into comments of at most `MAX_COMMENT_LENGTH`, preferably between paragraphs,
+    /// otherwise in the middle of a number. Returns `None` if more than `max_chain_length` comments would be needed.
+    fn split_text_into_chain(text: &str, max_chain_length: usize) -> Option<Vec<String>> {
+        let budget = MAX_COMMENT_LENGTH as usize - FULL_CHAIN_CONTINUED.len();
+        let mut chunks: Vec<String> = vec![];
+        let mut rest = text;
+        while rest.len() > MAX_COMMENT_LENGTH as usize {
+            if chunks.len() + 1 >= max_chain_length {
+                return None;
+            }
+            let mut end = budget;
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            let end = match rest[..end].rfind("\n\n") {
+                Some(paragraph_end) if paragraph_end > 0 => paragraph_end,
+                _ => end,
+            };
+            let (chunk, remaining) = rest.split_at(end);
+            chunks.push(format!("{chunk}{FULL_CHAIN_CONTINUED}"));
+            rest = remaining.trim_start_matches('\n');
+        }
+        chunks.push(rest.to_string());
+        Some(chunks)
+    }
+
+    /// Distributes the entries of a too long reply over several comments, each short enough for reddit.
+    /// Returns `None` if more than `max_chain_length` comments would be needed.
+    fn split_into_chain(
+        entries: &[String],
+        notes: &str,
+        max_chain_length: usize,
+    ) -> Option<Vec<String>> {
+        // Reserve space for the longest ending, so every chunk can be the last one
+        let budget = (MAX_COMMENT_LENGTH as usize)
+            .saturating_sub(TOO_LONG_HEADER.len())
+            .saturating_sub(CHAIN_CONTINUED.len().max(":)\n\n".len() + notes.len()));
+
+        let mut chunks: Vec<String> = vec![];
+        let mut chunk = String::new();
+        for entry in entries {
+            if entry.len() > budget {
+                return None;
+            }
+            if !chunk.is_empty() && chunk.len() + ",\n\n".len() + entry.len() > budget {
+                chunks.push(std::mem::take(&mut chunk));
+            }
+            if !chunk.is_empty() {
+                chunk.push_str(",\n\n");
+            }
+            chunk.push_str(entry);
+        }
+        chunks.push(chunk);
+
+        if chunks.len() > max_chain_length {
+            return None;
+        }
+
+        let last = chunks.len() - 1;
+        Some(
+            chunks
+                .into_iter()
+                .enumerate()
+                .map(|(i, chunk)| {
+                    let header = if i == 0 { TOO_LONG_HEADER } else { "" };
+                    if i == last {
+                        format!("{header}{chunk}\n\n:)\n\n{notes}")
+                    } else {
+                        format!("{header}{chunk}{CHAIN_CONTINUED}")
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Writes the count with the unit, which gets an "s" unless there is exactly one.
+pub(crate) fn plural(count: impl std::fmt::Display, unit: &str) -> String {
+    let count = count.to_string();
+    if count == "1" {
+        format!("{count} {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     use num_bigint::ToBigInt;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_comment_new() {
+        let comment = RedditComment::new(
+            "This is a test comment with a factorial of 5! and 6!",
+            "123",
+        );
+        assert_eq!(comment.id, "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![
+                Factorial {
+                    number: 5,
+                    level: 1,
+                    factorial: 120.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 6,
+                    level: 1,
+                    factorial: 720.to_bigint().unwrap(),
+                },
+            ],
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_calculate_factorials_reuses_partial_products() {
+        // Without any cache, descending factorials are still only calculated once per level and residue
+        let mut calculated = vec![];
+        let tasks = [(1000, 1), (999, 1), (998, 1), (13, 2), (11, 2), (10, 2)];
+        let factorials = RedditComment::calculate_factorials(&tasks, |n, k| {
+            calculated.push((n, k));
+            math::factorial(n, k)
+        });
+        assert_eq!(calculated, vec![(998, 1), (10, 2), (11, 2)]);
+        for (n, k) in tasks {
+            assert_eq!(factorials[&(n, k as u64)], math::factorial(n, k as u64));
+        }
+    }
+
+    #[test]
+    fn test_comment_new_double_factorial() {
+        let comment = RedditComment::new("This is a test comment with an n-factorial 6!!", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 6,
+                level: 2,
+                factorial: 48.to_bigint().unwrap(),
+            }]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_comment_new_triple_factorial() {
+        let comment = RedditComment::new("This is a test comment with an n-factorial 6!!!", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 6,
+                level: 3,
+                factorial: 18.to_bigint().unwrap(),
+            }]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_comment_new_spoiler() {
+        let comment = RedditComment::new(">!This is a spoiler comment 5!<", "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_code() {
+        let comment = RedditComment::new(
+            "```\nif !done { x = 5!; }\n```\nIn `assert!(3! == 6)` that's 4!",
+            "123",
+        );
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 4,
+                level: 1,
+                factorial: 24.to_bigint().unwrap(),
+            }]
+        );
+
+        let comment = RedditComment::new("Unclosed ```\n5! and 6!", "123");
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_no_quotes() {
+        let body = "> Is 5! really 120?\n&gt; and 6!\n\nYes, and 7! is bigger [noquotes]\n>!8!<";
+        let comment = RedditComment::new(body, "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 7,
+                level: 1,
+                factorial: 5040.to_bigint().unwrap(),
+            }]
+        );
+
+        let comment = RedditComment::new("> Is 5! really 120?\n\nYes, and 7! is bigger", "123");
+        assert_eq!(comment.factorial_list.len(), 2);
+    }
+
+    #[test]
+    fn test_comment_new_spoiler_html_encoded() {
+        let comment = RedditComment::new("&gt;!This is a spoiler comment 5!&lt;", "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_exclamations_one() {
+        let comment = RedditComment::new("This is a test with exclamation mark stuff!!!1!", "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_exclamations_eleven() {
+        let comment = RedditComment::new("This is a test with exclamation mark stuff!!!11!", "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_decimals() {
+        let comment = RedditComment::new("This is a test comment with decimal number 0.5!", "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_comma_decimals() {
+        let comment = RedditComment::new("This is a test comment with decimal number 0,5!", "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_big_number_and_normal_number() {
+        let comment = RedditComment::new(
+            "This is a test comment with a factorial of 555555555555555555555555555555555! and 6!",
+            "123",
+        );
+        assert_eq!(comment.id, "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 6,
+                level: 1,
+                factorial: 720.to_bigint().unwrap()
+            }]
+        );
+        assert_eq!(
+            comment.status,
+            vec![Status::NumberTooBig, Status::FactorialsFound]
+        );
+    }
+
+    #[test]
+    fn test_comment_new_very_big_number() {
+        let very_big_number = "9".repeat(10_000) + "!";
+        let comment = RedditComment::new(&very_big_number, "123");
+        assert_eq!(comment.id, "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(
+            comment.status,
+            vec![Status::NumberTooBig, Status::NoFactorial]
+        );
+    }
+
+    #[test]
+    fn test_add_status() {
+        let mut comment = RedditComment::new(
+            "This is a test comment with a factorial of 5! and 6!",
+            "123",
+        );
+        comment.add_status(Status::NotReplied);
+        assert_eq!(
+            comment.status,
+            vec![Status::FactorialsFound, Status::NotReplied]
+        );
+    }
+
+    #[test]
+    fn test_reply_text_too_long() {
+        let comment = RedditComment::new(
+            "3500! 3501! 3502! 3503! 3504! 3505! 3506! 3507! 3508! 3509! 3510! 3511! 3512! 3513! 3514! 3515! 3516! 3517! 3518! 3519! 3520! 3521! 3522! 3523! 3524! 3525! 3526! 3527! 3528! 3529! 3530! 3531! 3532! 3533! 3534! 3535! 3536! 3537! 3538! 3539! 3540! 3541! 3542! 3543! 3544! 3545! 3546! 3547! 3548! 3549! 3550! 3551! 3552! 3553! 3554! 3555! 3556! 3557! 3558! 3559! 3560! 3561! 3562! 3563! 3564! 3565! 3566! 3567! 3568! 3569! 3570! 3571! 3572! 3573! 3574! 3575! 3576! 3577! 3578! 3579! 3580! 3581! 3582! 3583! 3584! 3585! 3586! 3587! 3588! 3589! 3590! 3591! 3592! 3593! 3594! 3595! 3596! 3597! 3598! 3599! 3600!",
+            "123",
+        );
+        let reply = comment.get_reply();
+        assert_eq!(
+            reply,
+            // over 13k characters
+            "Sorry, but the reply text for all those number would be _really_ long, so I'd rather not even try posting lmao\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+    }
+
+    #[test]
+    fn test_get_reply_for_multifactorial() {
+        let comment = RedditComment {
+            id: "123".to_string(),
+            subreddit: String::new(),
+            author: String::new(),
+            thread: String::new(),
+            parent: String::new(),
+            factorial_list: vec![Factorial {
+                number: 10,
+                level: 3,
+                factorial: 280.to_bigint().unwrap(),
+            }],
+            binomial_list: vec![],
+            approximate_digits_list: vec![],
+            approximate_digits_tower_list: vec![],
+            trailing_zeros_list: vec![],
+            prime_factorization_list: vec![],
+            modular_list: vec![],
+            gamma_list: vec![],
+            tetration_list: vec![],
+            ratio_list: vec![],
+            scientific_notation_list: vec![],
+            inverse_list: vec![],
+            status: vec![Status::FactorialsFound],
+            commands: Commands::NONE,
+            inline_commands: HashMap::new(),
+            max_jobs_per_comment: DEFAULT_MAX_JOBS_PER_COMMENT,
+            parsed_body: String::new(),
+            spans: vec![],
+            decimals: NUMBER_DECIMALS_SCIENTIFIC,
+            paste_link: None,
+            merged_ids: vec![],
+        };
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Triple-Factorial of 10 is 280 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_for_multiple() {
+        let comment = RedditComment {
+            id: "123".to_string(),
+            subreddit: String::new(),
+            author: String::new(),
+            thread: String::new(),
+            parent: String::new(),
+            factorial_list: vec![
+                Factorial {
+                    number: 5,
+                    level: 1,
+                    factorial: 120.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 6,
+                    level: 1,
+                    factorial: 720.to_bigint().unwrap(),
+                },
+            ],
+            binomial_list: vec![],
+            approximate_digits_list: vec![],
+            approximate_digits_tower_list: vec![],
+            trailing_zeros_list: vec![],
+            prime_factorization_list: vec![],
+            modular_list: vec![],
+            gamma_list: vec![],
+            tetration_list: vec![],
+            ratio_list: vec![],
+            scientific_notation_list: vec![],
+            inverse_list: vec![],
+            status: vec![Status::FactorialsFound],
+            commands: Commands::NONE,
+            inline_commands: HashMap::new(),
+            max_jobs_per_comment: DEFAULT_MAX_JOBS_PER_COMMENT,
+            parsed_body: String::new(),
+            spans: vec![],
+            decimals: NUMBER_DECIMALS_SCIENTIFIC,
+            paste_link: None,
+            merged_ids: vec![],
+        };
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Factorial of 5 is 120 \n\nFactorial of 6 is 720 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_with_multiple_numbers() {
+        let comment = RedditComment {
+            id: "123".to_string(),
+            subreddit: String::new(),
+            author: String::new(),
+            thread: String::new(),
+            parent: String::new(),
+            factorial_list: vec![
+                Factorial {
+                    number: 5,
+                    level: 2,
+                    factorial: 60.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 6,
+                    level: 1,
+                    factorial: 720.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 3249,
+                    level: 1,
+                    factorial: math::factorial(3249, 1),
+                },
+            ],
+            binomial_list: vec![],
+            approximate_digits_list: vec![],
+            approximate_digits_tower_list: vec![],
+            trailing_zeros_list: vec![],
+            prime_factorization_list: vec![],
+            modular_list: vec![],
+            gamma_list: vec![],
+            tetration_list: vec![],
+            ratio_list: vec![],
+            scientific_notation_list: vec![],
+            inverse_list: vec![],
+            status: vec![Status::FactorialsFound, Status::ReplyWouldBeTooLong],
+            commands: Commands::NONE,
+            inline_commands: HashMap::new(),
+            max_jobs_per_comment: DEFAULT_MAX_JOBS_PER_COMMENT,
+            parsed_body: String::new(),
+            spans: vec![],
+            decimals: NUMBER_DECIMALS_SCIENTIFIC,
+            paste_link: None,
+            merged_ids: vec![],
+        };
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "If I post the whole numbers, the comment would get too long, as reddit only allows up to 10k characters.\n\nIn scientific notation the results would look roughly like that:\n\nDouble-Factorial of 5 = 6.0e1,\n\nFactorial of 6 = 7.20e2,\n\nFactorial of 3249 = 6.4123376882765521838840963030568127691878727205333658692200854486404915724268122521695176119279253636e10000\n\n:)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_from_new_comment() {
+        let comment =
+            RedditComment::new("This is a test comment with a factorial of 4000!", "1234");
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the factorial of 4000 would be (roughly) 1.8288019515140650133147431755739190442173777107304392197064526954208959797973177364850370286870484107e12673 though :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_from_new_comment_for_multifactorial() {
+        let comment =
+            RedditComment::new("This is a test comment with a factorial of 9000!!!", "1234");
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the Triple-factorial of 9000 would be (roughly) 9.5883799146548267640341391648545903348878025438772769707015576436531779580675303393957674423348854753e10561 though :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_from_number_3250() {
+        let comment =
+            RedditComment::new("This is a test comment with a factorial of 3250!", "1234");
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the factorial of 3250 would be (roughly) 2.0840097486898794597623312984934641499860586341733439074965277708081597610387139819550932238765757432e10004 though :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_comment_new_binomial() {
+        let comment = RedditComment::new(
+            "There are C(10,3) ways, or 10 choose 3, or 10C3, but 52 Choose 5 hands",
+            "123",
+        );
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(
+            comment.binomial_list,
+            vec![
+                Binomial {
+                    n: 10,
+                    k: 3,
+                    coefficient: 120.to_bigint().unwrap(),
+                },
+                Binomial {
+                    n: 52,
+                    k: 5,
+                    coefficient: 2598960.to_bigint().unwrap(),
+                },
+            ]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_comment_new_binomial_false_positives() {
+        let comment = RedditComment::new(
+            "It was 20C outside, 10C3x is a part number and 3 choose 4 is pointless, 10 choose 3.5 too",
+            "123",
+        );
+        assert_eq!(comment.binomial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_binomial_too_big() {
+        let comment = RedditComment::new("What is 1000000 choose 3?", "123");
+        assert_eq!(comment.binomial_list, vec![]);
+        assert_eq!(
+            comment.status,
+            vec![Status::NumberTooBig, Status::NoFactorial]
+        );
+    }
+
+    #[test]
+    fn test_get_reply_for_binomial_and_factorial() {
+        let comment = RedditComment::new("5! and C(10,3)", "123");
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Factorial of 5 is 120 \n\nBinomial coefficient of 10 choose 3 is 120 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_for_binomial() {
+        let comment = RedditComment {
+            id: "123".to_string(),
+            subreddit: String::new(),
+            author: String::new(),
+            thread: String::new(),
+            parent: String::new(),
+            factorial_list: vec![],
+            binomial_list: vec![Binomial {
+                n: 100,
+                k: 50,
+                coefficient: math::binomial(100, 50),
+            }],
+            approximate_digits_list: vec![],
+            approximate_digits_tower_list: vec![],
+            trailing_zeros_list: vec![],
+            prime_factorization_list: vec![],
+            modular_list: vec![],
+            gamma_list: vec![],
+            tetration_list: vec![],
+            ratio_list: vec![],
+            scientific_notation_list: vec![],
+            inverse_list: vec![],
+            status: vec![Status::FactorialsFound, Status::ReplyWouldBeTooLong],
+            commands: Commands::NONE,
+            inline_commands: HashMap::new(),
+            max_jobs_per_comment: DEFAULT_MAX_JOBS_PER_COMMENT,
+            parsed_body: String::new(),
+            spans: vec![],
+            decimals: NUMBER_DECIMALS_SCIENTIFIC,
+            paste_link: None,
+            merged_ids: vec![],
+        };
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the binomial coefficient of 100 choose 50 would be (roughly) 1.00891344545564193334812497256e29 though :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_comment_new_false_positive_corpus() {
+        let false_positives = [
+            "Catch-22!",
+            "Man, COVID-19! What a time to be alive",
+            "I'm 5!!1 years old",
+            "We got 2!!!one",
+            "It's over 9000!!!11!",
+            "Wow!!1 that's crazy",
+            "Yahoo!! 3!!eleven",
+            "Sent from my iPhone_14!",
+        ];
+        for text in false_positives {
+            let comment = RedditComment::new(text, "123");
+            assert_eq!(comment.factorial_list, vec![], "{text}");
+            assert_eq!(comment.status, vec![Status::NoFactorial], "{text}");
+        }
+    }
+
+    #[test]
+    fn test_jobs_deadline() {
+        let mut status = vec![];
+        let mut jobs = Jobs {
+            remaining: 5,
+            deadline: Instant::now() + CALCULATION_TIME_LIMIT,
+            spans: vec![],
+            inline_commands: HashMap::new(),
+        };
+        assert!(jobs.take(0..2, &mut status));
+        jobs.deadline = Instant::now();
+        assert!(!jobs.take(3..5, &mut status));
+        assert!(!jobs.take(6..8, &mut status));
+        assert_eq!(status, vec![Status::TooExpensive]);
+        assert_eq!(jobs.spans, vec![0..2]);
+        assert_eq!(jobs.remaining, 4);
+
+        let mut comment = RedditComment::new("3!", "123");
+        comment.status.push(Status::TooExpensive);
+        assert_eq!(
+            comment.get_reply(),
+            "Factorial of 3 is 6 \n\nThose calculations took too long, so I stopped after the first ones.\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+    }
+
+    #[test]
+    fn test_max_jobs_per_comment() {
+        let comment = RedditComment::try_new_with_settings(
+            "3! 4! 5! and H(3)",
+            "123",
+            Strictness::default(),
+            Commands::NONE,
+            &[],
+            2,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(
+            comment.status,
+            vec![Status::TooManyCalculations, Status::FactorialsFound]
+        );
+        assert_eq!(
+            comment
+                .factorial_list
+                .iter()
+                .map(|factorial| factorial.number)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(
+            comment.get_reply(),
+            "Factorial of 3 is 6 \n\nFactorial of 4 is 24 \n\nThat's a lot of calculations, so I only did the first 2 of them.\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+
+        let comment = RedditComment::try_new_with_settings(
+            "3! 4! 5! and H(3)",
+            "123",
+            Strictness::default(),
+            Commands::NONE,
+            &[],
+            4,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(comment.factorial_list.len(), 4);
+    }
+
+    #[test]
+    fn test_inverse_queries() {
+        let comment = RedditComment::new(
+            "!inverse 3628800, what factorial is 100? And what termial is 55? !inverse termial 54",
+            "123",
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(
+            comment.get_reply(),
+            "54 is between the termials of 9 and 10 \n\n\
+            55 is the termial of 10 \n\n\
+            100 is between the factorials of 4 and 5 \n\n\
+            3628800 is the factorial of 10 \n\n\
+            \n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+
+        let comment = RedditComment::new("!inverse 0 and what factorial is 1.5?", "123");
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+
+        let number = math::factorial(100, 1);
+        let comment = RedditComment::new(&format!("!inverse {number}"), "123");
+        assert_eq!(
+            comment.format_inverse_query(&comment.inverse_list[0]),
+            format!(
+                "{} is the factorial of 100",
+                RedditComment::format_scientific(&number, NUMBER_DECIMALS_SCIENTIFIC)
+            )
+        );
+    }
+
+    #[test]
+    fn test_inline_commands() {
+        let comment = RedditComment::new(
+            "5! [digits] and 6!, but 4000! [short] and 10!! [identities, facts]",
+            "123",
+        );
+        assert_eq!(comment.commands, Commands::NONE);
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(
+            comment.get_reply(),
+            "Factorial of 5 has 3 digits \n\n\
+            Factorial of 6 is 720 \n\n\
+            Double-Factorial of 10 is 3840 ^((4 digits, digit sum 15)) \n\n\
+            _Using the identity 10!! = 2^5 × 5!_ \n\n\
+            Factorial of 4000 is roughly 1.8288019515140650133147431755739190442173777107304392197064526954208959797973177364850370286870484107e12673 \n\n\
+            \n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+
+        // Written on their own, commands still count for the whole comment
+        let comment = RedditComment::new("[short] 5! and 3000!", "123");
+        assert_eq!(comment.commands, Commands::SHORT);
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert!(comment
+            .get_reply()
+            .starts_with("Factorial of 5 is roughly 1.20e2 \n\n"));
+
+        let comment = RedditComment::new("5! [citation needed]", "123");
+        assert!(comment.inline_commands.is_empty());
+        assert!(comment
+            .get_reply()
+            .starts_with("Factorial of 5 is 120 \n\n"));
+    }
+
+    #[test]
+    fn test_digits_only() {
+        let comment =
+            RedditComment::new("!digits 25!, C(100,50), 52!/47!, 3^^3 and 1000000!", "123");
+        assert_eq!(comment.commands, Commands::DIGITS_ONLY);
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(
+            comment.get_reply(),
+            "Factorial of 25 has 26 digits \n\n\
+            Binomial coefficient of 100 choose 50 has 30 digits \n\n\
+            52!/47! has 9 digits \n\n\
+            3↑↑3 has 13 digits \n\n\
+            Factorial of 1000000 has approximately 5565709 digits \n\n\
+            \n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+
+        let comment = RedditComment::new("1000000!", "123");
+        assert_eq!(
+            comment.status,
+            vec![Status::NumberTooBig, Status::NoFactorial]
+        );
+    }
+
+    #[test]
+    fn test_precision() {
+        let comment = RedditComment::new("!precision 5 !short 30!", "123");
+        assert_eq!(comment.decimals, 5);
+        assert_eq!(
+            comment.get_reply(),
+            "Factorial of 30 is roughly 2.65253e32 \n\n\
+            \n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+        );
+
+        let comment = RedditComment::try_new_with_settings(
+            "!precision 5000 30!",
+            "123",
+            Strictness::default(),
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.decimals, DEFAULT_MAX_DECIMALS);
+
+        let comment = RedditComment::new("30!", "123");
+        assert_eq!(comment.decimals, NUMBER_DECIMALS_SCIENTIFIC);
+    }
+
+    #[test]
+    fn test_sources() {
+        let comment = RedditComment::new(
+            "First 23! then ((3!)!)!, C(5,2) and twenty factorial, but not Catch-22!",
+            "123",
+        );
+        assert_eq!(
+            comment.sources(),
+            vec!["23!", "((3!)!)!", "C(5,2)", "twenty factorial"]
+        );
+        assert_eq!(comment.spans[0], 6..9);
+    }
+
+    #[test]
+    fn test_try_new_pathological_comment() {
+        let body = format!("{}1", "(".repeat(5000));
+        let result = RedditComment::try_new_with_settings(
+            &body,
+            "123",
+            Strictness::default(),
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        );
+        assert!(matches!(result, Err(ParseError::TooComplex(_))));
+    }
+
+    #[test]
+    fn test_comment_new_non_ascii_digits() {
+        // Digits of other scripts aren't numbers the bot calculates with
+        let comment = RedditComment::new("𑶩# and ৫! and ٣!!", "123");
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    }
+
+    #[test]
+    fn test_comment_new_strictness() {
+        let comment = RedditComment::try_new_with_settings(
+            "Catch-22!",
+            "123",
+            Strictness::Lenient,
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+
+        let comment = RedditComment::try_new_with_settings(
+            "We won 3!",
+            "123",
+            Strictness::Normal,
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+
+        let comment = RedditComment::try_new_with_settings(
+            "We won 3!",
+            "123",
+            Strictness::Strict,
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+
+        let comment = RedditComment::try_new_with_settings(
+            "The factorial of 3 is 3!",
+            "123",
+            Strictness::Strict,
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+
+        let comment = RedditComment::try_new_with_settings(
+            "3! is six",
+            "123",
+            Strictness::Strict,
+            Commands::NONE,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_strictness_from_str() {
+        assert_eq!("lenient".parse(), Ok(Strictness::Lenient));
+        assert_eq!(" Normal".parse(), Ok(Strictness::Normal));
+        assert_eq!("STRICT".parse(), Ok(Strictness::Strict));
+        assert!("whatever".parse::<Strictness>().is_err());
+    }
+
+    #[test]
+    fn test_comment_new_primorial() {
+        let comment =
+            RedditComment::new("The primorial 10# and 13# but not issue #10 or 5#3", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![
+                Factorial {
+                    number: 10,
+                    level: PRIMORIAL_LEVEL,
+                    factorial: 210.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 13,
+                    level: PRIMORIAL_LEVEL,
+                    factorial: 30030.to_bigint().unwrap(),
+                },
+            ]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_get_reply_for_primorial() {
+        let comment = RedditComment::new("10#", "123");
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Primorial of 10 is 210 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_for_primorial() {
+        let comment = RedditComment::new("30000# and 5!", "123");
+        assert!(comment.status.contains(&Status::ReplyWouldBeTooLong));
+
+        let reply = comment.get_reply();
+        assert!(reply.starts_with("If I post the whole numbers, the comment would get too long, as reddit only allows up to 10k characters.\n\nIn scientific notation the results would look roughly like that:\n\nFactorial of 5 = 1.20e2,\n\nPrimorial of 30000 = "));
+    }
+
+    #[test]
+    fn test_comment_new_factorial_chain() {
+        let comment = RedditComment::new("What is ((3!)!!)! though?", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![
+                Factorial {
+                    number: 3,
+                    level: 1,
+                    factorial: 6.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 6,
+                    level: 2,
+                    factorial: 48.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 48,
+                    level: 1,
+                    factorial: math::factorial(48, 1),
+                },
+            ]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_comment_new_factorial_chain_too_big() {
+        let comment = RedditComment::new("((((20!)!)!)!)", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 20,
+                level: 1,
+                factorial: math::factorial(20, 1),
+            }]
+        );
+        // The steps after 20! are approximated instead of dropping the chain
+        let numbers: Vec<&str> = comment
+            .scientific_notation_list
+            .iter()
+            .map(|factorial| factorial.number.as_str())
+            .collect();
+        assert_eq!(numbers, vec!["((20!)!)!", "(20!)!", "20!"]);
+        let digits: Vec<(u64, f64)> = comment
+            .scientific_notation_list
+            .iter()
+            .map(|factorial| factorial.digits.unwrap())
+            .collect();
+        assert_eq!((digits[0].0, digits[1].0, digits[2].0), (3, 2, 1));
+        assert!((digits[2].1 - 19.64).abs() < 0.01);
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_comment_new_factorial_chain_counts_once() {
+        let comment = RedditComment::try_new_with_settings(
+            "((3!)!!)! and 5!",
+            "123",
+            Strictness::Normal,
+            Commands::NONE,
+            &[],
+            2,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+        assert_eq!(comment.factorial_list.len(), 4);
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_comment_new_factorial_chain_surplus_parentheses() {
+        let comment = RedditComment::new("(((3!)!)", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![
+                Factorial {
+                    number: 3,
+                    level: 1,
+                    factorial: 6.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 6,
+                    level: 1,
+                    factorial: 720.to_bigint().unwrap(),
+                },
+            ]
+        );
+    }
 
     #[test]
-    fn test_comment_new() {
+    fn test_comment_new_factorial_of_expression() {
         let comment = RedditComment::new(
-            "This is a test comment with a factorial of 5! and 6!",
+            "What about (5+3)! and (2 * 10)! and ((1+2)!)! or (2^3^2 / 128 - 1)! but not (2)!",
             "123",
         );
-        assert_eq!(comment.id, "123");
         assert_eq!(
             comment.factorial_list,
             vec![
                 Factorial {
-                    number: 5,
+                    number: 3,
                     level: 1,
-                    factorial: 120.to_bigint().unwrap(),
+                    factorial: 6.to_bigint().unwrap(),
                 },
                 Factorial {
                     number: 6,
                     level: 1,
                     factorial: 720.to_bigint().unwrap(),
                 },
-            ],
+                Factorial {
+                    number: 8,
+                    level: 1,
+                    factorial: 40320.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 20,
+                    level: 1,
+                    factorial: math::factorial(20, 1),
+                },
+            ]
         );
         assert_eq!(comment.status, vec![Status::FactorialsFound]);
     }
 
     #[test]
-    fn test_comment_new_double_factorial() {
-        let comment = RedditComment::new("This is a test comment with an n-factorial 6!!", "123");
+    fn test_comment_new_factorial_of_invalid_expression() {
+        let comment = RedditComment::new("(3-5)! or (7/2)! or (1/0)! or (2^100000 - 1)!", "123");
+        assert_eq!(comment.factorial_list, vec![]);
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+
+        let comment = RedditComment::new("(1000*1000)!", "123");
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(
+            RedditComment::format_scientific_notation_factorial(
+                &comment.scientific_notation_list[0]
+            ),
+            "Factorial of 1000*1000 has approximately 5565709 digits"
+        );
+    }
+
+    #[test]
+    fn test_comment_new_factorial_of_power() {
+        let comment = RedditComment::new(
+            "(2^10)! and (2^20)! and (2^64)! and (2^100000)! and (1^99999999999)!",
+            "123",
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
         assert_eq!(
             comment.factorial_list,
             vec![Factorial {
-                number: 6,
-                level: 2,
-                factorial: 48.to_bigint().unwrap(),
+                number: 1024,
+                level: 1,
+                factorial: math::factorial(1024, 1),
+            }]
+        );
+        assert_eq!(
+            comment
+                .scientific_notation_list
+                .iter()
+                .map(RedditComment::format_scientific_notation_factorial)
+                .collect::<Vec<_>>(),
+            vec![
+                "Factorial of 2^100000 has approximately 10^30107.48 digits",
+                "Factorial of 2^20 has approximately 5857670 digits",
+                "Factorial of 2^64 has approximately 10^20.54 digits",
+            ]
+        );
+
+        let comment = RedditComment::new("((2^64)!)!", "123");
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(
+            comment
+                .scientific_notation_list
+                .iter()
+                .map(RedditComment::format_scientific_notation_factorial)
+                .collect::<Vec<_>>(),
+            vec![
+                "Factorial of (2^64)! has approximately 10^10^20.54 digits",
+                "Factorial of 2^64 has approximately 10^20.54 digits",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comment_new_word_factorials() {
+        let comment = RedditComment::new(
+            "What is five factorial? Or Twenty-three! and one hundred and five!! and one!",
+            "123",
+        );
+        assert_eq!(
+            comment.factorial_list,
+            vec![
+                Factorial {
+                    number: 5,
+                    level: 1,
+                    factorial: 120.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 23,
+                    level: 1,
+                    factorial: math::factorial(23, 1),
+                },
+                Factorial {
+                    number: 105,
+                    level: 2,
+                    factorial: math::factorial(105, 2),
+                },
+            ]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+
+        let comment = RedditComment::new("one million factorial and four!!!one", "123");
+        assert_eq!(
+            comment.status,
+            vec![Status::NumberTooBig, Status::NoFactorial]
+        );
+
+        for excited in [
+            "I waited ten! minutes",
+            "one! two! three!",
+            "Two! That's it",
+            "THREE!!",
+        ] {
+            let comment = RedditComment::new(excited, "123");
+            assert_eq!(comment.status, vec![Status::NoFactorial], "{excited}");
+        }
+        let comment = RedditComment::new("ten factorial", "123");
+        assert_eq!(comment.factorial_list[0].number, 10);
+
+        // A long run of number words is no reason to miss the other factorials
+        let comment = RedditComment::new(&format!("{} and 5!", "one ".repeat(2000)), "123");
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(comment.factorial_list[0].number, 5);
+    }
+
+    #[test]
+    fn test_word_factorial_suffix() {
+        assert_eq!(
+            RedditComment::word_factorial_suffix(" Factorial?"),
+            Some((10, None))
+        );
+        assert_eq!(
+            RedditComment::word_factorial_suffix("!! wow"),
+            Some((2, Some(2)))
+        );
+        assert_eq!(RedditComment::word_factorial_suffix("!5"), None);
+        assert_eq!(RedditComment::word_factorial_suffix("!&lt;"), None);
+        assert_eq!(RedditComment::word_factorial_suffix(" factorials"), None);
+        assert_eq!(RedditComment::word_factorial_suffix("factorial"), None);
+        assert_eq!(RedditComment::word_factorial_suffix(" apples"), None);
+    }
+
+    #[test]
+    fn test_parse_number_words() {
+        assert_eq!(RedditComment::parse_number_words("zero"), Some(0));
+        assert_eq!(RedditComment::parse_number_words("seventeen"), Some(17));
+        assert_eq!(RedditComment::parse_number_words("ninety-nine"), Some(99));
+        assert_eq!(
+            RedditComment::parse_number_words("two thousand and twenty four"),
+            Some(2024)
+        );
+        assert_eq!(
+            RedditComment::parse_number_words("one hundred thousand and one"),
+            Some(100_001)
+        );
+        assert_eq!(RedditComment::parse_number_words("three twenty"), None);
+        assert_eq!(RedditComment::parse_number_words("twenty twenty"), None);
+        assert_eq!(RedditComment::parse_number_words("ten five"), None);
+        assert_eq!(RedditComment::parse_number_words("hundred"), None);
+        assert_eq!(
+            RedditComment::parse_number_words("one thousand one thousand"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_evaluate_expression() {
+        let evaluate = |expression| {
+            RedditComment::evaluate_expression(expression).map(|result| result.to_string())
+        };
+        assert_eq!(evaluate("5+3"), Some("8".to_string()));
+        assert_eq!(evaluate("2+3*4"), Some("14".to_string()));
+        assert_eq!(evaluate("10-2-3"), Some("5".to_string()));
+        assert_eq!(evaluate("24/4/2"), Some("3".to_string()));
+        assert_eq!(evaluate("2^3^2"), Some("512".to_string()));
+        assert_eq!(evaluate("2 ^ 10 - 1000"), Some("24".to_string()));
+        assert_eq!(evaluate("1-2"), None);
+        assert_eq!(evaluate("5/2"), None);
+        assert_eq!(evaluate("5/0"), None);
+        assert_eq!(evaluate("10^10000"), None);
+        assert_eq!(evaluate("1^99999999999"), Some("1".to_string()));
+        assert_eq!(evaluate("0^0"), Some("1".to_string()));
+    }
+
+    #[test]
+    fn test_comment_new_hyper_and_superfactorial() {
+        let comment = RedditComment::new("What about H(5) and sf(4) or even H(1000)?", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![
+                Factorial {
+                    number: 4,
+                    level: SUPERFACTORIAL_LEVEL,
+                    factorial: 288.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 5,
+                    level: HYPERFACTORIAL_LEVEL,
+                    factorial: 86400000.to_bigint().unwrap(),
+                },
+            ]
+        );
+        assert_eq!(
+            comment.approximate_digits_list,
+            vec![ApproximateDigits {
+                number: 1000,
+                level: HYPERFACTORIAL_LEVEL,
+                digits: math::hyperfactorial_digits(1000),
+            }]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_get_reply_for_hyper_and_superfactorial() {
+        let comment = RedditComment::new("H(3) sf(3) sf(1000)", "123");
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Superfactorial of 3 is 12 \n\nHyperfactorial of 3 is 108 \n\nSuperfactorial of 1000 has approximately 1177246 digits \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_for_hyperfactorial() {
+        let comment = RedditComment::new("H(200)", "123");
+        assert!(comment.status.contains(&Status::ReplyWouldBeTooLong));
+
+        let reply = comment.get_reply();
+        assert!(reply.starts_with("If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the hyperfactorial of 200 would be (roughly) "));
+    }
+
+    #[test]
+    fn test_comment_new_left_factorial() {
+        let comment = RedditComment::new("!5 is the left factorial", "123");
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
+
+        let comment = RedditComment::new("[left] !5 and !20000 but not stuff!!!1", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 5,
+                level: LEFT_FACTORIAL_LEVEL,
+                factorial: 34.to_bigint().unwrap(),
+            }]
+        );
+        assert_eq!(
+            comment.approximate_digits_list,
+            vec![ApproximateDigits {
+                number: 20000,
+                level: LEFT_FACTORIAL_LEVEL,
+                digits: math::left_factorial_digits(20000),
+            }]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+    }
+
+    #[test]
+    fn test_get_reply_for_left_factorial_from_subreddit_commands() {
+        let comment = RedditComment::try_new_with_settings(
+            "What is !4?",
+            "123",
+            Strictness::Normal,
+            Commands::LEFT_FACTORIAL,
+            &[],
+            DEFAULT_MAX_JOBS_PER_COMMENT,
+            DEFAULT_MAX_DECIMALS,
+        )
+        .unwrap();
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Left factorial of 4 is 10 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_comment_new_alternating_factorial() {
+        let comment = RedditComment::new("af(5) and af(50000) but not leaf(5)", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 5,
+                level: ALTERNATING_FACTORIAL_LEVEL,
+                factorial: 101.to_bigint().unwrap(),
+            }]
+        );
+        assert_eq!(
+            comment.approximate_digits_list,
+            vec![ApproximateDigits {
+                number: 50000,
+                level: ALTERNATING_FACTORIAL_LEVEL,
+                digits: math::alternating_factorial_digits(50000),
+            }]
+        );
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Alternating factorial of 5 is 101 \n\nAlternating factorial of 50000 has approximately 213237 digits \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_with_exact_limit_note() {
+        let comment = RedditComment::new("[limits] 4000! and 100#", "1234");
+        let reply = comment.get_reply();
+        assert!(reply.ends_with(":)\n\nI can print up to 3249! exactly, just ask for it :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*"));
+
+        let comment = RedditComment::new("!limits H(1000) and 7000!! and 5!", "1234");
+        let reply = comment.get_reply();
+        assert!(reply.contains("I can print up to H(104) and 5982!! exactly, just ask for it :)"));
+
+        let comment = RedditComment::new("[limits] 5!", "1234");
+        let reply = comment.get_reply();
+        assert!(!reply.contains("I can print up to"));
+
+        let comment = RedditComment::new("4000!", "1234");
+        let reply = comment.get_reply();
+        assert!(!reply.contains("I can print up to"));
+    }
+
+    #[test]
+    fn test_comment_new_exponential_factorial() {
+        let comment = RedditComment::new("ef(4) ef(7) ef(1000)", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 4,
+                level: EXPONENTIAL_FACTORIAL_LEVEL,
+                factorial: 262144.to_bigint().unwrap(),
+            }]
+        );
+        assert_eq!(comment.approximate_digits_tower_list.len(), 1);
+        assert_eq!(comment.approximate_digits_tower_list[0].depth, 2);
+        assert_eq!(
+            comment.status,
+            vec![Status::NumberTooBig, Status::FactorialsFound]
+        );
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Exponential factorial of 4 is 262144 \n\nExponential factorial of 7 has approximately 10^10^183230.68 digits \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_get_reply_too_long_for_exponential_factorial() {
+        let comment = RedditComment::new("ef(5) and ef(6)", "123");
+        assert!(comment.status.contains(&Status::ReplyWouldBeTooLong));
+
+        let reply = comment.get_reply();
+        assert!(reply.starts_with("If I post the whole numbers, the comment would get too long, as reddit only allows up to 10k characters.\n\nIn scientific notation the results would look roughly like that:\n\nExponential factorial of 5 = 6.2"));
+        assert!(reply.contains(
+            ",\n\nExponential factorial of 6 has approximately 10^183230.68 digits\n\n:)"
+        ));
+    }
+
+    #[test]
+    fn test_get_reply_chain() {
+        let body = (3501..=3600)
+            .map(|n| format!("{n}!"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let comment = RedditComment::new(&body, "123");
+
+        let chain = comment.get_reply_chain_with_footer(FOOTER_TEXT, 1);
+        assert_eq!(chain, vec![comment.get_reply()]);
+        assert!(chain[0].starts_with("Sorry"));
+
+        let chain = comment.get_reply_chain_with_footer(FOOTER_TEXT, 3);
+        assert_eq!(chain.len(), 2);
+        assert!(chain[0].starts_with("If I post the whole numbers"));
+        assert!(chain[0].ends_with(&format!("{CHAIN_CONTINUED}{FOOTER_TEXT}")));
+        assert!(chain[1].starts_with("Factorial of "));
+        assert!(chain[1].ends_with(&format!(
+            "Factorial of 3600 = {}\n\n:)\n\n{FOOTER_TEXT}",
+            RedditComment::format_scientific(&math::factorial(3600, 1), NUMBER_DECIMALS_SCIENTIFIC)
+        )));
+        for reply in chain.iter() {
+            assert!(reply.len() <= 10_000);
+        }
+        // Nothing gets lost
+        for n in 3501..=3600 {
+            assert!(chain
+                .iter()
+                .any(|reply| reply.contains(&format!("Factorial of {n} = "))));
+        }
+    }
+
+    #[test]
+    fn test_get_reply_chain_full() {
+        let comment = RedditComment::new("!full 2000! and 5000!", "123");
+        assert!(comment.status.contains(&Status::ReplyWouldBeTooLong));
+
+        let chain = comment.get_reply_chain_with_footer(FOOTER_TEXT, 4);
+        assert_eq!(chain.len(), 3);
+        for reply in chain.iter().take(2) {
+            assert!(reply.ends_with(&format!("{FULL_CHAIN_CONTINUED}{FOOTER_TEXT}")));
+        }
+        for reply in chain.iter() {
+            assert!(reply.len() <= 10_000);
+        }
+        // The first result fits into the first comment, the second one is cut in the middle
+        assert!(chain[0].starts_with("Factorial of 2000 is "));
+        assert!(chain[1].starts_with("Factorial of 5000 is "));
+        let digits: String = chain[1..]
+            .iter()
+            .map(|reply| {
+                reply
+                    .trim_start_matches("Factorial of 5000 is ")
+                    .split(|c: char| !c.is_ascii_digit())
+                    .next()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(digits, math::factorial(5000, 1).to_string());
+
+        // Falls back to scientific notation if the chain would get too long
+        let chain = comment.get_reply_chain_with_footer(FOOTER_TEXT, 2);
+        assert_eq!(chain.len(), 1);
+        assert!(chain[0].starts_with("If I post the whole numbers"));
+
+        let comment = RedditComment::new("2000! and 5000!", "123");
+        let chain = comment.get_reply_chain_with_footer(FOOTER_TEXT, 4);
+        assert!(chain[0].starts_with("If I post the whole numbers"));
+    }
+
+    #[test]
+    fn test_paste_link() {
+        let mut comment = RedditComment::new("2000! and 5000!", "123");
+        let text = comment.get_paste_text(1).unwrap();
+        assert!(text.starts_with("Factorial of 2000 is "));
+        assert!(text.contains(&math::factorial(5000, 1).to_string()));
+        assert_eq!(
+            RedditComment::new("!full 2000! and 5000!", "123").get_paste_text(4),
+            None
+        );
+        assert_eq!(RedditComment::new("20!", "123").get_paste_text(1), None);
+
+        comment.paste_link = Some("https://paste.rs/abc".to_string());
+        assert!(comment.get_reply().ends_with(&format!(
+            "You can find the whole numbers [here](https://paste.rs/abc).\n\n{FOOTER_TEXT}"
+        )));
+    }
+
+    #[test]
+    fn test_get_reply_chain_short_reply() {
+        let comment = RedditComment::new("5! and 3250!", "123");
+        assert_eq!(
+            comment.get_reply_chain_with_footer(FOOTER_TEXT, 5),
+            vec![comment.get_reply()]
+        );
+    }
+
+    #[test]
+    fn test_comment_new_trailing_zeros() {
+        let comment = RedditComment::new("25! and 100000000000000000000!", "123");
+        assert!(comment.trailing_zeros_list.is_empty());
+        assert!(comment.status.contains(&Status::NumberTooBig));
+
+        let comment = RedditComment::new("[zeros] 25! 5!! and 100000000000000000000!", "123");
+        assert_eq!(
+            comment.trailing_zeros_list,
+            vec![
+                TrailingZeros {
+                    number: 25.to_bigint().unwrap(),
+                    zeros: 6.to_bigint().unwrap(),
+                },
+                TrailingZeros {
+                    number: "100000000000000000000".parse().unwrap(),
+                    zeros: "24999999999999999996".parse().unwrap(),
+                },
+            ]
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Double-Factorial of 5 is 15 \n\nFactorial of 25 is 15511210043330985984000000 \n\nFactorial of 25 has 6 trailing zeros \n\nFactorial of 100000000000000000000 has 24999999999999999996 trailing zeros \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    }
+
+    #[test]
+    fn test_comment_new_prime_factors() {
+        let comment = RedditComment::new("10! and 1001! and 4!!", "123");
+        assert!(comment.prime_factorization_list.is_empty());
+
+        let comment = RedditComment::new("10! and 1001! and 4!! !factors", "123");
+        assert_eq!(
+            comment.prime_factorization_list,
+            vec![PrimeFactorization {
+                number: 10,
+                factors: vec![(2, 8), (3, 4), (5, 2), (7, 1)],
             }]
         );
+        assert!(comment
+            .get_reply()
+            .contains("Prime factorization of 10! is 2^8 × 3^4 × 5^2 × 7 \n\n"));
+    }
+
+    #[test]
+    fn test_prime_factorization_of_limit_fits_reply() {
+        let comment = RedditComment::new(&format!("{UPPER_PRIME_FACTORS_LIMIT}! [factors]"), "123");
+        assert_eq!(comment.prime_factorization_list.len(), 1);
+        assert!(
+            RedditComment::format_prime_factorization(&comment.prime_factorization_list[0]).len()
+                < MAX_COMMENT_LENGTH as usize / 2
+        );
+    }
+
+    #[test]
+    fn test_comment_new_skip_units() {
+        let units: Vec<String> = DEFAULT_UNIT_SKIP_LIST
+            .iter()
+            .map(|unit| unit.to_string())
+            .collect();
+        for text in [
+            "Only $5! today",
+            "Only € 5! today",
+            "It weighs 5!kg",
+            "Shipping takes 5!-7! business days",
+            "Shipping takes 5! - 7! Business Days",
+            "That's 3! %",
+        ] {
+            let comment = RedditComment::try_new_with_settings(
+                text,
+                "123",
+                Strictness::Lenient,
+                Commands::SKIP_UNITS,
+                &units,
+                DEFAULT_MAX_JOBS_PER_COMMENT,
+                DEFAULT_MAX_DECIMALS,
+            )
+            .unwrap();
+            assert_eq!(comment.status, vec![Status::NoFactorial], "{text}");
+
+            let comment = RedditComment::try_new_with_settings(
+                text,
+                "123",
+                Strictness::Lenient,
+                Commands::NONE,
+                &units,
+                DEFAULT_MAX_JOBS_PER_COMMENT,
+                DEFAULT_MAX_DECIMALS,
+            )
+            .unwrap();
+            assert_eq!(comment.status, vec![Status::FactorialsFound], "{text}");
+        }
+
+        for text in [
+            "5! is a lot",
+            "What is 5! ?",
+            "It's 5! kgs",
+            "5!, 6! and 7! weeks",
+        ] {
+            let comment = RedditComment::try_new_with_settings(
+                text,
+                "123",
+                Strictness::Normal,
+                Commands::SKIP_UNITS,
+                &units,
+                DEFAULT_MAX_JOBS_PER_COMMENT,
+                DEFAULT_MAX_DECIMALS,
+            )
+            .unwrap();
+            assert!(!comment.factorial_list.is_empty(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_comment_new_modular() {
+        let comment = RedditComment::new(
+            "100000! mod 1000000007 and 10!! % 7 and 100000000000000000000! mod 97",
+            "123",
+        );
+        assert!(comment.factorial_list.is_empty());
+        assert_eq!(
+            comment.modular_list,
+            vec![
+                Modular {
+                    number: 10.to_bigint().unwrap(),
+                    level: 2,
+                    modulus: 7,
+                    remainder: 4,
+                },
+                Modular {
+                    number: 100000.to_bigint().unwrap(),
+                    level: 1,
+                    modulus: 1000000007,
+                    remainder: 457992974,
+                },
+                Modular {
+                    number: "100000000000000000000".parse().unwrap(),
+                    level: 1,
+                    modulus: 97,
+                    remainder: 0,
+                },
+            ]
+        );
         assert_eq!(comment.status, vec![Status::FactorialsFound]);
+
+        let reply = comment.get_reply();
+        assert_eq!(reply, "Double-Factorial of 10 mod 7 is 4 \n\nFactorial of 100000 mod 1000000007 is 457992974 \n\nFactorial of 100000000000000000000 mod 97 is 0 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
     }
 
     #[test]
-    fn test_comment_new_triple_factorial() {
-        let comment = RedditComment::new("This is a test comment with an n-factorial 6!!!", "123");
+    fn test_comment_new_modular_too_big() {
+        let comment = RedditComment::new("100000000000! mod 1000000000007", "123");
+        assert!(comment.modular_list.is_empty());
         assert_eq!(
-            comment.factorial_list,
-            vec![Factorial {
-                number: 6,
-                level: 3,
-                factorial: 18.to_bigint().unwrap(),
-            }]
+            comment.status,
+            vec![Status::NumberTooBig, Status::NoFactorial]
         );
-        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+
+        let comment = RedditComment::new("5! mod 0", "123");
+        assert_eq!(comment.status, vec![Status::NoFactorial]);
     }
 
     #[test]
-    fn test_comment_new_spoiler() {
-        let comment = RedditComment::new(">!This is a spoiler comment 5!<", "123");
-        assert_eq!(comment.factorial_list, vec![]);
-        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    fn test_get_reply_digit_facts() {
+        let comment = RedditComment::new("10! and 5 choose 2 [facts]", "123");
+        assert_eq!(comment.get_reply(), "Factorial of 10 is 3628800 ^((7 digits, digit sum 27)) \n\nBinomial coefficient of 5 choose 2 is 10 ^((2 digits, digit sum 1)) \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+
+        let comment = RedditComment::new("10!", "123");
+        assert_eq!(comment.get_reply(), "Factorial of 10 is 3628800 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
     }
 
     #[test]
-    fn test_comment_new_spoiler_html_encoded() {
-        let comment = RedditComment::new("&gt;!This is a spoiler comment 5!&lt;", "123");
-        assert_eq!(comment.factorial_list, vec![]);
-        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    fn test_get_reply_double_factorial_identities() {
+        let comment = RedditComment::new("[identities] 10!! and 9!! and 4!", "123");
+        assert_eq!(comment.get_reply(), "Factorial of 4 is 24 \n\nDouble-Factorial of 9 is 945 \n\n_Using the identity 9!! = 9! / (2^4 × 4!)_ \n\nDouble-Factorial of 10 is 3840 \n\n_Using the identity 10!! = 2^5 × 5!_ \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+
+        let comment = RedditComment::new("10!!", "123");
+        assert!(!comment.get_reply().contains("identity"));
     }
 
     #[test]
-    fn test_comment_new_exclamations_one() {
-        let comment = RedditComment::new("This is a test with exclamation mark stuff!!!1!", "123");
-        assert_eq!(comment.factorial_list, vec![]);
-        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    fn test_get_reply_approximation() {
+        let comment = RedditComment::new("!approx 10! and 3!!", "123");
+        assert_eq!(comment.get_reply(), "Double-Factorial of 3 is 3 \n\nFactorial of 10 is 3628800 \n\n_Stirling's approximation gives roughly 3.5987e6, 0.83% less_ \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+
+        let comment = RedditComment::new("10!", "123");
+        assert!(!comment.get_reply().contains("Stirling"));
     }
 
     #[test]
-    fn test_comment_new_exclamations_eleven() {
-        let comment = RedditComment::new("This is a test with exclamation mark stuff!!!11!", "123");
-        assert_eq!(comment.factorial_list, vec![]);
-        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    fn test_get_reply_factorion_note() {
+        let comment = RedditComment::new("145!!!!! and 2!", "123");
+        assert_eq!(comment.get_reply(), "Factorial of 2 is 2 \n\nQuintuple-Factorial of 145 is 1646906508829389131542968750000000000000000000000000 \n\n_Fun fact: 145 is a factorion, as 1! + 4! + 5! = 145_ \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+
+        let comment = RedditComment::new("!nonote 145!", "123");
+        assert!(!comment.get_reply().contains("factorion"));
     }
 
     #[test]
-    fn test_comment_new_decimals() {
-        let comment = RedditComment::new("This is a test comment with decimal number 0.5!", "123");
-        assert_eq!(comment.factorial_list, vec![]);
-        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    fn test_get_reply_fun_facts() {
+        let comment = RedditComment::new("!funfacts 1000! and 5!", "123");
+        assert!(comment.get_reply().ends_with(&format!(
+            "_Fun fact: The biggest result is bigger than the number of atoms in the observable universe \
+            and would take about 14 minutes to read aloud._\n\n{FOOTER_TEXT}"
+        )));
+
+        let comment = RedditComment::new("!funfacts 3000! [short]", "123");
+        assert!(comment.get_reply().contains("would fill about 3 pages"));
+
+        let comment = RedditComment::new("!funfacts 5!", "123");
+        assert!(!comment.get_reply().contains("Fun fact"));
+        let comment = RedditComment::new("1000!", "123");
+        assert!(!comment.get_reply().contains("Fun fact"));
     }
 
     #[test]
-    fn test_comment_new_comma_decimals() {
-        let comment = RedditComment::new("This is a test comment with decimal number 0,5!", "123");
-        assert_eq!(comment.factorial_list, vec![]);
-        assert_eq!(comment.status, vec![Status::NoFactorial]);
+    fn test_plural() {
+        assert_eq!(plural(1, "digit"), "1 digit");
+        assert_eq!(plural(0, "digit"), "0 digits");
+        assert_eq!(
+            plural(BigInt::from(21), "trailing zero"),
+            "21 trailing zeros"
+        );
+
+        let comment = RedditComment::new("[zeros] [facts] 5! and 3! [digits]", "123");
+        assert_eq!(comment.get_reply(), "Factorial of 3 has 1 digit \n\nFactorial of 5 is 120 ^((3 digits, digit sum 3)) \n\nFactorial of 3 has 0 trailing zeros \n\nFactorial of 5 has 1 trailing zero \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
     }
 
     #[test]
-    fn test_comment_new_big_number_and_normal_number() {
-        let comment = RedditComment::new(
-            "This is a test comment with a factorial of 555555555555555555555555555555555! and 6!",
-            "123",
+    fn test_comment_new_gamma_factorial() {
+        let comment = RedditComment::new("What is (-2.5)! or (-0.5)! or (-3.0)!?", "123");
+        assert_eq!(comment.gamma_list.len(), 2);
+        assert_eq!(comment.gamma_list[0].number, -2.5);
+        assert_eq!(comment.gamma_list[1].number, -0.5);
+        assert_eq!(
+            comment.status,
+            vec![
+                Status::DecimalFactorial,
+                Status::DecimalFactorial,
+                Status::FactorialsFound
+            ]
         );
-        assert_eq!(comment.id, "123");
+
+        let reply = comment.get_reply();
+        assert!(reply.starts_with("Factorial of -2.5 is approximately 2.3632718012e0 "));
+        assert!(reply.contains("Factorial of -0.5 is approximately 1.7724538509e0 "));
+
+        // Close to the limit, the results are still short
+        let comment = RedditComment::new("(-169.5)! and (169.5)!", "123");
+        let reply = comment.get_reply();
+        assert!(reply.contains("Factorial of -169.5 is approximately -9.5737343988e-304 "));
+        assert!(reply.contains("Factorial of 169.5 is approximately 5.5620924146e305 "));
+
+        let comment = RedditComment::new("(-200.5)!", "123");
+        assert!(comment.status.contains(&Status::NumberTooBig));
+        assert!(comment.status.contains(&Status::NoFactorial));
+    }
+
+    #[test]
+    fn test_comment_new_unicode_superscripts_and_fractions() {
+        let comment = RedditComment::new("What about 10⁶! or 2³! and ½! or 2¾!", "123");
         assert_eq!(
             comment.factorial_list,
             vec![Factorial {
-                number: 6,
+                number: 8,
                 level: 1,
-                factorial: 720.to_bigint().unwrap()
+                factorial: 40320.to_bigint().unwrap(),
             }]
         );
         assert_eq!(
-            comment.status,
-            vec![Status::NumberTooBig, Status::FactorialsFound]
+            comment
+                .gamma_list
+                .iter()
+                .map(|gamma| gamma.number)
+                .collect::<Vec<_>>(),
+            vec![0.5, 2.75]
         );
+
+        let reply = comment.get_reply();
+        assert!(reply.contains("Factorial of 0.5 is approximately 8.8622692545e-1 "));
+        assert!(reply.contains("Factorial of 10^6 has approximately 5565709 digits"));
     }
 
     #[test]
-    fn test_comment_new_very_big_number() {
-        let very_big_number = "9".repeat(10_000) + "!";
-        let comment = RedditComment::new(&very_big_number, "123");
-        assert_eq!(comment.id, "123");
-        assert_eq!(comment.factorial_list, vec![]);
+    fn test_comment_new_latex() {
+        let comment = RedditComment::new(
+            r"Is $\binom{5}{2}$ less than $\frac{8}{2}!$ or $$\left(2 \cdot 3\right)!$$ or {7}!? And $\frac{1}{2}!$?",
+            "123",
+        );
         assert_eq!(
-            comment.status,
-            vec![Status::NumberTooBig, Status::NoFactorial]
+            comment.factorial_list,
+            vec![
+                Factorial {
+                    number: 4,
+                    level: 1,
+                    factorial: 24.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 6,
+                    level: 1,
+                    factorial: 720.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 7,
+                    level: 1,
+                    factorial: 5040.to_bigint().unwrap(),
+                },
+            ]
         );
+        assert_eq!(comment.binomial_list.len(), 1);
+        assert_eq!(comment.gamma_list.len(), 1);
+        assert_eq!(comment.gamma_list[0].number, 0.5);
     }
 
     #[test]
-    fn test_add_status() {
-        let mut comment = RedditComment::new(
-            "This is a test comment with a factorial of 5! and 6!",
-            "123",
+    fn test_normalize_latex() {
+        assert_eq!(
+            RedditComment::normalize_latex(r"$\\binom{10}{3}$ costs $5").unwrap(),
+            " C(10,3)  costs $5"
         );
-        comment.add_status(Status::NotReplied);
         assert_eq!(
-            comment.status,
-            vec![Status::FactorialsFound, Status::NotReplied]
+            RedditComment::normalize_latex(r"\frac{1}{0}!").unwrap(),
+            r"\frac{1}{0}!"
         );
     }
 
     #[test]
-    fn test_reply_text_too_long() {
-        let comment = RedditComment::new(
-            "3500! 3501! 3502! 3503! 3504! 3505! 3506! 3507! 3508! 3509! 3510! 3511! 3512! 3513! 3514! 3515! 3516! 3517! 3518! 3519! 3520! 3521! 3522! 3523! 3524! 3525! 3526! 3527! 3528! 3529! 3530! 3531! 3532! 3533! 3534! 3535! 3536! 3537! 3538! 3539! 3540! 3541! 3542! 3543! 3544! 3545! 3546! 3547! 3548! 3549! 3550! 3551! 3552! 3553! 3554! 3555! 3556! 3557! 3558! 3559! 3560! 3561! 3562! 3563! 3564! 3565! 3566! 3567! 3568! 3569! 3570! 3571! 3572! 3573! 3574! 3575! 3576! 3577! 3578! 3579! 3580! 3581! 3582! 3583! 3584! 3585! 3586! 3587! 3588! 3589! 3590! 3591! 3592! 3593! 3594! 3595! 3596! 3597! 3598! 3599! 3600!",
-            "123",
+    fn test_normalize_unicode() {
+        assert_eq!(
+            RedditComment::normalize_unicode("10⁶! and 10⁶").unwrap(),
+            "(10^6)! and 10⁶"
         );
-        let reply = comment.get_reply();
         assert_eq!(
-            reply,
-            // over 13k characters
-            "Sorry, but the reply text for all those number would be _really_ long, so I'd rather not even try posting lmao\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*"
+            RedditComment::normalize_unicode("⅓! and 1½!").unwrap(),
+            "(0.3333333333333333)! and (1.5)!"
         );
     }
 
     #[test]
-    fn test_get_reply_for_multifactorial() {
-        let comment = RedditComment {
-            id: "123".to_string(),
-            factorial_list: vec![Factorial {
-                number: 10,
-                level: 3,
-                factorial: 280.to_bigint().unwrap(),
-            }],
-            status: vec![Status::FactorialsFound],
-        };
+    fn test_comment_new_tetration() {
+        let comment =
+            RedditComment::new("2^^4 and 3↑↑3 and 2^^5 and 2↑↑6 and 10^^3 and 1^^5", "123");
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(comment.tetration_list.len(), 5);
 
         let reply = comment.get_reply();
-        assert_eq!(reply, "Triple-Factorial of 10 is 280 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+        assert_eq!(reply, "2↑↑4 is 65536 \n\n2↑↑5 has approximately 19729 digits \n\n2↑↑6 has approximately 10^19727.78 digits \n\n3↑↑3 is 7625597484987 \n\n10↑↑3 has approximately 10000000001 digits \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
     }
 
     #[test]
-    fn test_get_reply_for_multiple() {
-        let comment = RedditComment {
-            id: "123".to_string(),
-            factorial_list: vec![
+    fn test_format_tower_beyond_whole_numbers() {
+        assert_eq!(RedditComment::format_tower(0, 1e14), "100000000000001");
+        assert_eq!(RedditComment::format_tower(0, 1e20), "10^20.00");
+        assert!(RedditComment::new("4^^4", "123")
+            .get_reply()
+            .starts_with("4↑↑4 has approximately 10^153.9"));
+    }
+
+    #[test]
+    fn test_comment_new_tetration_too_high() {
+        let comment = RedditComment::new("2^^1000", "123");
+        assert_eq!(
+            comment.status,
+            vec![Status::NumberTooBig, Status::NoFactorial]
+        );
+    }
+
+    #[test]
+    fn test_comment_new_factorial_ratio() {
+        let comment = RedditComment::new(
+            "How many hands? 52!/47! or 47! / 52! but 10000000000! / 9999999999! and 5!!/3! and 6!",
+            "123",
+        );
+        assert_eq!(
+            comment.factorial_list,
+            vec![
                 Factorial {
-                    number: 5,
+                    number: 3,
                     level: 1,
-                    factorial: 120.to_bigint().unwrap(),
+                    factorial: 6.to_bigint().unwrap(),
+                },
+                Factorial {
+                    number: 5,
+                    level: 2,
+                    factorial: 15.to_bigint().unwrap(),
                 },
                 Factorial {
                     number: 6,
                     level: 1,
                     factorial: 720.to_bigint().unwrap(),
                 },
-            ],
-            status: vec![Status::FactorialsFound],
-        };
+            ]
+        );
+        assert_eq!(comment.ratio_list.len(), 3);
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
 
         let reply = comment.get_reply();
-        assert_eq!(reply, "Factorial of 5 is 120 \n\nFactorial of 6 is 720 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+        assert_eq!(reply, "Factorial of 3 is 6 \n\nDouble-Factorial of 5 is 15 \n\nFactorial of 6 is 720 \n\n47!/52! is 1/311875200 \n\n52!/47! is 311875200 \n\n10000000000!/9999999999! is 10000000000 \n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
     }
 
     #[test]
-    fn test_get_reply_too_long_with_multiple_numbers() {
-        let comment = RedditComment {
-            id: "123".to_string(),
-            factorial_list: vec![
-                Factorial {
-                    number: 5,
-                    level: 2,
-                    factorial: 60.to_bigint().unwrap(),
-                },
+    fn test_get_reply_too_long_factorial_ratio() {
+        let comment = RedditComment::new("100000!/2!", "123");
+        assert!(!comment.status.contains(&Status::ReplyWouldBeTooLong));
+
+        let reply = comment.get_reply();
+        assert!(reply.starts_with("If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the ratio 100000!/2! would be (roughly) 1.41"));
+    }
+
+    #[test]
+    fn test_comment_new_scientific_notation() {
+        let comment = RedditComment::new(
+            "1e1! and 1.2e2! and 2.5e0! and 1e6! and 1e100! and 1e999999999999!",
+            "123",
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(
+            comment.factorial_list,
+            vec![
                 Factorial {
-                    number: 6,
+                    number: 10,
                     level: 1,
-                    factorial: 720.to_bigint().unwrap(),
+                    factorial: 3628800.to_bigint().unwrap(),
                 },
                 Factorial {
-                    number: 3249,
+                    number: 120,
                     level: 1,
-                    factorial: math::factorial(3249, 1),
+                    factorial: math::factorial(120, 1),
                 },
-            ],
-            status: vec![Status::FactorialsFound, Status::ReplyWouldBeTooLong],
-        };
+            ]
+        );
+        assert_eq!(
+            comment
+                .scientific_notation_list
+                .iter()
+                .map(RedditComment::format_scientific_notation_factorial)
+                .collect::<Vec<_>>(),
+            vec![
+                "Factorial of 1e100 has approximately 10^102.00 digits",
+                "Factorial of 1e6 has approximately 5565709 digits",
+                "Factorial of 1e999999999999 has approximately 10^1000000000011.00 digits",
+            ]
+        );
+    }
 
+    #[test]
+    fn test_comment_new_scientific_notation_beyond_integers() {
+        let exponent_beyond_u64 = format!("1e{}0!", u64::MAX);
+        let exponent_beyond_f64 = format!("1e{}!", "9".repeat(400));
+        let comment = RedditComment::new(
+            &format!("{exponent_beyond_u64} and {exponent_beyond_f64}"),
+            "123",
+        );
+        assert_eq!(comment.status, vec![Status::FactorialsFound]);
+        assert_eq!(comment.scientific_notation_list.len(), 2);
         let reply = comment.get_reply();
-        assert_eq!(reply, "If I post the whole numbers, the comment would get too long, as reddit only allows up to 10k characters.\n\nIn scientific notation the results would look roughly like that:\n\nDouble-Factorial of 5 = 6.0e1,\n\nFactorial of 6 = 7.20e2,\n\nFactorial of 3249 = 6.4123376882765521838840963030568127691878727205333658692200854486404915724268122521695176119279253636e10000\n\n:)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+        assert!(reply.contains(&format!(
+            "Factorial of {} has approximately 10^10^20.27 digits",
+            exponent_beyond_u64.trim_end_matches('!')
+        )));
+        assert!(reply.contains(&format!(
+            "Factorial of {} is too large to even describe",
+            exponent_beyond_f64.trim_end_matches('!')
+        )));
     }
 
     #[test]
-    fn test_get_reply_too_long_from_new_comment() {
-        let comment =
-            RedditComment::new("This is a test comment with a factorial of 4000!", "1234");
-
-        let reply = comment.get_reply();
-        assert_eq!(reply, "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the factorial of 4000 would be (roughly) 1.8288019515140650133147431755739190442173777107304392197064526954208959797973177364850370286870484107e12673 though :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+    fn test_comment_new_scientific_notation_not_factorial() {
+        let comment = RedditComment::new("1.25e1! and 0e5! and e5! and 1e5", "123");
+        assert_eq!(
+            comment.factorial_list,
+            vec![Factorial {
+                number: 0,
+                level: 1,
+                factorial: 1.to_bigint().unwrap(),
+            }]
+        );
+        assert!(comment.scientific_notation_list.is_empty());
     }
 
     #[test]
-    fn test_get_reply_too_long_from_new_comment_for_multifactorial() {
-        let comment =
-            RedditComment::new("This is a test comment with a factorial of 9000!!!", "1234");
+    fn test_get_reply_printable_results_adding_up_too_long() {
+        let comment = RedditComment::new("3000! and 3100!", "123");
+        assert!(!comment.status.contains(&Status::ReplyWouldBeTooLong));
 
         let reply = comment.get_reply();
-        assert_eq!(reply, "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the Triple-factorial of 9000 would be (roughly) 9.5883799146548267640341391648545903348878025438772769707015576436531779580675303393957674423348854753e10561 though :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+        assert!(reply.len() <= 10_000);
+        assert!(reply.starts_with(TOO_LONG_HEADER));
+    }
+
+    /// Something the parser picks up, from tiny to far beyond the calculation limit.
+    fn calculation() -> impl Strategy<Value = String> {
+        prop_oneof![
+            (0u64..200, 1usize..4).prop_map(|(n, k)| format!("{n}{}", "!".repeat(k))),
+            (0u64..=12_000).prop_map(|n| format!("{n}!")),
+            (100_000u64..200_000).prop_map(|n| format!("{n}!")),
+            (0u64..600).prop_map(|n| format!("H({n}) sf({n})")),
+            (0u64..30_000).prop_map(|n| format!("{n}# af({})", n / 5)),
+            (0u64..8).prop_map(|n| format!("ef({n})")),
+            (0u64..5_000, 0u64..5_000).prop_map(|(n, k)| format!("C({n},{k})")),
+            (0u64..20_000, 0u64..20_000).prop_map(|(n, k)| format!("{n}!/{k}!")),
+            (2u64..10, 2u64..8).prop_map(|(a, b)| format!("{a}^^{b}")),
+            (0u64..100_000, 1u64..1_000_000).prop_map(|(n, m)| format!("{n}! mod {m}")),
+            (1u64..400).prop_map(|e| format!("1e{e}!")),
+            (0u64..50, 0u64..50).prop_map(|(a, b)| format!("({a}*{b})!")),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(24))]
+
+        #[test]
+        fn test_reply_never_exceeds_comment_length(
+            calculations in prop::collection::vec(calculation(), 1..8),
+            command_bits in 0u32..(1 << Commands::NAMES.len()),
+            footer_length in 0..=MAX_FOOTER_LENGTH,
+            max_chain_length in 1usize..4,
+        ) {
+            let commands = Commands::from_bits(command_bits).unwrap();
+            let comment = RedditComment::try_new_with_settings(
+                &calculations.join(" and "),
+                "123",
+                Strictness::Lenient,
+                commands,
+                &[],
+                DEFAULT_MAX_JOBS_PER_COMMENT,
+                DEFAULT_MAX_DECIMALS,
+            )
+            .unwrap();
+            let footer = "f".repeat(footer_length);
+
+            let replies = comment.get_reply_chain_with_footer(&footer, max_chain_length);
+            prop_assert!(!replies.is_empty() && replies.len() <= max_chain_length);
+            for reply in replies {
+                prop_assert!(reply.len() <= 10_000, "reply has {} characters", reply.len());
+                prop_assert!(reply.ends_with(&footer));
+            }
+        }
+    }
+
+    /// Text around calculations, including what the parser treats specially.
+    fn filler() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "[a-zA-Z ,.?]{0,20}",
+            Just("> ".to_string()),
+            Just("\n\n".to_string()),
+            Just("`".to_string()),
+            Just("$".to_string()),
+            Just("Catch-".to_string()),
+            Just("\\frac{".to_string()),
+            Just("(".to_string()),
+            Just(")".to_string()),
+            Just("[short]".to_string()),
+            Just("!precision 5".to_string()),
+            "\\PC{0,5}",
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn test_parser_never_panics_on_arbitrary_text(
+            body in "\\PC{0,200}",
+            strictness in prop_oneof![
+                Just(Strictness::Lenient),
+                Just(Strictness::Normal),
+                Just(Strictness::Strict)
+            ],
+            command_bits in 0u32..(1 << Commands::NAMES.len()),
+        ) {
+            let commands = Commands::from_bits(command_bits).unwrap();
+            let _ = RedditComment::try_new_with_settings(
+                &body,
+                "123",
+                strictness,
+                commands,
+                &[],
+                DEFAULT_MAX_JOBS_PER_COMMENT,
+                DEFAULT_MAX_DECIMALS,
+            );
+        }
+
+        #[test]
+        fn test_parser_never_panics_on_mixed_text(
+            parts in prop::collection::vec(prop_oneof![calculation(), filler()], 0..12),
+        ) {
+            let body = parts.concat();
+            if let Ok(comment) = RedditComment::try_new_with_settings(
+                &body,
+                "123",
+                Strictness::Normal,
+                Commands::NONE,
+                &[],
+                DEFAULT_MAX_JOBS_PER_COMMENT,
+                DEFAULT_MAX_DECIMALS,
+            ) {
+                let _ = comment.get_reply();
+            }
+        }
+
+        #[test]
+        fn test_multifactorials_keep_their_level(number in 0u64..3_000, level in 1usize..10) {
+            let comment = RedditComment::new(&format!("{number}{}", "!".repeat(level)), "123");
+            prop_assert_eq!(
+                comment
+                    .factorial_list
+                    .iter()
+                    .map(|factorial| (factorial.number, factorial.level))
+                    .collect::<Vec<_>>(),
+                vec![(number, level as i64)]
+            );
+        }
+
+        #[test]
+        fn test_parsing_found_expressions_again_is_stable(
+            calculations in prop::collection::vec(calculation(), 1..6),
+        ) {
+            let comment = RedditComment::new(&calculations.join(" and "), "123");
+            let reparsed = RedditComment::new(&comment.sources().join(" and "), "123");
+            prop_assert_eq!(reparsed.sources(), comment.sources());
+            prop_assert_eq!(&reparsed.factorial_list, &comment.factorial_list);
+            prop_assert_eq!(reparsed.get_reply(), comment.get_reply());
+        }
+    }
+
+    const GOLDEN_REPLIES_FILE_PATH: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/replies.txt");
+
+    /// Splits a golden file into its description and the pairs of comment and reply.
+    fn parse_golden(golden: &str) -> (&str, Vec<(&str, &str)>) {
+        let mut cases = golden.split("=== comment\n");
+        let description = cases.next().unwrap_or_default();
+        let cases = cases
+            .map(|case| {
+                let (comment, reply) = case
+                    .split_once("\n=== reply\n")
+                    .expect("Golden case without reply");
+                (comment, reply.strip_suffix('\n').unwrap_or(reply))
+            })
+            .collect();
+        (description, cases)
     }
 
     #[test]
-    fn test_get_reply_too_long_from_number_3250() {
-        let comment =
-            RedditComment::new("This is a test comment with a factorial of 3250!", "1234");
+    fn test_golden_replies() {
+        let golden = std::fs::read_to_string(GOLDEN_REPLIES_FILE_PATH).unwrap();
+        let (description, cases) = parse_golden(&golden);
+        let replies: Vec<(&str, String)> = cases
+            .iter()
+            .map(|(comment, _)| (*comment, RedditComment::new(comment, "golden").get_reply()))
+            .collect();
 
-        let reply = comment.get_reply();
-        assert_eq!(reply, "If I post the whole number, the comment would get too long, as reddit only allows up to 10k characters.\n\n In scientific notation the factorial of 3250 would be (roughly) 2.0840097486898794597623312984934641499860586341733439074965277708081597610387139819550932238765757432e10004 though :)\n\n\n*^(This action was performed by a bot. Please DM me if you have any questions.)*");
+        if std::env::var("UPDATE_GOLDEN").is_ok() {
+            let mut updated = description.to_string();
+            for (comment, reply) in &replies {
+                updated.push_str(&format!("=== comment\n{comment}\n=== reply\n{reply}\n"));
+            }
+            std::fs::write(GOLDEN_REPLIES_FILE_PATH, updated).unwrap();
+            return;
+        }
+        for ((comment, expected), (_, reply)) in cases.iter().zip(&replies) {
+            assert_eq!(
+                reply, expected,
+                "Reply to {comment:?} changed, see {GOLDEN_REPLIES_FILE_PATH}"
+            );
+        }
     }
 }
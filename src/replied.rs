@@ -0,0 +1,84 @@
+use crate::error::FactorionError;
+use std::collections::HashSet;
+use std::fs;
+
+pub(crate) const COMMENT_IDS_FILE_PATH: &str = "comment_ids.txt";
+
+/// The ids of the comments the bot answered and of its own replies, so nothing is answered twice.
+/// Kept in a set for constant-time lookups, and in the order they were added for the file, one per line.
+#[derive(Debug, Default)]
+pub(crate) struct RepliedIds {
+    ids: HashSet<String>,
+    order: Vec<String>,
+}
+
+impl RepliedIds {
+    /// Reads the ids, starting without any if there is no file yet.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        match fs::read_to_string(COMMENT_IDS_FILE_PATH) {
+            Ok(file) => Ok(Self::parse(&file)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(FactorionError::Io {
+                path: COMMENT_IDS_FILE_PATH,
+                source,
+            }),
+        }
+    }
+
+    fn parse(file: &str) -> Self {
+        let mut replied = Self::default();
+        replied.extend(
+            file.lines()
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string),
+        );
+        replied
+    }
+
+    pub(crate) fn store(&self) -> Result<(), FactorionError> {
+        let file: String = self.order.iter().map(|id| format!("{id}\n")).collect();
+        fs::write(COMMENT_IDS_FILE_PATH, file).map_err(|source| FactorionError::Io {
+            path: COMMENT_IDS_FILE_PATH,
+            source,
+        })
+    }
+
+    pub(crate) fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+
+    pub(crate) fn push(&mut self, id: String) {
+        if self.ids.insert(id.clone()) {
+            self.order.push(id);
+        }
+    }
+
+    pub(crate) fn extend(&mut self, ids: impl IntoIterator<Item = String>) {
+        for id in ids {
+            self.push(id);
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replied_ids() {
+        let mut replied = RepliedIds::parse("abc\n\ndef\nabc\n");
+        assert_eq!(replied.len(), 2);
+        assert!(replied.contains("abc"));
+        assert!(!replied.contains("ghi"));
+
+        replied.push("ghi".to_string());
+        replied.extend(["def".to_string(), "jkl".to_string()]);
+        assert_eq!(replied.order, vec!["abc", "def", "ghi", "jkl"]);
+        assert!(replied.contains("jkl"));
+    }
+}
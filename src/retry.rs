@@ -0,0 +1,144 @@
+use crate::error::FactorionError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+pub(crate) const RETRY_QUEUE_FILE_PATH: &str = "retry_queue.json";
+/// Seconds to wait before the first retry, doubled with every further one.
+const RETRY_BASE_DELAY: u64 = 60;
+/// Replies are dropped after failing this many times.
+const MAX_RETRY_ATTEMPTS: u32 = 6;
+
+/// A reply that failed for a reason that might go away, like a rate limit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PendingReply {
+    /// The comment to answer, or the last posted reply if the chain failed partway.
+    pub(crate) comment_id: String,
+    pub(crate) subreddit: String,
+    /// The reply chain, as it would have been posted.
+    pub(crate) replies: Vec<String>,
+    /// How often posting failed so far.
+    pub(crate) attempts: u32,
+    /// Unix time in seconds, before which the reply isn't tried again.
+    pub(crate) next_attempt: u64,
+}
+
+/// Failed replies waiting to be posted again, with exponential backoff.
+/// Stored as JSON, so they survive restarts.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RetryQueue {
+    pending: Vec<PendingReply>,
+}
+
+impl RetryQueue {
+    /// Reads the queue, starting with an empty one if there is no file yet.
+    pub(crate) fn load() -> Result<Self, FactorionError> {
+        match fs::read_to_string(RETRY_QUEUE_FILE_PATH) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(FactorionError::Io {
+                path: RETRY_QUEUE_FILE_PATH,
+                source,
+            }),
+        }
+    }
+
+    pub(crate) fn store(&self) -> Result<(), FactorionError> {
+        fs::write(RETRY_QUEUE_FILE_PATH, serde_json::to_string(self)?).map_err(|source| {
+            FactorionError::Io {
+                path: RETRY_QUEUE_FILE_PATH,
+                source,
+            }
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Queues a reply that failed for the first time.
+    pub(crate) fn push(
+        &mut self,
+        comment_id: String,
+        subreddit: String,
+        replies: Vec<String>,
+        now: u64,
+    ) {
+        self.retry_later(
+            PendingReply {
+                comment_id,
+                subreddit,
+                replies,
+                attempts: 0,
+                next_attempt: now,
+            },
+            now,
+        );
+    }
+
+    /// Takes the replies out of the queue that are due to be tried again.
+    pub(crate) fn take_due(&mut self, now: u64) -> Vec<PendingReply> {
+        let (due, waiting) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|pending| pending.next_attempt <= now);
+        self.pending = waiting;
+        due
+    }
+
+    /// Queues a reply again after it failed once more, waiting twice as long as before.
+    /// Returns `false` if it failed too often and was dropped.
+    pub(crate) fn retry_later(&mut self, mut pending: PendingReply, now: u64) -> bool {
+        if pending.attempts >= MAX_RETRY_ATTEMPTS {
+            return false;
+        }
+        pending.next_attempt = now + RETRY_BASE_DELAY * 2u64.pow(pending.attempts);
+        pending.attempts += 1;
+        self.pending.push(pending);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_queue_backoff() {
+        let mut queue = RetryQueue::default();
+        queue.push(
+            "abc".to_string(),
+            "mathmemes".to_string(),
+            vec!["5! = 120".to_string()],
+            1000,
+        );
+        assert_eq!(queue.len(), 1);
+        assert!(queue.take_due(1059).is_empty());
+
+        let mut pending = queue.take_due(1060).pop().unwrap();
+        assert_eq!(pending.attempts, 1);
+        assert_eq!(queue.len(), 0);
+
+        let mut now = 1060;
+        for expected_delay in [120, 240, 480, 960, 1920] {
+            assert!(queue.retry_later(pending, now));
+            assert!(queue.take_due(now + expected_delay - 1).is_empty());
+            now += expected_delay;
+            pending = queue.take_due(now).pop().unwrap();
+        }
+        assert_eq!(pending.attempts, MAX_RETRY_ATTEMPTS);
+        assert!(!queue.retry_later(pending, now));
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_retry_queue_roundtrip() {
+        let mut queue = RetryQueue::default();
+        queue.push(
+            "abc".to_string(),
+            "mathmemes".to_string(),
+            vec!["5! = 120".to_string()],
+            0,
+        );
+        let json = serde_json::to_string(&queue).unwrap();
+        assert_eq!(serde_json::from_str::<RetryQueue>(&json).unwrap(), queue);
+    }
+}
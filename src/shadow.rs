@@ -0,0 +1,163 @@
+use crate::error::FactorionError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+pub(crate) const SHADOW_REPLIES_FILE_PATH: &str = "shadow_replies.jsonl";
+
+/// A reply the bot would have posted in a subreddit in shadow mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ShadowReply {
+    pub(crate) comment_id: String,
+    pub(crate) subreddit: String,
+    pub(crate) reply: String,
+}
+
+/// Subreddits in which the bot only stores its replies instead of posting them,
+/// so moderators can review them before enabling the bot.
+/// With `*`, nothing is posted at all, for a dry run of parser changes against live comments.
+#[derive(Debug, Default)]
+pub(crate) struct ShadowMode {
+    subreddits: HashSet<String>,
+    everywhere: bool,
+}
+
+impl ShadowMode {
+    /// Parses a comma separated list of subreddits, or `*` for all. Subreddit names are stored in lowercase.
+    pub(crate) fn new(subreddits: &str) -> Self {
+        let subreddits: HashSet<String> = subreddits
+            .split(',')
+            .map(|subreddit| subreddit.trim().to_lowercase())
+            .filter(|subreddit| !subreddit.is_empty())
+            .collect();
+        Self {
+            everywhere: subreddits.contains("*"),
+            subreddits,
+        }
+    }
+
+    pub(crate) fn is_shadowed(&self, subreddit: &str) -> bool {
+        self.everywhere || self.subreddits.contains(&subreddit.to_lowercase())
+    }
+
+    /// Appends the reply to the shadow replies file, one JSON object per line.
+    pub(crate) fn store(&self, reply: &ShadowReply) -> Result<(), FactorionError> {
+        let io_error = |source| FactorionError::Io {
+            path: SHADOW_REPLIES_FILE_PATH,
+            source,
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(SHADOW_REPLIES_FILE_PATH)
+            .map_err(io_error)?;
+        writeln!(file, "{}", serde_json::to_string(reply)?).map_err(io_error)
+    }
+}
+
+/// Summarizes the stored shadow replies per subreddit, for the `shadow-report` command.
+pub(crate) fn report(shadow_replies: &str) -> Result<String, FactorionError> {
+    let mut per_subreddit: BTreeMap<String, Vec<ShadowReply>> = BTreeMap::new();
+    for line in shadow_replies
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+    {
+        let reply: ShadowReply = serde_json::from_str(line)?;
+        per_subreddit
+            .entry(reply.subreddit.clone())
+            .or_default()
+            .push(reply);
+    }
+
+    if per_subreddit.is_empty() {
+        return Ok("No shadow replies stored".to_string());
+    }
+
+    Ok(per_subreddit
+        .iter()
+        .map(|(subreddit, replies)| {
+            let average_length =
+                replies.iter().map(|reply| reply.reply.len()).sum::<usize>() / replies.len();
+            let comments = replies
+                .iter()
+                .map(|reply| reply.comment_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "r/{subreddit}: {} replies, {average_length} characters on average (comments: {comments})",
+                replies.len()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Prints the report of the shadow replies file.
+pub(crate) fn print_report() -> Result<(), FactorionError> {
+    let shadow_replies = match fs::read_to_string(SHADOW_REPLIES_FILE_PATH) {
+        Ok(shadow_replies) => shadow_replies,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(source) => {
+            return Err(FactorionError::Io {
+                path: SHADOW_REPLIES_FILE_PATH,
+                source,
+            })
+        }
+    };
+    println!("{}", report(&shadow_replies)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadow_mode_subreddits() {
+        let shadow_mode = ShadowMode::new("MathMemes, askmath,");
+        assert!(shadow_mode.is_shadowed("mathmemes"));
+        assert!(shadow_mode.is_shadowed("AskMath"));
+        assert!(!shadow_mode.is_shadowed("math"));
+        assert!(!ShadowMode::new("").is_shadowed(""));
+
+        let dry_run = ShadowMode::new("mathmemes, *");
+        assert!(dry_run.is_shadowed("math"));
+        assert!(dry_run.is_shadowed(""));
+    }
+
+    #[test]
+    fn test_shadow_report() {
+        assert_eq!(report("").unwrap(), "No shadow replies stored");
+
+        let replies = [
+            ShadowReply {
+                comment_id: "a1".to_string(),
+                subreddit: "mathmemes".to_string(),
+                reply: "1234".to_string(),
+            },
+            ShadowReply {
+                comment_id: "b2".to_string(),
+                subreddit: "askmath".to_string(),
+                reply: "12".to_string(),
+            },
+            ShadowReply {
+                comment_id: "c3".to_string(),
+                subreddit: "mathmemes".to_string(),
+                reply: "12".to_string(),
+            },
+        ];
+        let shadow_replies = replies
+            .iter()
+            .map(|reply| serde_json::to_string(reply).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(
+            report(&shadow_replies).unwrap(),
+            "r/askmath: 1 replies, 2 characters on average (comments: b2)\n\
+             r/mathmemes: 2 replies, 3 characters on average (comments: a1, c3)"
+        );
+        assert!(report("not json").is_err());
+    }
+}